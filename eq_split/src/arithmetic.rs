@@ -0,0 +1,501 @@
+use std::{
+    cmp::Ordering,
+    ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+use bigdecimal::{num_bigint::BigInt, BigDecimal, One, RoundingMode, Signed, ToPrimitive, Zero};
+use num_complex::Complex64;
+use num_rational::BigRational;
+
+use crate::{
+    numbers::{parse_decimal_literal, parse_number_literal},
+    operators::factorial,
+};
+
+/// the factorial of whole numbers larger than this is not calculated exactly
+pub const MAX_FACTORIAL: u64 = 10_000;
+
+/// whole exponents of fractions larger than this are calculated with f64
+const MAX_EXPONENT: u32 = 100_000;
+
+/// the roots of fractions larger than this are calculated with f64 eg. 2^(1/1000)
+const MAX_ROOT: u32 = 64;
+
+/// the digits of the constants for the decimals, more digits than this are not known
+const PI_DIGITS: &str = "3.14159265358979323846264338327950288419716939937510582097494459230781640628620899862803482534211706798214808651328230664709384460955058223172535940812848111745028410270193852110555964462294895493038196";
+const E_DIGITS: &str = "2.71828182845904523536028747135266249775724709369995957496696762772407663035354759457138217852516642742746639193200305992181741359662904357290033429526059563073813232862794349076323382988075319525101901";
+
+/// the product of the whole numbers from 1 to 'value'
+/// None if 'value' is negative or larger than 'MAX_FACTORIAL'
+pub fn integer_factorial(value: &BigInt) -> Option<BigInt> {
+    let n = value.to_u64().filter(|n| *n <= MAX_FACTORIAL)?;
+    Some((2..=n).fold(BigInt::one(), |product, i| product * i))
+}
+
+/// a number type that an equation can be calculated with eg. f64, f32, fractions, complex numbers
+/// implement it to calculate with the number type of another crate eg. dual numbers, fixed-point
+/// an operation gives None when it has no result in the number type eg. 1/0 or 2^0.5 for fractions
+pub trait Number:
+    Clone + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self>
+{
+    /// the number of a whole number eg. 1 for true and 0 for false
+    fn from_whole(value: i64) -> Self;
+
+    /// the number closest to the f64, None if there is none eg. NaN for fractions
+    fn from_float(value: f64) -> Option<Self>;
+
+    /// the f64 closest to the number, used for the bitwise operators
+    /// and for the operations the number type does not have
+    fn to_float(&self) -> f64;
+
+    fn checked_div(self, other: Self) -> Option<Self>;
+
+    /// the remainder with the sign of the dividend eg. -7 rem 3 = -1
+    fn checked_rem(self, other: Self) -> Option<Self>;
+
+    fn checked_pow(self, exponent: Self) -> Option<Self>;
+
+    /// the largest whole number that is not larger than the number
+    fn checked_floor(self) -> Option<Self>;
+
+    /// None when the numbers cannot be ordered eg. NaN or complex numbers that are not equal
+    fn compare(&self, other: &Self) -> Option<Ordering>;
+
+    /// parse a number literal eg. 0.1, 6.02e-23, 0xFF
+    fn parse_literal(literal: &str) -> Option<Self> {
+        let literal = literal.chars().collect::<Vec<char>>();
+        Self::from_float(parse_number_literal(&literal)?)
+    }
+
+    /// the number with the other sign eg. -x
+    fn negate(self) -> Self {
+        -self
+    }
+
+    /// the factorial extended to the numbers that are not whole with the gamma function
+    fn factorial(self) -> Option<Self> {
+        Self::from_float(factorial(self.to_float()))
+    }
+
+    fn pi() -> Option<Self> {
+        Self::from_float(std::f64::consts::PI)
+    }
+
+    fn e() -> Option<Self> {
+        Self::from_float(std::f64::consts::E)
+    }
+
+    /// the imaginary unit i, None for the real number types
+    fn imaginary_unit() -> Option<Self> {
+        None
+    }
+
+    /// the builtin function 'name' calculated with the number type eg. sqrt, max
+    /// None to calculate it with f64 instead
+    fn call_function(_name: &str, _args: &[Self]) -> Option<Self> {
+        None
+    }
+}
+
+impl Number for f64 {
+    fn from_whole(value: i64) -> Self {
+        value as f64
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        Some(value)
+    }
+
+    fn to_float(&self) -> f64 {
+        *self
+    }
+
+    fn checked_div(self, other: Self) -> Option<Self> {
+        Some(self / other)
+    }
+
+    fn checked_rem(self, other: Self) -> Option<Self> {
+        Some(self % other)
+    }
+
+    fn checked_pow(self, exponent: Self) -> Option<Self> {
+        Some(self.powf(exponent))
+    }
+
+    fn checked_floor(self) -> Option<Self> {
+        Some(self.floor())
+    }
+
+    fn compare(&self, other: &Self) -> Option<Ordering> {
+        self.partial_cmp(other)
+    }
+}
+
+impl Number for f32 {
+    fn from_whole(value: i64) -> Self {
+        value as f32
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        Some(value as f32)
+    }
+
+    fn to_float(&self) -> f64 {
+        *self as f64
+    }
+
+    fn checked_div(self, other: Self) -> Option<Self> {
+        Some(self / other)
+    }
+
+    fn checked_rem(self, other: Self) -> Option<Self> {
+        Some(self % other)
+    }
+
+    fn checked_pow(self, exponent: Self) -> Option<Self> {
+        Some(self.powf(exponent))
+    }
+
+    fn checked_floor(self) -> Option<Self> {
+        Some(self.floor())
+    }
+
+    fn compare(&self, other: &Self) -> Option<Ordering> {
+        self.partial_cmp(other)
+    }
+}
+
+impl Number for BigRational {
+    fn from_whole(value: i64) -> Self {
+        Self::from_integer(BigInt::from(value))
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        BigRational::from_float(value)
+    }
+
+    fn to_float(&self) -> f64 {
+        self.to_f64().unwrap_or(f64::NAN)
+    }
+
+    fn checked_div(self, other: Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        Some(self / other)
+    }
+
+    fn checked_rem(self, other: Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        let quotient = (&self / &other).trunc();
+        Some(self - other * quotient)
+    }
+
+    /// a root is exact when the result is a fraction eg. 4^0.5 = 2, (8/27)^(1/3) = 2/3
+    fn checked_pow(self, exponent: Self) -> Option<Self> {
+        let power = exponent
+            .numer()
+            .to_i32()
+            .filter(|power| power.unsigned_abs() <= MAX_EXPONENT)?;
+        let root = exponent.denom().to_u32().filter(|root| *root <= MAX_ROOT)?;
+        let base = exact_root(&self, root)?;
+        if base.is_zero() && power < 0 {
+            return None;
+        }
+        Some(base.pow(power))
+    }
+
+    fn checked_floor(self) -> Option<Self> {
+        Some(self.floor())
+    }
+
+    fn compare(&self, other: &Self) -> Option<Ordering> {
+        self.partial_cmp(other)
+    }
+
+    /// parse a number literal straight into a fraction eg. 0.1 = 1/10, 2.5e-3 = 1/400
+    /// None when the power of 10 is too large to be calculated eg. 1e99999999999
+    fn parse_literal(literal: &str) -> Option<Self> {
+        let (digits, scale) = parse_decimal_literal(literal)?.into_bigint_and_exponent();
+        let exponent = u32::try_from(scale.unsigned_abs())
+            .ok()
+            .filter(|exponent| *exponent <= MAX_EXPONENT)?;
+        let power = BigInt::from(10).pow(exponent);
+        if scale < 0 {
+            Some(Self::from_integer(digits * power))
+        } else {
+            Some(Self::new(digits, power))
+        }
+    }
+
+    /// pi has no fraction
+    fn pi() -> Option<Self> {
+        None
+    }
+
+    /// e has no fraction
+    fn e() -> Option<Self> {
+        None
+    }
+
+    /// the factorial of a whole fraction is exact eg. 20! = 2432902008176640000
+    fn factorial(self) -> Option<Self> {
+        match integer_factorial(&self.to_integer()) {
+            Some(product) if self.is_integer() => Some(Self::from_integer(product)),
+            _ => Self::from_float(factorial(self.to_float())),
+        }
+    }
+
+    /// the functions with an exact result eg. abs, floor, max
+    fn call_function(name: &str, args: &[Self]) -> Option<Self> {
+        let result = match (name, args) {
+            ("abs", [value]) => value.abs(),
+            ("floor", [value]) => value.floor(),
+            ("ceil", [value]) => value.ceil(),
+            ("round", [value]) => value.round(),
+            ("min", [left, right]) => left.min(right).clone(),
+            ("max", [left, right]) => left.max(right).clone(),
+            _ => return None,
+        };
+        Some(result)
+    }
+}
+
+/// the decimals are divided with 100 significant digits
+impl Number for BigDecimal {
+    fn from_whole(value: i64) -> Self {
+        Self::from(value)
+    }
+
+    /// the f64 written as a decimal with the shortest digits that give it back eg. 0.1
+    fn from_float(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        Self::from_str(&value.to_string()).ok()
+    }
+
+    fn to_float(&self) -> f64 {
+        self.to_f64().unwrap_or(f64::NAN)
+    }
+
+    fn checked_div(self, other: Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        Some(self / other)
+    }
+
+    fn checked_rem(self, other: Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        Some(self % other)
+    }
+
+    /// an exponent that is not whole is calculated with f64
+    fn checked_pow(self, exponent: Self) -> Option<Self> {
+        match exponent.to_i64().filter(|_| exponent.is_integer()) {
+            Some(exponent) if self.is_zero() && exponent < 0 => None,
+            Some(exponent) => Some(self.powi(exponent)),
+            None => Self::from_float(self.to_float().powf(exponent.to_float())),
+        }
+    }
+
+    fn checked_floor(self) -> Option<Self> {
+        Some(self.with_scale_round(0, RoundingMode::Floor))
+    }
+
+    fn compare(&self, other: &Self) -> Option<Ordering> {
+        self.partial_cmp(other)
+    }
+
+    fn parse_literal(literal: &str) -> Option<Self> {
+        parse_decimal_literal(literal)
+    }
+
+    /// pi to 200 digits
+    fn pi() -> Option<Self> {
+        Self::from_str(PI_DIGITS).ok()
+    }
+
+    /// e to 200 digits
+    fn e() -> Option<Self> {
+        Self::from_str(E_DIGITS).ok()
+    }
+}
+
+/// the operations other than + - * / ^ need real numbers
+impl Number for Complex64 {
+    fn from_whole(value: i64) -> Self {
+        Self::from(value as f64)
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        Some(Self::from(value))
+    }
+
+    /// NaN when the number is not real
+    fn to_float(&self) -> f64 {
+        if self.im == 0.0 {
+            self.re
+        } else {
+            f64::NAN
+        }
+    }
+
+    fn checked_div(self, other: Self) -> Option<Self> {
+        Some(self / other)
+    }
+
+    // 0 - value keeps the imaginary part of a real number at +0
+    // so that it stays above the branch cut eg. sqrt(-4) = 2i instead of -2i
+    fn negate(self) -> Self {
+        Self::from(0.0) - self
+    }
+
+    fn checked_rem(self, other: Self) -> Option<Self> {
+        let (left, right) = (as_real(self)?, as_real(other)?);
+        Some(Self::from(left % right))
+    }
+
+    /// the principal value eg. (-8)^(1/3) = 1+1.7320508075688772i
+    /// whole exponents are calculated by multiplying so that i^2 = -1
+    fn checked_pow(self, exponent: Self) -> Option<Self> {
+        let is_whole = exponent.im == 0.0 && exponent.re.fract() == 0.0;
+        if is_whole && exponent.re.abs() <= i32::MAX as f64 {
+            return Some(self.powi(exponent.re as i32));
+        }
+
+        if self == Self::from(0.0) && exponent.re > 0.0 {
+            return Some(self);
+        }
+        Some(self.powc(exponent))
+    }
+
+    fn checked_floor(self) -> Option<Self> {
+        as_real(self).map(|value| Self::from(value.floor()))
+    }
+
+    fn compare(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            return Some(Ordering::Equal);
+        }
+        as_real(*self)?.partial_cmp(&as_real(*other)?)
+    }
+
+    fn imaginary_unit() -> Option<Self> {
+        Some(Self::i())
+    }
+
+    /// the principal values eg. sqrt(-4) = 2i, ln(-1) = pi*i
+    fn call_function(name: &str, args: &[Self]) -> Option<Self> {
+        let [value] = args else {
+            return None;
+        };
+
+        let result = match name {
+            "sin" => value.sin(),
+            "cos" => value.cos(),
+            "tan" => value.tan(),
+            "asin" => value.asin(),
+            "acos" => value.acos(),
+            "atan" => value.atan(),
+            "sinh" => value.sinh(),
+            "cosh" => value.cosh(),
+            "tanh" => value.tanh(),
+            "sqrt" => value.sqrt(),
+            "cbrt" => value.cbrt(),
+            "ln" => value.ln(),
+            "log" => value.log10(),
+            "log2" => value.log2(),
+            "exp" => value.exp(),
+            "abs" => Self::from(value.norm()),
+            "re" => Self::from(value.re),
+            "im" => Self::from(value.im),
+            "arg" => Self::from(value.arg()),
+            "conj" => value.conj(),
+            _ => return None,
+        };
+        Some(result)
+    }
+}
+
+/// the 'root'-th root of 'value' when it is a fraction eg. the 3rd root of 8/27 is 2/3
+fn exact_root(value: &BigRational, root: u32) -> Option<BigRational> {
+    if value.is_negative() && root.is_multiple_of(2) {
+        return None;
+    }
+
+    let numer = value.numer().nth_root(root);
+    let denom = value.denom().nth_root(root);
+    let result = BigRational::new(numer, denom);
+    if result.pow(root as i32) == *value {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+fn as_real(value: Complex64) -> Option<f64> {
+    if value.im == 0.0 {
+        Some(value.re)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operators::{Operators, PostfixOperators};
+
+    #[test]
+    pub fn test_calculate_number() {
+        let q = |numer: i64, denom: i64| BigRational::new(numer.into(), denom.into());
+
+        let cases = [
+            (Operators::Div, q(1, 3), q(1, 6), Some(q(2, 1))),
+            (Operators::Div, q(1, 3), q(0, 1), None),
+            (Operators::Modulo, q(-7, 2), q(3, 1), Some(q(5, 2))),
+            (Operators::Rem, q(-7, 1), q(3, 1), Some(q(-1, 1))),
+            (Operators::IntDiv, q(-7, 1), q(2, 1), Some(q(-4, 1))),
+            (Operators::Exp, q(8, 27), q(1, 3), Some(q(2, 3))),
+            (Operators::Exp, q(2, 1), q(1, 2), None),
+            (Operators::BitOr, q(6, 1), q(8, 1), Some(q(14, 1))),
+            (Operators::Less, q(1, 3), q(1, 2), Some(q(1, 1))),
+        ];
+        for (operator, left, right, result) in cases {
+            assert_eq!(operator.calculate(left, right), result, "{:?}", operator);
+        }
+
+        assert_eq!(Operators::Modulo.calculate(-7.0, 3.0), Some(2.0));
+        assert_eq!(Operators::Equal.calculate(f64::NAN, f64::NAN), Some(0.0));
+        assert_eq!(Operators::Exp.calculate(2.0_f32, 10.0), Some(1024.0));
+        assert_eq!(
+            PostfixOperators::Factorial.calculate(q(20, 1)),
+            Some(q(2432902008176640000, 1))
+        );
+
+        let i = Complex64::i();
+        assert_eq!(
+            Operators::Exp.calculate(i, Complex64::from(2.0)),
+            Some(-Complex64::from(1.0))
+        );
+        assert_eq!(
+            Operators::Less.calculate(i, Complex64::from(2.0)),
+            Some(Complex64::from(0.0))
+        );
+        assert_eq!(Operators::Modulo.calculate(i, Complex64::from(2.0)), None);
+
+        let decimal = |value: &str| BigDecimal::from_str(value).unwrap();
+        assert_eq!(
+            Operators::Plus.calculate(decimal("0.1"), decimal("0.2")),
+            Some(decimal("0.3"))
+        );
+        assert_eq!(Operators::Div.calculate(decimal("1"), decimal("0")), None);
+    }
+}
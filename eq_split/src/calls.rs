@@ -0,0 +1,107 @@
+use crate::{identifiers::is_identifier, EquationString};
+
+pub trait SplitCall {
+    fn split_call(&self) -> Option<(String, Vec<(Self, usize)>)>
+    where
+        Self: Sized;
+}
+
+impl SplitCall for EquationString {
+    /// split a function call into its name and its arguments eg. max(1,2) -> max, [1, 2]
+    /// each argument comes with its position in the eq
+    /// returns None if the eq is not a single function call
+    /// the parentheses are assumed to be balanced
+    fn split_call(&self) -> Option<(String, Vec<(Self, usize)>)> {
+        let open_index = self.iter().position(|c| *c == '(')?;
+        if !is_identifier(&self[..open_index]) || self.last() != Some(&')') {
+            return None;
+        }
+
+        let mut arguments: Vec<(Self, usize)> = Vec::new();
+        let mut opened_parentheses = 0;
+        let mut argument_start = open_index + 1;
+
+        for (i, c) in self.iter().enumerate().skip(open_index + 1) {
+            match c {
+                '(' => opened_parentheses += 1,
+                ')' if opened_parentheses == 0 && i != self.len() - 1 => {
+                    // the call ends before the end of the eq eg. f(1)+(2)
+                    return None;
+                }
+                ')' if opened_parentheses == 0 => {}
+                ')' => opened_parentheses -= 1,
+                ',' if opened_parentheses == 0 => {
+                    arguments.push((self[argument_start..i].to_vec(), argument_start));
+                    argument_start = i + 1;
+                }
+                _ => {}
+            }
+        }
+
+        let last_index = self.len() - 1;
+        if argument_start != last_index || !arguments.is_empty() {
+            // f() has no arguments, but f(1,) has an empty second argument
+            arguments.push((self[argument_start..last_index].to_vec(), argument_start));
+        }
+
+        let name = self[..open_index].iter().collect::<String>();
+        Some((name, arguments))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::eq_sanitize::EqSanitize;
+
+    use super::*;
+
+    #[test]
+    pub fn test_split_call() {
+        let eq = "max(1,(2+3)*4)";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (name, arguments) = eq.split_call().unwrap();
+        assert_eq!(name, "max");
+        assert_eq!(arguments.len(), 2);
+        assert_eq!(arguments[0].0.clone().to_string(), "1");
+        assert_eq!(arguments[0].1, 4);
+        assert_eq!(arguments[1].0.clone().to_string(), "(2+3)*4");
+        assert_eq!(arguments[1].1, 6);
+
+        let eq = "f(g(1,2))";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (name, arguments) = eq.split_call().unwrap();
+        assert_eq!(name, "f");
+        assert_eq!(arguments.len(), 1);
+        assert_eq!(arguments[0].0.clone().to_string(), "g(1,2)");
+
+        let eq = "f()";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (_, arguments) = eq.split_call().unwrap();
+        assert!(arguments.is_empty());
+
+        let eq = "f(1,)";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (_, arguments) = eq.split_call().unwrap();
+        assert_eq!(arguments.len(), 2);
+        assert_eq!(arguments[1], (Vec::new(), 4));
+    }
+
+    #[test]
+    pub fn test_not_a_call() {
+        let eq = "f(1)+(2)";
+        let eq = EquationString::remove_whitespaces(eq);
+        assert!(eq.split_call().is_none());
+
+        let eq = "2(1)";
+        let eq = EquationString::remove_whitespaces(eq);
+        assert!(eq.split_call().is_none());
+
+        let eq = "(1,2)";
+        let eq = EquationString::remove_whitespaces(eq);
+        assert!(eq.split_call().is_none());
+
+        let eq = "sin";
+        let eq = EquationString::remove_whitespaces(eq);
+        assert!(eq.split_call().is_none());
+    }
+}
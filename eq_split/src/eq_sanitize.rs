@@ -1,70 +1,174 @@
 use crate::{
+    error::EquationError,
+    identifiers::{
+        ends_with_function_name, is_identifier, is_identifier_char, is_identifier_start,
+    },
     math_characters::is_math_character,
-    precedences::{all_matcher::AllMatcher, traits::MatchOperator},
+    numbers::{
+        ends_with_number, is_exponent, is_number_part, last_number_start, number_literal_len,
+    },
+    operators::is_word_operator,
+    precedences::{all_matcher::AllMatcher, postfix::PostfixMatcher, traits::MatchOperator},
+    units::is_unit,
     EquationString,
 };
 
 pub trait EqSanitize {
     fn remove_whitespaces(eq: &str) -> Self;
-    fn handle_special_character_multiplication(&self) -> Result<Self, String>
+    fn handle_interval_brackets(&self) -> Result<Self, EquationError>
     where
         Self: Sized;
-    fn handle_direct_multiplication(&self) -> Result<Self, String>
+    fn handle_special_character_multiplication(&self) -> Result<Self, EquationError>
+    where
+        Self: Sized;
+    fn handle_direct_multiplication(&self) -> Result<Self, EquationError>
     where
         Self: Sized;
 
     fn to_string(self) -> String;
+    fn source_positions(&self, eq: &str) -> Vec<usize>;
 }
 
 impl EqSanitize for EquationString {
     /// remove all whitespaces in the eq string
+    /// except for a single space that separates a word operator
+    /// from a name or a number eg. 7 rem 3
+    /// or that separates an e from what would be its exponent eg. 5e + 1
+    /// or that separates a modulo from a sign so that it is not a percent eg. 10 % -3
+    /// a name followed by another word is multiplied with it
+    /// so that it is not read as a longer name eg. x y -> x*y, n items -> n*items, 2 kg m -> 2kg*m
     fn remove_whitespaces(eq: &str) -> Self {
-        let new_eq = eq.replace(" ", "");
-        new_eq.chars().collect::<EquationString>()
+        let eq = eq.chars().collect::<EquationString>();
+        let mut new_eq: EquationString = Vec::new();
+
+        for (i, current_char) in eq.iter().enumerate() {
+            if *current_char != ' ' {
+                new_eq.push(*current_char);
+                continue;
+            }
+
+            let next_word = &eq[i..];
+            let next_word = &next_word[next_word.iter().take_while(|c| **c == ' ').count()..];
+            let next_word_len = next_word
+                .iter()
+                .take_while(|c| is_identifier_char(**c))
+                .count();
+            let previous_word_len = new_eq
+                .iter()
+                .rev()
+                .take_while(|c| is_identifier_char(**c))
+                .count();
+
+            let separates_word_operator = previous_word_len > 0
+                && next_word_len > 0
+                && (is_word_operator(&new_eq[new_eq.len() - previous_word_len..])
+                    || is_word_operator(&next_word[..next_word_len]));
+
+            // 5e + 1 is 5*e+1 and not the number 5e+1
+            let mut joined_eq = new_eq.clone();
+            joined_eq.extend(next_word.iter().filter(|c| **c != ' ').take(2));
+            let separates_exponent = new_eq.last() != Some(&' ')
+                && (is_exponent(&joined_eq, new_eq.len().saturating_sub(1))
+                    || is_exponent(&joined_eq, new_eq.len()));
+
+            // the name at the end of a word that starts with a number eg. kg of 2kg
+            let previous_word = &new_eq[new_eq.len() - previous_word_len..];
+            let previous_name = &previous_word[previous_word
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .count()..];
+            let separates_names = next_word_len > 0 && is_identifier(previous_name);
+
+            // 10 % -3 is a modulo by -3 and 10% - 3 or 10%-3 is 3 less than 10%
+            let separates_modulo = new_eq.last() == Some(&'%')
+                && matches!(next_word, ['+' | '-', c, ..] if *c != ' ');
+
+            if separates_word_operator || separates_exponent || separates_modulo {
+                new_eq.push(' ');
+            } else if separates_names {
+                new_eq.push('*');
+            }
+        }
+        new_eq
     }
 
-    /// add a * in between a number and a special character
-    /// eg 5π -> 5*π
-    fn handle_special_character_multiplication(&self) -> Result<Self, String> {
+    /// write the interval [a, b] as the call interval(a, b)
+    /// a value right before it is multiplied eg. 2[1, 2] -> 2*interval(1,2)
+    fn handle_interval_brackets(&self) -> Result<Self, EquationError> {
         let mut new_eq: EquationString = Vec::new();
-        let mut eq = self.to_vec();
 
-        let mut previous_char: Option<char> = None;
+        for current_char in self.iter() {
+            match current_char {
+                '[' => {
+                    let word_len = new_eq
+                        .iter()
+                        .rev()
+                        .take_while(|c| is_identifier_char(**c))
+                        .count();
+                    let is_after_operator =
+                        word_len > 0 && is_word_operator(&new_eq[new_eq.len() - word_len..]);
 
-        loop {
-            let eq_len = eq.len();
-            if eq_len == 0 {
-                break;
+                    if is_after_operator {
+                        // the name cannot be joined to the word operator eg. not [0, 1]
+                        new_eq.push(' ');
+                    } else if word_len > 0 || matches!(new_eq.last(), Some(')' | '.')) {
+                        new_eq.push('*');
+                    }
+                    new_eq.extend("interval(".chars());
+                }
+                ']' => new_eq.push(')'),
+                _ => new_eq.push(*current_char),
             }
+        }
+        Ok(new_eq)
+    }
 
-            let current_char = eq[0];
-            if is_math_character(current_char)
-                && previous_char.is_some()
-                && previous_char.unwrap().is_digit(10)
-            {
-                previous_char = Some(current_char);
+    /// add a * in between a number and a special character or a name
+    /// eg 5π -> 5*π, 2x -> 2*x, 2sin(1) -> 2*sin(1)
+    /// a number and its unit are kept together eg. 1km/1s -> (1*km)/(1*s), 3m^2 -> (3*m^2)
+    /// names that contain a digit followed by a special character are left alone eg. x2e
+    /// and so are a word operator eg. 2in and the parts of a number literal eg. 5e-3, 0xFF
+    fn handle_special_character_multiplication(&self) -> Result<Self, EquationError> {
+        let mut new_eq: EquationString = Vec::new();
+        let mut i = 0;
+
+        while i < self.len() {
+            let is_special = is_math_character(self, i) || is_name_start(self, i);
+            if !is_special || !ends_with_number(&self[..i]) || is_number_part(self, i) {
+                new_eq.push(self[i]);
+                i += 1;
+                continue;
+            }
+
+            // 2^3m is 2^3*m and not 2^(3*m)
+            let number_start = last_number_start(&new_eq);
+            if !is_unit(self, i) || new_eq[..number_start].last() == Some(&'^') {
                 new_eq.push('*');
-                new_eq.push(current_char);
-                eq = eq[1..].to_vec();
-            } else {
-                previous_char = Some(current_char);
-                new_eq.push(current_char);
-                eq = eq[1..].to_vec();
+                new_eq.push(self[i]);
+                i += 1;
+                continue;
             }
+
+            let unit_len = unit_len(&self[i..]);
+            new_eq.insert(number_start, '(');
+            new_eq.push('*');
+            new_eq.extend(&self[i..i + unit_len]);
+            new_eq.push(')');
+            i += unit_len;
         }
         Ok(new_eq)
     }
 
     /// perform string manipulation to add '*' to operations involving brackets
     /// eg. 5(2) -> 5*(2)
-    fn handle_direct_multiplication(&self) -> Result<Self, String>
+    /// brackets after the name of a function are left alone eg. sin(2)
+    fn handle_direct_multiplication(&self) -> Result<Self, EquationError>
     where
         Self: Sized,
     {
         let mut new_eq: EquationString = Vec::new();
         let mut eq = self.to_vec();
-        let mut previous_char: Option<char> = None;
-        let all_matcher = AllMatcher {};
+        let all_matcher = AllMatcher;
 
         loop {
             let eq_len = eq.len();
@@ -73,29 +177,35 @@ impl EqSanitize for EquationString {
             }
 
             let current_char = eq[0];
+            let previous_char = new_eq.last().copied();
+            // the bracket is needed to tell if a % before it is a modulo eg. 7%(2)
+            let eq_until_bracket = [&new_eq[..], &['(']].concat();
             if current_char == '('
                 && previous_char.is_some()
-                && !all_matcher.match_operator(previous_char.unwrap())
-                && previous_char.unwrap() != '('
+                && !all_matcher.match_operator_before(&eq_until_bracket, new_eq.len())
+                && previous_char != Some('(')
+                && previous_char != Some(',')
+                && !ends_with_function_name(&new_eq)
             {
-                previous_char = Some(current_char);
                 new_eq.push('*');
                 new_eq.push(current_char);
                 eq = eq[1..].to_vec();
             } else if current_char == ')' && eq.len() > 1 {
                 let next_char = eq[1];
-                if !all_matcher.match_operator(next_char) && next_char != ')' {
-                    previous_char = Some('*');
+                if all_matcher.match_operator(&eq, 1).is_none()
+                    && PostfixMatcher.match_operator(&eq, 1).is_none()
+                    && next_char != ')'
+                    && next_char != ','
+                    && next_char != ' '
+                {
                     new_eq.push(current_char);
                     new_eq.push('*');
                     eq = eq[1..].to_vec();
                 } else {
-                    previous_char = Some(current_char);
                     new_eq.push(current_char);
                     eq = eq[1..].to_vec();
                 }
             } else {
-                previous_char = Some(current_char);
                 new_eq.push(current_char);
                 eq = eq[1..].to_vec();
             }
@@ -107,18 +217,166 @@ impl EqSanitize for EquationString {
     fn to_string(self) -> String {
         self.iter().collect()
     }
+
+    /// find where each char of the sanitized eq came from in the original 'eq'
+    /// the sanitizing only removes whitespaces, adds '*' and writes [a, b] as interval(a,b)
+    /// so an added '*' or interval takes the position of the char after it
+    /// an extra position is added at the end for the end of the eq
+    fn source_positions(&self, eq: &str) -> Vec<usize> {
+        let original = eq.chars().collect::<EquationString>();
+        let mut positions: Vec<usize> = Vec::new();
+        let mut original_index = 0;
+
+        for c in self.iter() {
+            while original_index < original.len()
+                && original[original_index] != *c
+                && original[original_index] == ' '
+            {
+                original_index += 1;
+            }
+
+            positions.push(original_index);
+            if original_index < original.len() && is_same_char(original[original_index], *c) {
+                original_index += 1;
+            }
+        }
+
+        positions.push(original.len());
+        positions
+    }
+}
+
+/// check if a name that is not a word operator starts at 'index' of the eq eg. the x of 2x
+fn is_name_start(eq: &[char], index: usize) -> bool {
+    let name_len = eq[index..]
+        .iter()
+        .take_while(|c| is_identifier_char(**c))
+        .count();
+    is_identifier_start(eq[index]) && !is_word_operator(&eq[index..index + name_len])
+}
+
+/// the length of the unit at the start of the eq with its power eg. 3 for m^2, 6 for s^(-1)
+fn unit_len(eq: &[char]) -> usize {
+    let name_len = eq.iter().take_while(|c| is_identifier_char(**c)).count();
+    if eq.get(name_len) != Some(&'^') {
+        return name_len;
+    }
+
+    let exponent = &eq[name_len + 1..];
+    let exponent_len = match exponent.first() {
+        Some('(') => {
+            let mut depth = 0;
+            exponent
+                .iter()
+                .position(|c| {
+                    match c {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ => (),
+                    }
+                    depth == 0
+                })
+                .map_or(0, |i| i + 1)
+        }
+        Some('-' | '+') => match number_literal_len(&exponent[1..]) {
+            0 => 0,
+            len => len + 1,
+        },
+        _ => number_literal_len(exponent),
+    };
+
+    match exponent_len {
+        0 => name_len,
+        len => name_len + 1 + len,
+    }
+}
+
+/// the brackets of an interval are written as parentheses after sanitizing eg. [1, 2]
+fn is_same_char(original: char, sanitized: char) -> bool {
+    matches!((original, sanitized), ('[', '(') | (']', ')')) || original == sanitized
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    pub fn test_remove_whitespaces() {
+        let eq = " 1 + 2 * x ";
+        let new_eq = EquationString::remove_whitespaces(eq);
+        assert_eq!(new_eq.to_string(), "1+2*x");
+
+        let eq = "7  rem 3 + x rem (2) - premium  2";
+        let new_eq = EquationString::remove_whitespaces(eq);
+        assert_eq!(new_eq.to_string(), "7 rem 3+x rem(2)-premium*2");
+
+        let eq = "5e + 1 + 5e-1 + 5 e2 + 5e  2";
+        let new_eq = EquationString::remove_whitespaces(eq);
+        assert_eq!(new_eq.to_string(), "5e +1+5e-1+5 e2+5e 2");
+
+        let eq = "x y + n items + rate 2 + 2 x + 3 m";
+        let new_eq = EquationString::remove_whitespaces(eq);
+        assert_eq!(new_eq.to_string(), "x*y+n*items+rate*2+2x+3m");
+
+        let eq = "2 x y + 2 kg m + 1 N m + 1 m 2 + 2x3 y + 2 rem 3";
+        let new_eq = EquationString::remove_whitespaces(eq);
+        assert_eq!(new_eq.to_string(), "2x*y+2kg*m+1N*m+1m*2+2x3*y+2 rem 3");
+
+        let eq = "10 % -3 + 10% - 3 + 10 % - 3 + 10%-3";
+        let new_eq = EquationString::remove_whitespaces(eq);
+        assert_eq!(new_eq.to_string(), "10% -3+10%-3+10%-3+10%-3");
+    }
+
     #[test]
     pub fn test_handle_special_character_multiplication() {
         let eq = "e(e)e+2e^ep";
         let eq = EquationString::remove_whitespaces(eq);
         let new_eq = eq.handle_special_character_multiplication().unwrap();
         assert_eq!(new_eq.to_string(), "e(e)e+2*e^ep");
+
+        let eq = "x2e+2e+rate2pi+2pi+n_1π";
+        let eq = EquationString::remove_whitespaces(eq);
+        let new_eq = eq.handle_special_character_multiplication().unwrap();
+        assert_eq!(new_eq.to_string(), "x2e+2*e+rate2pi+2*pi+n_1π");
+
+        let eq = "5ex+5pix+5e2";
+        let eq = EquationString::remove_whitespaces(eq);
+        let new_eq = eq.handle_special_character_multiplication().unwrap();
+        assert_eq!(new_eq.to_string(), "5*ex+5*pix+5e2");
+
+        let eq = "5e-3+2e^2-5e+x2e-3";
+        let eq = EquationString::remove_whitespaces(eq);
+        let new_eq = eq.handle_special_character_multiplication().unwrap();
+        assert_eq!(new_eq.to_string(), "5e-3+2*e^2-5*e+x2e-3");
+
+        let eq = "2i+3-2.5i+2pi+x2i+5if+2in";
+        let eq = EquationString::remove_whitespaces(eq);
+        let new_eq = eq.handle_special_character_multiplication().unwrap();
+        assert_eq!(new_eq.to_string(), "2*i+3-2.5*i+2*pi+x2i+5*if+2in");
+
+        let eq = "3 m + 20cm + 2.5h + x2m + 5e-3kg + 2 mx";
+        let eq = EquationString::remove_whitespaces(eq);
+        let new_eq = eq.handle_special_character_multiplication().unwrap();
+        assert_eq!(
+            new_eq.to_string(),
+            "(3*m)+(20*cm)+(2.5*h)+x2m+(5e-3*kg)+2*mx"
+        );
+
+        let eq = "1 km / 1 s + 9.81m/s^2 * 3 m^2 + 2 s^-1 + 4 m^(1+1) + 2^3m";
+        let eq = EquationString::remove_whitespaces(eq);
+        let new_eq = eq.handle_special_character_multiplication().unwrap();
+        assert_eq!(
+            new_eq.to_string(),
+            "(1*km)/(1*s)+(9.81*m)/s^2*(3*m^2)+(2*s^-1)+(4*m^(1+1))+2^3*m"
+        );
+
+        let eq = "2x+3x^2+2rate+2sin(1)+0xFF+10x+2 rem 3";
+        let eq = EquationString::remove_whitespaces(eq);
+        let new_eq = eq.handle_special_character_multiplication().unwrap();
+        assert_eq!(
+            new_eq.to_string(),
+            "2*x+3*x^2+2*rate+2*sin(1)+0xFF+10*x+2 rem 3"
+        );
     }
 
     #[test]
@@ -128,6 +386,13 @@ mod tests {
         let new_eq = eq.handle_direct_multiplication().unwrap();
         assert_eq!(new_eq.to_string(), "2*(π+3)^e+7--(5/(3-2))*pi");
 
+        let eq = "3 m to cm";
+        let eq = EquationString::remove_whitespaces(eq)
+            .handle_special_character_multiplication()
+            .unwrap();
+        let new_eq = eq.handle_direct_multiplication().unwrap();
+        assert_eq!(new_eq.to_string(), "(3*m) to cm");
+
         let eq = "2(e+2)^π*2+-((5+7/2)-3^pi)";
         let eq = EquationString::remove_whitespaces(eq);
         let new_eq = eq.handle_direct_multiplication().unwrap();
@@ -162,5 +427,51 @@ mod tests {
         let eq = EquationString::remove_whitespaces(eq);
         let new_eq = eq.handle_direct_multiplication().unwrap();
         assert_eq!(new_eq.to_string(), "(1+2)*π*5π*(5+2)/4");
+
+        let eq = "2*sin(pi(1))(3)+max((1)(2),(3))";
+        let eq = EquationString::remove_whitespaces(eq);
+        let new_eq = eq.handle_direct_multiplication().unwrap();
+        assert_eq!(new_eq.to_string(), "2*sin(pi*(1))*(3)+max((1)*(2),(3))");
+
+        let eq = "(2)!(3)+3!(1)+7%(2)+(50)%";
+        let eq = EquationString::remove_whitespaces(eq);
+        let new_eq = eq.handle_direct_multiplication().unwrap();
+        assert_eq!(new_eq.to_string(), "(2)!*(3)+3!*(1)+7%(2)+(50)%");
+    }
+
+    #[test]
+    pub fn test_source_positions() {
+        let eq = "2 (1 + 3)";
+        let new_eq = EquationString::remove_whitespaces(eq);
+        let new_eq = new_eq.handle_direct_multiplication().unwrap();
+        assert_eq!(new_eq.clone().to_string(), "2*(1+3)");
+        assert_eq!(new_eq.source_positions(eq), vec![0, 2, 2, 3, 5, 7, 8, 9]);
+
+        let eq = "5e + 1";
+        let new_eq = EquationString::remove_whitespaces(eq);
+        let new_eq = new_eq.handle_special_character_multiplication().unwrap();
+        assert_eq!(new_eq.clone().to_string(), "5*e +1");
+        assert_eq!(new_eq.source_positions(eq), vec![0, 1, 1, 2, 3, 5, 6]);
+
+        let eq = "2[1, 2]";
+        let new_eq = EquationString::remove_whitespaces(eq);
+        let new_eq = new_eq.handle_interval_brackets().unwrap();
+        assert_eq!(new_eq.clone().to_string(), "2*interval(1,2)");
+        let positions = new_eq.source_positions(eq);
+        assert_eq!(
+            positions,
+            vec![0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 3, 5, 6, 7]
+        );
+    }
+
+    #[test]
+    pub fn test_handle_interval_brackets() {
+        let eq = "[1.2, 1.3] + x[0, 1] - not [0, 1] * ([2, 3])";
+        let eq = EquationString::remove_whitespaces(eq);
+        let new_eq = eq.handle_interval_brackets().unwrap();
+        assert_eq!(
+            new_eq.to_string(),
+            "interval(1.2,1.3)+x*interval(0,1)-not interval(0,1)*(interval(2,3))"
+        );
     }
 }
@@ -0,0 +1,208 @@
+use std::{error::Error, fmt};
+
+/// a range of char offsets in the equation
+/// 'start' is inclusive and 'end' is exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// span that covers the single char at 'index'
+    pub fn at(index: usize) -> Self {
+        Self::new(index, index + 1)
+    }
+
+    /// move the span to the right by 'offset'
+    /// used when an error found in a part of the equation
+    /// is reported against the whole equation
+    pub fn shift(self, offset: usize) -> Self {
+        Self::new(self.start + offset, self.end + offset)
+    }
+}
+
+/// errors that can happen while sanitizing, splitting
+/// or building the operation tree of an equation
+#[derive(Debug, Clone, PartialEq)]
+pub enum EquationError {
+    /// an open bracket that is never closed or a close bracket without an open bracket
+    UnbalancedParenthesis(Span),
+    /// a value that cannot be read as a number
+    InvalidNumber(Span),
+    /// an operator in a place where a value is expected
+    UnexpectedOperator(Span),
+    /// an equation or a part of it that has nothing in it
+    EmptyExpression(Span),
+    /// a math character such as e or pi that is used incorrectly eg. π2, pi = 3
+    InvalidConstantUsage(Span),
+    /// something other than a name on the left of '=' eg. 1 = 2, y = x = 2
+    /// or a name that is a keyword or a builtin function eg. not = 1, sin = 2
+    InvalidAssignment(Span),
+}
+
+impl EquationError {
+    /// the part of the equation that caused the error
+    pub fn span(&self) -> Span {
+        match self {
+            Self::UnbalancedParenthesis(span)
+            | Self::InvalidNumber(span)
+            | Self::UnexpectedOperator(span)
+            | Self::EmptyExpression(span)
+            | Self::InvalidConstantUsage(span)
+            | Self::InvalidAssignment(span) => *span,
+        }
+    }
+
+    /// point the error at the original eq instead of the sanitized eq
+    /// 'positions' is the position in the original eq of each char in the sanitized eq
+    /// see 'EqSanitize::source_positions'
+    pub fn to_source(self, positions: &[usize]) -> Self {
+        let span = self.span();
+        let start = positions[span.start];
+        let end = if span.end > span.start {
+            positions[span.end - 1] + 1
+        } else {
+            start
+        };
+
+        self.with_span(Span::new(start, end))
+    }
+
+    /// the same error pointing at another part of the equation
+    pub fn with_span(self, span: Span) -> Self {
+        match self {
+            Self::UnbalancedParenthesis(_) => Self::UnbalancedParenthesis(span),
+            Self::InvalidNumber(_) => Self::InvalidNumber(span),
+            Self::UnexpectedOperator(_) => Self::UnexpectedOperator(span),
+            Self::EmptyExpression(_) => Self::EmptyExpression(span),
+            Self::InvalidConstantUsage(_) => Self::InvalidConstantUsage(span),
+            Self::InvalidAssignment(_) => Self::InvalidAssignment(span),
+        }
+    }
+
+    /// move the span of the error to the right by 'offset'
+    pub fn shift(self, offset: usize) -> Self {
+        let span = self.span().shift(offset);
+        self.with_span(span)
+    }
+
+    fn message(&self) -> &'static str {
+        match self {
+            Self::UnbalancedParenthesis(_) => "Unbalanced parenthesis",
+            Self::InvalidNumber(_) => "Incorrect number",
+            Self::UnexpectedOperator(_) => "Unexpected operator",
+            Self::EmptyExpression(_) => "Cannot be empty",
+            Self::InvalidConstantUsage(_) => "Invalid use of math character",
+            Self::InvalidAssignment(_) => {
+                "Can only assign to a name that is not a keyword or function"
+            }
+        }
+    }
+
+    /// show the eq with the part that caused the error marked, eg.
+    /// (1343+3
+    /// ^
+    /// Open bracket not closed
+    ///
+    /// 'eq' is the eq that the span of the error points to
+    pub fn report(&self, eq: &str) -> String {
+        let span = self.span();
+        let eq_chars = eq.chars().collect::<Vec<char>>();
+
+        let is_open_bracket = matches!(eq_chars.get(span.start), Some('(' | '['));
+
+        let explanation = match self {
+            Self::UnbalancedParenthesis(_) if is_open_bracket => "Open bracket not closed",
+            Self::UnbalancedParenthesis(_) => "Close bracket found without open brackets",
+            Self::EmptyExpression(_) => "Expected a value here",
+            _ => self.message(),
+        };
+
+        let marker_len = span.end.saturating_sub(span.start).max(1);
+        let marker = format!("{}^{}", " ".repeat(span.start), "~".repeat(marker_len - 1));
+
+        format!("{}\n{}\n{}", eq, marker, explanation)
+    }
+}
+
+impl fmt::Display for EquationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let span = self.span();
+        write!(f, "{} at {}..{}", self.message(), span.start, span.end)
+    }
+}
+
+impl Error for EquationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_shift_error() {
+        let error = EquationError::InvalidNumber(Span::new(1, 4));
+        assert_eq!(
+            error.shift(3),
+            EquationError::InvalidNumber(Span::new(4, 7))
+        );
+
+        let error = EquationError::UnbalancedParenthesis(Span::at(0));
+        assert_eq!(error.span(), Span::new(0, 1));
+    }
+
+    #[test]
+    pub fn test_error_to_source() {
+        // "1 + 2(3" sanitized to "1+2*(3"
+        let positions = vec![0, 2, 4, 5, 5, 6, 7];
+
+        let error = EquationError::UnbalancedParenthesis(Span::at(4));
+        assert_eq!(
+            error.to_source(&positions),
+            EquationError::UnbalancedParenthesis(Span::at(5))
+        );
+
+        let error = EquationError::InvalidNumber(Span::new(0, 3));
+        assert_eq!(
+            error.to_source(&positions),
+            EquationError::InvalidNumber(Span::new(0, 5))
+        );
+
+        let error = EquationError::EmptyExpression(Span::new(6, 6));
+        assert_eq!(
+            error.to_source(&positions),
+            EquationError::EmptyExpression(Span::new(7, 7))
+        );
+    }
+
+    #[test]
+    pub fn test_report_error() {
+        let error = EquationError::UnbalancedParenthesis(Span::at(0));
+        assert_eq!(
+            error.report("(1343+3"),
+            "(1343+3\n^\nOpen bracket not closed"
+        );
+
+        let error = EquationError::InvalidNumber(Span::new(2, 7));
+        assert_eq!(
+            error.report("1+1.2.3*2"),
+            "1+1.2.3*2\n  ^~~~~\nIncorrect number"
+        );
+
+        let error = EquationError::EmptyExpression(Span::new(2, 2));
+        assert_eq!(error.report("1+"), "1+\n  ^\nExpected a value here");
+    }
+
+    #[test]
+    pub fn test_display_error() {
+        let error = EquationError::UnexpectedOperator(Span::at(2));
+        assert_eq!(error.to_string(), "Unexpected operator at 2..3");
+
+        let error = EquationError::EmptyExpression(Span::new(0, 0));
+        assert_eq!(error.to_string(), "Cannot be empty at 0..0");
+    }
+}
@@ -0,0 +1,79 @@
+use crate::operators::is_word_operator;
+
+/// names of variables are made of letters, digits and '_'
+/// and cannot start with a digit eg. x, rate, n_items
+pub fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+pub fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// check if the entire eq is a single name
+pub fn is_identifier(eq: &[char]) -> bool {
+    match eq.split_first() {
+        Some((first, rest)) => {
+            is_identifier_start(*first) && rest.iter().all(|c| is_identifier_char(*c))
+        }
+        None => false,
+    }
+}
+
+/// check if the eq ends with the name of a function eg. 2*sin, max
+/// the math characters e and pi are values and not functions
+/// and a word operator such as rem is not a function either
+pub fn ends_with_function_name(eq: &[char]) -> bool {
+    let name_start = eq
+        .iter()
+        .rposition(|c| !is_identifier_char(*c))
+        .map_or(0, |i| i + 1);
+    let name = &eq[name_start..];
+
+    is_identifier(name) && name != ['e'] && name != ['p', 'i'] && !is_word_operator(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EquationString;
+
+    #[test]
+    pub fn test_is_identifier() {
+        let eq = "n_items2".chars().collect::<EquationString>();
+        assert!(is_identifier(&eq));
+
+        let eq = "_x".chars().collect::<EquationString>();
+        assert!(is_identifier(&eq));
+
+        let eq = "2x".chars().collect::<EquationString>();
+        assert!(!is_identifier(&eq));
+
+        let eq = "x+1".chars().collect::<EquationString>();
+        assert!(!is_identifier(&eq));
+
+        let eq = "π".chars().collect::<EquationString>();
+        assert!(!is_identifier(&eq));
+
+        let eq = "".chars().collect::<EquationString>();
+        assert!(!is_identifier(&eq));
+    }
+
+    #[test]
+    pub fn test_ends_with_function_name() {
+        let eq = "2*sin".chars().collect::<EquationString>();
+        assert!(ends_with_function_name(&eq));
+
+        let eq = "log2".chars().collect::<EquationString>();
+        assert!(ends_with_function_name(&eq));
+
+        let eq = "2*pi".chars().collect::<EquationString>();
+        assert!(!ends_with_function_name(&eq));
+
+        let eq = "5*e".chars().collect::<EquationString>();
+        assert!(!ends_with_function_name(&eq));
+
+        let eq = "1+25".chars().collect::<EquationString>();
+        assert!(!ends_with_function_name(&eq));
+    }
+}
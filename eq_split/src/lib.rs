@@ -1,16 +1,32 @@
-use operators::{Operators, SplitOperator};
+use error::{EquationError, Span};
+use operators::{Operators, SplitOperator, UnaryOperators};
+use options::ParseOptions;
 use parentheses::{FindParentheses, ParenthesesFinder, SplitParentheses};
+use precedences::{all_matcher::AllMatcher, traits::MatchOperator};
 
+pub mod arithmetic;
+pub mod calls;
 pub mod eq_sanitize;
+pub mod error;
+pub mod identifiers;
+pub mod numbers;
 pub mod operators;
+pub mod options;
 mod parentheses;
 mod precedences;
+pub mod units;
 mod math_characters;
 
 pub type EquationString = Vec<char>;
 
 pub trait FullSplit {
-    fn split(&self) -> Result<(Self, Self, Operators), String>
+    fn split(&self) -> Result<(Self, Self, Operators), EquationError>
+    where
+        Self: Sized;
+    fn split_with(&self, options: ParseOptions) -> Result<(Self, Self, Operators), EquationError>
+    where
+        Self: Sized;
+    fn remove_outer_parentheses(&self) -> Result<(Self, usize), EquationError>
     where
         Self: Sized;
 }
@@ -19,22 +35,26 @@ impl FullSplit for EquationString {
     /// split equation to a two halves, a left and a right
     /// callee will need to reiterative call this function
     /// to ensure all nodes are single value only
-    fn split(&self) -> Result<(Self, Self, Operators), String>
+    fn split(&self) -> Result<(Self, Self, Operators), EquationError>
     where
         Self: Sized,
     {
-        let first_parentheses = ParenthesesFinder::find_first((&self).to_vec());
-        if let Err(t) = first_parentheses {
-            return Err(t);
-        }
-        let first_parentheses = first_parentheses.unwrap();
+        self.split_with(ParseOptions::default())
+    }
+
+    /// split equation the same way as 'split' with the rules set in 'options'
+    fn split_with(&self, options: ParseOptions) -> Result<(Self, Self, Operators), EquationError>
+    where
+        Self: Sized,
+    {
+        let first_parentheses = ParenthesesFinder::find_first(self.to_vec())?;
 
         if first_parentheses.is_none() {
             // eq has no parentheses
             // normal eq eg.1+3*5
             // has no operators eg. 578
 
-            return Ok(self.split_by_precedence());
+            return Ok(self.split_by_precedence(options));
         }
         // eq has parentheses
         let (left_parentheses_index, right_parentheses_index) = first_parentheses.unwrap();
@@ -44,32 +64,68 @@ impl FullSplit for EquationString {
             // remove the outer most parentheses and call 'split' recursively
             // return split(&eq[1..eq.len() - 1]);
             let eq = self[1..self.len() - 1].to_vec();
-            return eq.split();
+            return eq.split_with(options).map_err(|e| e.shift(1));
         }
 
-        if left_parentheses_index == 1 && right_parentheses_index == self.len() - 1 {
-            // parentheses contains the entire eq except for a negative operator eg. -(1+3*5)
-            // remove the outer most parentheses and call 'split' recursively
-            // return split(&eq[1..eq.len() - 1]);
-            if self[0] == '-' {
-                let left = vec!['-', '1'];
-                let right = self[1..].to_vec();
-                let operators = Operators::Mult;
-
-                return Ok((left, right, operators));
-            } else {
-                return Err(String::from("Invalid operator in front of bracket"));
-            }
+        let signs = self
+            .iter()
+            .take_while(|c| UnaryOperators::to_enum(**c).is_some())
+            .count();
+        if signs > 0 && left_parentheses_index == signs && right_parentheses_index == self.len() - 1
+        {
+            // parentheses contains the entire eq except for the signs eg. -(1+3*5), --(2)
+            // this is a single value and the signs are handled by the callee
+            return Ok((self.to_vec(), Self::new(), Operators::None));
+        }
+
+        if left_parentheses_index == 1
+            && right_parentheses_index == self.len() - 1
+            && AllMatcher.match_operator(self, 0).is_some()
+        {
+            // invalid operator in front of bracket
+            return Err(EquationError::UnexpectedOperator(Span::at(0)));
         }
 
         // we split the eq into two halves according to the parentheses
         let (left, right) =
-            self.split_by_parentheses(left_parentheses_index, right_parentheses_index);
-        let operators = Operators::to_enum(right[0]);
-        let right = right[1..].to_vec();
+            self.split_by_parentheses(left_parentheses_index, right_parentheses_index, options);
+        if right.is_empty() {
+            // no operator outside of the parentheses
+            // this is a single value eg. sin(2), -max(1,2)
+            return Ok((left, right, Operators::None));
+        }
+
+        let Some(operator_len) = AllMatcher.match_operator(&right, 0) else {
+            // the parentheses are followed by something that is not an operator
+            return Ok((self.to_vec(), Self::new(), Operators::None));
+        };
+
+        let operators = Operators::to_enum(&right[..operator_len]);
+        let right = right[operator_len..].to_vec();
 
         Ok((left, right, operators))
     }
+
+    /// remove the parentheses that wrap the entire eq eg. ((1+3)) -> 1+3
+    /// returns the eq and the number of parentheses removed from each side
+    /// so that callee can keep track of where the eq is in the original eq
+    fn remove_outer_parentheses(&self) -> Result<(Self, usize), EquationError> {
+        let mut eq = self.to_vec();
+        let mut removed = 0;
+
+        while let Some((left_parentheses_index, right_parentheses_index)) =
+            ParenthesesFinder::find_first(eq.to_vec()).map_err(|e| e.shift(removed))?
+        {
+            if left_parentheses_index != 0 || right_parentheses_index != eq.len() - 1 {
+                break;
+            }
+
+            eq = eq[1..eq.len() - 1].to_vec();
+            removed += 1;
+        }
+
+        Ok((eq, removed))
+    }
 }
 
 #[cfg(test)]
@@ -83,8 +139,8 @@ mod tests {
         let eq = "1+-2*2+5";
         let eq = EquationString::remove_whitespaces(eq);
         let (left, right, operator) = eq.split().unwrap();
-        assert_eq!(left.to_string(), "1");
-        assert_eq!(right.to_string(), "-2*2+5");
+        assert_eq!(left.to_string(), "1+-2*2");
+        assert_eq!(right.to_string(), "5");
         assert_eq!(operator, Operators::Plus);
 
         let eq = "-2*2-5";
@@ -104,6 +160,20 @@ mod tests {
         let eq = "-2^2^5";
         let eq = EquationString::remove_whitespaces(eq);
         let (left, right, operator) = eq.split().unwrap();
+        assert_eq!(left.to_string(), "-2^2^5");
+        assert_eq!(right.to_string(), "");
+        assert_eq!(operator, Operators::None);
+
+        let eq = "2^2^5";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split().unwrap();
+        assert_eq!(left.to_string(), "2");
+        assert_eq!(right.to_string(), "2^5");
+        assert_eq!(operator, Operators::Exp);
+
+        let eq = "-2^2^5";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split_with(ParseOptions::legacy()).unwrap();
         assert_eq!(left.to_string(), "-2^2");
         assert_eq!(right.to_string(), "5");
         assert_eq!(operator, Operators::Exp);
@@ -111,9 +181,16 @@ mod tests {
 
     #[test]
     pub fn test_split_eq_wrapped_with_parentheses() {
-        let eq = "(-2^2^5)";
+        let eq = "(2^2^5)";
         let eq = EquationString::remove_whitespaces(eq);
         let (left, right, operator) = eq.split().unwrap();
+        assert_eq!(left.to_string(), "2");
+        assert_eq!(right.to_string(), "2^5");
+        assert_eq!(operator, Operators::Exp);
+
+        let eq = "(-2^2^5)";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split_with(ParseOptions::legacy()).unwrap();
         assert_eq!(left.to_string(), "-2^2");
         assert_eq!(right.to_string(), "5");
         assert_eq!(operator, Operators::Exp);
@@ -121,9 +198,21 @@ mod tests {
         let eq = "-(-2^2^5)";
         let eq = EquationString::remove_whitespaces(eq);
         let (left, right, operator) = eq.split().unwrap();
-        assert_eq!(left.to_string(), "-1");
-        assert_eq!(right.to_string(), "(-2^2^5)");
-        assert_eq!(operator, Operators::Mult);
+        assert_eq!(left.to_string(), "-(-2^2^5)");
+        assert_eq!(right.to_string(), "");
+        assert_eq!(operator, Operators::None);
+
+        let eq = "-+(1)";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split().unwrap();
+        assert_eq!(left.to_string(), "-+(1)");
+        assert_eq!(right.to_string(), "");
+        assert_eq!(operator, Operators::None);
+
+        let eq = "*(1)";
+        let eq = EquationString::remove_whitespaces(eq);
+        let error = eq.split().unwrap_err();
+        assert_eq!(error, EquationError::UnexpectedOperator(Span::at(0)));
     }
 
     #[test]
@@ -141,6 +230,13 @@ mod tests {
         assert_eq!(left.to_string(), "-1");
         assert_eq!(right.to_string(), "(-2^2^5)*25");
         assert_eq!(operator, Operators::Plus);
+
+        let eq = "(1 m + 2) to cm";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split().unwrap();
+        assert_eq!(left.to_string(), "(1m+2)");
+        assert_eq!(right.to_string(), " cm");
+        assert_eq!(operator, Operators::Convert);
     }
 
     #[test]
@@ -173,4 +269,80 @@ mod tests {
         assert_eq!(right.to_string(), "3");
         assert_eq!(operator, Operators::Div);
     }
+
+    #[test]
+    pub fn test_remove_outer_parentheses() {
+        let eq = "((1+2))";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (eq, removed) = eq.remove_outer_parentheses().unwrap();
+        assert_eq!(eq.to_string(), "1+2");
+        assert_eq!(removed, 2);
+
+        let eq = "(1+2)*(3)";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (eq, removed) = eq.remove_outer_parentheses().unwrap();
+        assert_eq!(eq.to_string(), "(1+2)*(3)");
+        assert_eq!(removed, 0);
+
+        let eq = "((1+2)";
+        let eq = EquationString::remove_whitespaces(eq);
+        let error = eq.remove_outer_parentheses().unwrap_err();
+        assert_eq!(error, EquationError::UnbalancedParenthesis(Span::at(0)));
+    }
+
+    #[test]
+    pub fn test_split_errors() {
+        let eq = "(1+(2)";
+        let eq = EquationString::remove_whitespaces(eq);
+        let error = eq.split().unwrap_err();
+        assert_eq!(error, EquationError::UnbalancedParenthesis(Span::at(0)));
+
+        let eq = "(1+2))";
+        let eq = EquationString::remove_whitespaces(eq);
+        let error = eq.split().unwrap_err();
+        assert_eq!(error, EquationError::UnbalancedParenthesis(Span::at(5)));
+
+        let eq = "*(1+2)";
+        let eq = EquationString::remove_whitespaces(eq);
+        let error = eq.split().unwrap_err();
+        assert_eq!(error, EquationError::UnexpectedOperator(Span::at(0)));
+    }
+
+    #[test]
+    pub fn test_split_function_call() {
+        let eq = "sin(2)";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split().unwrap();
+        assert_eq!(left.to_string(), "sin(2)");
+        assert_eq!(right.to_string(), "");
+        assert_eq!(operator, Operators::None);
+
+        let eq = "f(2,3)";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split().unwrap();
+        assert_eq!(left.to_string(), "f(2,3)");
+        assert_eq!(right.to_string(), "");
+        assert_eq!(operator, Operators::None);
+
+        let eq = "-max(1,2)";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split().unwrap();
+        assert_eq!(left.to_string(), "-max(1,2)");
+        assert_eq!(right.to_string(), "");
+        assert_eq!(operator, Operators::None);
+
+        let eq = "2*sin(2)^2";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split().unwrap();
+        assert_eq!(left.to_string(), "2");
+        assert_eq!(right.to_string(), "sin(2)^2");
+        assert_eq!(operator, Operators::Mult);
+
+        let eq = "sin(2)^2";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split().unwrap();
+        assert_eq!(left.to_string(), "sin(2)");
+        assert_eq!(right.to_string(), "2");
+        assert_eq!(operator, Operators::Exp);
+    }
 }
@@ -1,8 +1,15 @@
-pub fn is_math_character(val: char) -> bool {
-    match val {
-        'π' => true,
-        'p' => true,
-        'e' => true,
-        _ => false,
-    }
+use crate::identifiers::is_identifier_char;
+
+/// check if a whole math character starts at 'index' of the eq
+/// eg. the e in 5e+1 but not the e in 5ex, as it is part of the name ex
+/// i is the imaginary unit eg. 2i
+pub fn is_math_character(eq: &[char], index: usize) -> bool {
+    let len = match eq[index] {
+        'π' => return true,
+        'e' | 'i' => 1,
+        'p' if eq.get(index + 1) == Some(&'i') => 2,
+        _ => return false,
+    };
+
+    !eq.get(index + len).is_some_and(|c| is_identifier_char(*c))
 }
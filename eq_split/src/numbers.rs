@@ -0,0 +1,249 @@
+use std::str::FromStr;
+
+use bigdecimal::{num_bigint::BigInt, BigDecimal};
+
+use crate::identifiers::is_identifier_char;
+
+/// the length of the number literal at the start of the eq
+/// eg. 12, 1.5, .5, 1e10, 6.02e-23, 2.5E+3, 0xFF, 0b1010, 0o755
+/// the e is only an exponent when digits follow it, else it is Euler's number eg. 5e, 2e^2
+pub fn number_literal_len(eq: &[char]) -> usize {
+    if let Some(radix) = literal_radix(eq) {
+        let digits_len = eq[2..].iter().take_while(|c| c.is_digit(radix)).count();
+        return 2 + digits_len;
+    }
+
+    let mantissa_len = eq
+        .iter()
+        .take_while(|c| c.is_ascii_digit() || **c == '.')
+        .count();
+    if mantissa_len == 0 || !matches!(eq.get(mantissa_len), Some('e' | 'E')) {
+        return mantissa_len;
+    }
+
+    let sign_len = usize::from(matches!(eq.get(mantissa_len + 1), Some('+' | '-')));
+    let exponent_start = mantissa_len + 1 + sign_len;
+    let exponent_len = eq[exponent_start.min(eq.len())..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+
+    if exponent_len == 0 {
+        mantissa_len
+    } else {
+        exponent_start + exponent_len
+    }
+}
+
+/// the radix of an integer literal that starts with 0x, 0b or 0o
+/// eg. 16 for 0xFF
+pub fn literal_radix(eq: &[char]) -> Option<u32> {
+    if eq.first() != Some(&'0') {
+        return None;
+    }
+
+    match eq.get(1) {
+        Some('x' | 'X') => Some(16),
+        Some('b' | 'B') => Some(2),
+        Some('o' | 'O') => Some(8),
+        _ => None,
+    }
+}
+
+/// parse a whole number literal eg. 1.5, 6.02e-23, 0xFF
+/// the integer literals that do not fit in an i64 are rounded eg. 0xFFFFFFFFFFFFFFFFF = 2^68
+pub fn parse_number_literal(eq: &[char]) -> Option<f64> {
+    let literal: String = eq.iter().collect();
+
+    match literal_radix(eq) {
+        Some(radix) => i64::from_str_radix(&literal[2..], radix)
+            .ok()
+            .map(|value| value as f64)
+            .or_else(|| parse_large_integer(&literal[2..], radix)),
+        None => literal.parse::<f64>().ok(),
+    }
+}
+
+/// parse a number literal straight into a decimal eg. 0.1, 6.02e-23, 0xFF
+pub fn parse_decimal_literal(literal: &str) -> Option<BigDecimal> {
+    let chars = literal.chars().collect::<Vec<char>>();
+    match literal_radix(&chars) {
+        Some(radix) => BigInt::parse_bytes(&literal.as_bytes()[2..], radix).map(BigDecimal::from),
+        None => BigDecimal::from_str(literal).ok(),
+    }
+}
+
+/// parse the digits of an integer literal that is too large for an i64
+fn parse_large_integer(digits: &str, radix: u32) -> Option<f64> {
+    if digits.is_empty() {
+        return None;
+    }
+
+    digits.chars().try_fold(0.0, |value, c| {
+        let digit = c.to_digit(radix)?;
+        Some(value * radix as f64 + digit as f64)
+    })
+}
+
+/// check if the char at 'index' is the e or the sign of the exponent of a number literal
+/// eg. the e and the - in 6.02e-23 but not the e in 5e or x2e-3
+pub fn is_exponent(eq: &[char], index: usize) -> bool {
+    let e_index = match eq.get(index) {
+        Some('e' | 'E') => index,
+        Some('+' | '-') if index > 0 && matches!(eq[index - 1], 'e' | 'E') => index - 1,
+        _ => return false,
+    };
+
+    if !ends_with_number(&eq[..e_index]) {
+        return false;
+    }
+
+    let number_start = eq[..e_index]
+        .iter()
+        .rposition(|c| !c.is_ascii_digit() && *c != '.')
+        .map_or(0, |i| i + 1);
+    number_start + number_literal_len(&eq[number_start..]) > index
+}
+
+/// check if the char at 'index' continues the number literal right before it
+/// eg. the x of 0xFF and the e of 6.02e-23 but not the x of 10x or the e of 5e
+pub fn is_number_part(eq: &[char], index: usize) -> bool {
+    if !ends_with_number(&eq[..index]) {
+        return false;
+    }
+
+    let number_start = eq[..index]
+        .iter()
+        .rposition(|c| !c.is_ascii_digit() && *c != '.')
+        .map_or(0, |i| i + 1);
+    number_start + number_literal_len(&eq[number_start..]) > index
+}
+
+/// check if the eq ends with a number that is not part of a name
+/// eg. 5 in 1+5 but not 2 in 1+x2
+pub fn ends_with_number(eq: &[char]) -> bool {
+    let number_start = eq
+        .iter()
+        .rposition(|c| !c.is_ascii_digit() && *c != '.')
+        .map_or(0, |i| i + 1);
+
+    number_start < eq.len() && (number_start == 0 || !is_identifier_char(eq[number_start - 1]))
+}
+
+/// the index where the number literal at the end of the eq starts
+/// eg. 2 for 1+6.02e-23 and 6 for 1+x2e-3, the eq has to end with a number
+pub fn last_number_start(eq: &[char]) -> usize {
+    let digits_start = |eq: &[char]| {
+        eq.iter()
+            .rposition(|c| !c.is_ascii_digit() && *c != '.')
+            .map_or(0, |i| i + 1)
+    };
+
+    let number_start = digits_start(eq);
+    let e_index = match eq[..number_start] {
+        [.., 'e' | 'E', '+' | '-'] => number_start - 2,
+        [.., 'e' | 'E'] => number_start - 1,
+        _ => return number_start,
+    };
+
+    let mantissa_start = digits_start(&eq[..e_index]);
+    let is_literal = mantissa_start < e_index
+        && (mantissa_start == 0 || !is_identifier_char(eq[mantissa_start - 1]))
+        && mantissa_start + number_literal_len(&eq[mantissa_start..]) == eq.len();
+    if is_literal {
+        mantissa_start
+    } else {
+        number_start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EquationString;
+
+    #[test]
+    pub fn test_number_literal_len() {
+        let cases = [
+            ("12+3", 2),
+            ("1.5", 3),
+            (".5", 2),
+            ("1e10", 4),
+            ("6.02e-23*2", 8),
+            ("2.5E+3", 6),
+            ("5e", 1),
+            ("5e+", 1),
+            ("2e^2", 1),
+            ("5ex", 1),
+            ("x", 0),
+            ("0xFF+1", 4),
+            ("0x1e+5", 4),
+            ("0b1012", 5),
+            ("0o755", 5),
+            ("0x", 2),
+        ];
+
+        for (eq, len) in cases {
+            let eq = eq.chars().collect::<EquationString>();
+            assert_eq!(number_literal_len(&eq), len);
+        }
+    }
+
+    #[test]
+    pub fn test_parse_number_literal() {
+        let cases = [
+            ("0xFF", Some(255.0)),
+            ("0Xff", Some(255.0)),
+            ("0b1010", Some(10.0)),
+            ("0o755", Some(493.0)),
+            ("6.02e-23", Some(6.02e-23)),
+            ("0x", None),
+            ("0b102", None),
+            ("0xFFFFFFFFFFFFFFFFF", Some(2.0_f64.powi(68))),
+        ];
+
+        for (eq, value) in cases {
+            let eq = eq.chars().collect::<EquationString>();
+            assert_eq!(parse_number_literal(&eq), value);
+        }
+    }
+
+    #[test]
+    pub fn test_is_exponent() {
+        let eq = "1+6.02e-23".chars().collect::<EquationString>();
+        assert!(is_exponent(&eq, 6));
+        assert!(is_exponent(&eq, 7));
+        assert!(!is_exponent(&eq, 1));
+
+        let eq = "5e-3+x2e-3+5e-x".chars().collect::<EquationString>();
+        assert!(is_exponent(&eq, 2));
+        assert!(!is_exponent(&eq, 7));
+        assert!(!is_exponent(&eq, 8));
+        assert!(!is_exponent(&eq, 13));
+
+        let eq = "0x1e+5".chars().collect::<EquationString>();
+        assert!(!is_exponent(&eq, 3));
+        assert!(!is_exponent(&eq, 4));
+    }
+
+    #[test]
+    pub fn test_is_number_part() {
+        let eq = "0xFF+10x+6.02e-23+5e".chars().collect::<EquationString>();
+        assert!(is_number_part(&eq, 1));
+        assert!(!is_number_part(&eq, 7));
+        assert!(is_number_part(&eq, 13));
+        assert!(!is_number_part(&eq, 19));
+    }
+
+    #[test]
+    pub fn test_last_number_start() {
+        let eq = "1+6.02e-23".chars().collect::<EquationString>();
+        assert_eq!(last_number_start(&eq), 2);
+        let eq = "1+x2e-3".chars().collect::<EquationString>();
+        assert_eq!(last_number_start(&eq), 6);
+        let eq = "2*5e3".chars().collect::<EquationString>();
+        assert_eq!(last_number_start(&eq), 2);
+        let eq = "12.5".chars().collect::<EquationString>();
+        assert_eq!(last_number_start(&eq), 0);
+    }
+}
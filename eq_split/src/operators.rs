@@ -1,7 +1,16 @@
+use std::cmp::Ordering;
+
 use crate::{
+    arithmetic::Number,
+    options::ParseOptions,
     precedences::{
-        all_matcher::AllMatcher, high_precedence::HighPrecedenceMatcher,
-        low_precedence::LowPrecedenceMatcher, medium_precedence::MediumPrecendenceMatcher,
+        all_matcher::AllMatcher,
+        bitwise_precedence::BITWISE_MATCHERS,
+        conversion_precedence::ConversionMatcher,
+        high_precedence::HighPrecedenceMatcher,
+        logical_precedence::{NotMatcher, COMPARISON_MATCHERS, LOGICAL_MATCHERS},
+        low_precedence::LowPrecedenceMatcher,
+        medium_precedence::MediumPrecendenceMatcher,
         traits::MatchOperator,
     },
     EquationString,
@@ -15,13 +24,13 @@ pub trait FindOperator {
         eq: EquationString,
         match_operator: M,
         start_from: Option<usize>,
-    ) -> Option<(usize, char)>;
+    ) -> Option<(usize, Operators)>;
 
     fn find_last<M: MatchOperator>(
         eq: EquationString,
         match_operator: M,
         start_from: Option<usize>,
-    ) -> Option<(usize, char)>;
+    ) -> Option<(usize, Operators)>;
 }
 
 impl FindOperator for OperatorFinder {
@@ -31,20 +40,13 @@ impl FindOperator for OperatorFinder {
         eq: EquationString,
         match_operator: M,
         start_from: Option<usize>,
-    ) -> Option<(usize, char)> {
-        let start_index = if let Some(i) = start_from {
-            i as usize
-        } else {
-            0 as usize
-        };
-
-        for (i, c) in eq[start_index..].iter().enumerate() {
-            if match_operator.match_operator(*c) {
-                return Some((i + start_index, *c));
-            }
-        }
+    ) -> Option<(usize, Operators)> {
+        let start_index = start_from.unwrap_or(0);
 
-        None
+        (start_index..eq.len()).find_map(|i| {
+            let len = match_operator.match_operator(&eq, i)?;
+            Some((i, Operators::to_enum(&eq[i..i + len])))
+        })
     }
 
     /// find the last occuring operator
@@ -53,60 +55,468 @@ impl FindOperator for OperatorFinder {
         eq: EquationString,
         match_operator: M,
         start_from: Option<usize>,
-    ) -> Option<(usize, char)> {
-        let start_index = if let Some(i) = start_from {
-            i as usize
-        } else {
-            0 as usize
-        };
-        let last_element_index = eq.len() - 1;
-        for (i, c) in eq[start_index..].iter().rev().enumerate() {
-            if match_operator.match_operator(*c) {
-                return Some((last_element_index - i, *c));
+    ) -> Option<(usize, Operators)> {
+        let mut last_operator: Option<(usize, Operators)> = None;
+        let mut i = start_from.unwrap_or(0);
+
+        // loop from the front so that an operator made of many chars is
+        // matched from its first char eg. the // in 5//2
+        while i < eq.len() {
+            if let Some(len) = match_operator.match_operator(&eq, i) {
+                last_operator = Some((i, Operators::to_enum(&eq[i..i + len])));
+                i += len;
+            } else {
+                i += 1;
             }
         }
-        None
+
+        last_operator
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Operators {
     Plus,
     Minus,
+    /// the interval around the left eg. 1.25 ± 0.05 = [1.2, 1.3]
+    /// it only works in the interval mode
+    PlusMinus,
     Mult,
     Div,
+    /// floored modulo, the result has the sign of the right eg. -7 % 3 = 2
+    Modulo,
+    /// division rounded down eg. -7 // 2 = -4
+    IntDiv,
+    /// truncated remainder, the result has the sign of the left eg. -7 rem 3 = -1
+    Rem,
     Exp,
+    /// the bitwise operators only work on whole numbers
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    /// the comparisons and the logical operators give true or false
+    /// which is 1 or 0 when calculated with numbers
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    And,
+    Or,
+    /// changes a value to another unit eg. 5 ft to m, written as to or in
+    /// it only works on the values with a unit
+    Convert,
     None,
 }
 
 impl Operators {
     /// convert operator to enum
-    pub fn to_enum(c: char) -> Self {
-        match c {
-            '+' => Self::Plus,
-            '-' => Self::Minus,
-            '*' => Self::Mult,
-            '/' => Self::Div,
-            '^' => Self::Exp,
+    pub fn to_enum(operator: &[char]) -> Self {
+        match operator.iter().collect::<String>().as_str() {
+            "+" => Self::Plus,
+            "-" => Self::Minus,
+            "±" => Self::PlusMinus,
+            "*" => Self::Mult,
+            "/" => Self::Div,
+            "%" => Self::Modulo,
+            "//" => Self::IntDiv,
+            "rem" => Self::Rem,
+            "^" => Self::Exp,
+            "&" => Self::BitAnd,
+            "|" => Self::BitOr,
+            "xor" => Self::BitXor,
+            "<<" => Self::ShiftLeft,
+            ">>" => Self::ShiftRight,
+            "<" => Self::Less,
+            "<=" => Self::LessEqual,
+            ">" => Self::Greater,
+            ">=" => Self::GreaterEqual,
+            "==" => Self::Equal,
+            "!=" => Self::NotEqual,
+            "&&" => Self::And,
+            "||" => Self::Or,
+            "to" | "in" => Self::Convert,
             _ => Self::None,
         }
     }
 
-    /// calculate the result of the operation
-    pub fn calculate(&self, left: f64, right: f64) -> f64 {
+    /// the operator as it is written in the eq
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Plus => "+",
+            Self::Minus => "-",
+            Self::PlusMinus => "±",
+            Self::Mult => "*",
+            Self::Div => "/",
+            Self::Modulo => "%",
+            Self::IntDiv => "//",
+            Self::Rem => "rem",
+            Self::Exp => "^",
+            Self::BitAnd => "&",
+            Self::BitOr => "|",
+            Self::BitXor => "xor",
+            Self::ShiftLeft => "<<",
+            Self::ShiftRight => ">>",
+            Self::Less => "<",
+            Self::LessEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterEqual => ">=",
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+            Self::And => "&&",
+            Self::Or => "||",
+            Self::Convert => "to",
+            Self::None => "",
+        }
+    }
+
+    /// the number of chars of the operator in the eq
+    pub fn symbol_len(&self) -> usize {
+        self.symbol().chars().count()
+    }
+
+    /// the operators that only work on whole numbers
+    pub fn is_bitwise(&self) -> bool {
+        matches!(
+            self,
+            Self::BitAnd | Self::BitOr | Self::BitXor | Self::ShiftLeft | Self::ShiftRight
+        )
+    }
+
+    /// the operators that compare two values eg. <, ==
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Self::Less
+                | Self::LessEqual
+                | Self::Greater
+                | Self::GreaterEqual
+                | Self::Equal
+                | Self::NotEqual
+        )
+    }
+
+    /// the operators that work on true and false
+    pub fn is_logical(&self) -> bool {
+        matches!(self, Self::And | Self::Or)
+    }
+
+    /// how tightly the operator holds its operands, the operators with a lower one are split first
+    /// eg. 1+2*3 is 1+(2*3) as + is lower than *, see 'split_by_precedence'
+    pub fn precedence(&self) -> u8 {
         match self {
+            Self::Convert => 0,
+            Self::Or => 1,
+            Self::And => 2,
+            // 3 is the precedence of not
+            Self::Equal | Self::NotEqual => 4,
+            Self::Less | Self::LessEqual | Self::Greater | Self::GreaterEqual => 5,
+            Self::BitOr => 6,
+            Self::BitXor => 7,
+            Self::BitAnd => 8,
+            Self::ShiftLeft | Self::ShiftRight => 9,
+            Self::Plus | Self::Minus | Self::PlusMinus => 10,
+            Self::Mult | Self::Div | Self::Modulo | Self::IntDiv | Self::Rem => 11,
+            // 12 is the precedence of the signs
+            Self::Exp => 13,
+            Self::None => u8::MAX,
+        }
+    }
+
+    /// compare the two values, None if this is not a comparison
+    /// the values that cannot be ordered are only not equal eg. NaN
+    pub fn compare<N: Number>(&self, left: &N, right: &N) -> Option<bool> {
+        let ordering = left.compare(right);
+        match self {
+            Self::Less => Some(ordering == Some(Ordering::Less)),
+            Self::LessEqual => Some(matches!(ordering, Some(Ordering::Less | Ordering::Equal))),
+            Self::Greater => Some(ordering == Some(Ordering::Greater)),
+            Self::GreaterEqual => Some(matches!(
+                ordering,
+                Some(Ordering::Greater | Ordering::Equal)
+            )),
+            Self::Equal => Some(ordering == Some(Ordering::Equal)),
+            Self::NotEqual => Some(ordering != Some(Ordering::Equal)),
+            _ => None,
+        }
+    }
+
+    /// check if the operation divides by zero eg. 1/0, 7 % 0, 0^-1
+    /// which has no result with the number types that have no infinity eg. fractions
+    pub fn divides_by_zero<N: Number>(&self, left: &N, right: &N) -> bool {
+        match self {
+            Self::Div | Self::Modulo | Self::IntDiv | Self::Rem => is_zero(right),
+            Self::Exp => is_zero(left) && is_negative(right),
+            _ => false,
+        }
+    }
+
+    /// calculate the result of the operation with the number type 'N'
+    /// the bitwise operators expect whole numbers, see 'is_bitwise'
+    /// true is 1 and false is 0 for the comparisons and the logical operators
+    /// None when the result is not a number of 'N' eg. 1/0 for fractions, 1 << 63
+    /// or for a conversion and ±, as a number has no unit and is not an interval
+    pub fn calculate<N: Number>(&self, left: N, right: N) -> Option<N> {
+        let result = match self {
             Self::Plus => left + right,
             Self::Minus => left - right,
+            Self::PlusMinus => return None,
             Self::Mult => left * right,
-            Self::Div => left / right,
-            Self::Exp => left.powf(right),
+            Self::Div => left.checked_div(right)?,
+            Self::Modulo => {
+                let remainder = left.checked_rem(right.clone())?;
+                if !is_zero(&remainder) && is_negative(&remainder) != is_negative(&right) {
+                    remainder + right
+                } else {
+                    remainder
+                }
+            }
+            Self::IntDiv => left.checked_div(right)?.checked_floor()?,
+            Self::Rem => left.checked_rem(right)?,
+            Self::Exp => left.checked_pow(right)?,
+            Self::BitAnd => N::from_whole(to_whole(&left) & to_whole(&right)),
+            Self::BitOr => N::from_whole(to_whole(&left) | to_whole(&right)),
+            Self::BitXor => N::from_whole(to_whole(&left) ^ to_whole(&right)),
+            Self::ShiftLeft => N::from_whole(shift_left(to_whole(&left), to_whole(&right))?),
+            Self::ShiftRight => N::from_whole(shift_left(
+                to_whole(&left),
+                to_whole(&right).saturating_neg(),
+            )?),
+            Self::Less
+            | Self::LessEqual
+            | Self::Greater
+            | Self::GreaterEqual
+            | Self::Equal
+            | Self::NotEqual => N::from_whole(i64::from(self.compare(&left, &right) == Some(true))),
+            Self::And => N::from_whole(i64::from(!is_zero(&left) && !is_zero(&right))),
+            Self::Or => N::from_whole(i64::from(!is_zero(&left) || !is_zero(&right))),
+            Self::Convert => return None,
             Self::None => left,
+        };
+        Some(result)
+    }
+}
+
+/// the signs written in front of a value eg. -x, +2, --(1+2), ~5, not x
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOperators {
+    Negate,
+    Plus,
+    /// flips all the bits of a whole number eg. ~5 = -6
+    BitNot,
+    /// flips true and false eg. not 1 > 2 = true
+    Not,
+}
+
+impl UnaryOperators {
+    /// convert sign to enum
+    pub fn to_enum(sign: char) -> Option<Self> {
+        match sign {
+            '-' => Some(Self::Negate),
+            '+' => Some(Self::Plus),
+            '~' => Some(Self::BitNot),
+            _ => None,
         }
     }
+
+    /// the operator at the start of the eq with its length
+    /// eg. (Negate, 1) for -x and (Not, 3) for not x
+    pub fn match_prefix(eq: &[char]) -> Option<(Self, usize)> {
+        if let Some(len) = NotMatcher.match_operator(eq, 0) {
+            return Some((Self::Not, len));
+        }
+
+        eq.first()
+            .and_then(|c| Self::to_enum(*c))
+            .map(|operator| (operator, 1))
+    }
+
+    /// the sign as it is written in the eq
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Negate => "-",
+            Self::Plus => "+",
+            Self::BitNot => "~",
+            Self::Not => "not",
+        }
+    }
+
+    /// the operators that only work on whole numbers
+    pub fn is_bitwise(&self) -> bool {
+        *self == Self::BitNot
+    }
+
+    /// not is applied after the comparisons eg. not 1>2 = not (1>2)
+    /// and the signs after ^ eg. -3^2 = -(3^2), see 'Operators::precedence'
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Self::Not => 3,
+            _ => 12,
+        }
+    }
+
+    /// calculate the result of the operation with the number type 'N'
+    /// the bitwise operators expect whole numbers, see 'is_bitwise'
+    /// true is 1 and false is 0 for not
+    pub fn calculate<N: Number>(&self, value: N) -> N {
+        match self {
+            Self::Negate => value.negate(),
+            Self::Plus => value,
+            Self::BitNot => N::from_whole(!to_whole(&value)),
+            Self::Not => N::from_whole(i64::from(is_zero(&value))),
+        }
+    }
+}
+
+/// the whole number the bitwise operators work on, the fraction is dropped eg. 5.0 = 5
+fn to_whole<N: Number>(value: &N) -> i64 {
+    value.to_float() as i64
+}
+
+fn is_zero<N: Number>(value: &N) -> bool {
+    value.compare(&N::from_whole(0)) == Some(Ordering::Equal)
+}
+
+fn is_negative<N: Number>(value: &N) -> bool {
+    value.compare(&N::from_whole(0)) == Some(Ordering::Less)
+}
+
+/// shift the bits of 'value' to the left by 'shift', a negative 'shift' moves them to the right
+/// None when a bit shifted to the left does not fit in an i64 eg. 1 << 63, 1 << 64
+/// the bits shifted out to the right are lost eg. 1 >> 1 = 0
+pub fn shift_left(value: i64, shift: i64) -> Option<i64> {
+    let amount = u32::try_from(shift.unsigned_abs()).unwrap_or(u32::MAX);
+    if shift < 0 {
+        // the sign is kept when shifting to the right eg. -8 >> 1 = -4
+        return Some(
+            value
+                .checked_shr(amount)
+                .unwrap_or(if value < 0 { -1 } else { 0 }),
+        );
+    }
+
+    if value == 0 {
+        return Some(0);
+    }
+    let result = value.checked_shl(amount)?;
+    (result >> amount == value).then_some(result)
+}
+
+/// the operators written after a value eg. 5!, 12.5%
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PostfixOperators {
+    /// extended to non-integers with the gamma function eg. 0.5! = 0.886...
+    Factorial,
+    Percent,
+}
+
+impl PostfixOperators {
+    /// convert operator to enum
+    pub fn to_enum(operator: char) -> Option<Self> {
+        match operator {
+            '!' => Some(Self::Factorial),
+            '%' => Some(Self::Percent),
+            _ => None,
+        }
+    }
+
+    /// the operator as it is written in the eq
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Factorial => "!",
+            Self::Percent => "%",
+        }
+    }
+
+    /// the postfix operators hold their value tighter than any other operator eg. 2^3! = 2^(3!)
+    pub fn precedence(&self) -> u8 {
+        14
+    }
+
+    /// calculate the result of the operation with the number type 'N'
+    /// None when the result is not a number of 'N'
+    pub fn calculate<N: Number>(&self, value: N) -> Option<N> {
+        match self {
+            Self::Factorial => value.factorial(),
+            Self::Percent => value.checked_div(N::from_whole(100)),
+        }
+    }
+}
+
+/// the product 1*2*...*n for whole numbers so that the result is exact
+/// and gamma(n+1) for the other numbers
+pub(crate) fn factorial(value: f64) -> f64 {
+    if value >= 0.0 && value.fract() == 0.0 {
+        return (1..=value.min(171.0) as u32).fold(1.0, |product, i| product * i as f64);
+    }
+
+    gamma(value + 1.0)
+}
+
+/// the Lanczos approximation of the gamma function
+/// the poles at 0, -1, -2, ... give NaN
+fn gamma(value: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if value <= 0.0 && value.fract() == 0.0 {
+        return f64::NAN;
+    }
+
+    if value < 0.5 {
+        // reflection formula for the left half of the numbers
+        return std::f64::consts::PI / ((std::f64::consts::PI * value).sin() * gamma(1.0 - value));
+    }
+
+    let value = value - 1.0;
+    let t = value + G + 0.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, coefficient)| {
+            sum + coefficient / (value + i as f64 + 1.0)
+        });
+
+    (2.0 * std::f64::consts::PI).sqrt() * t.powf(value + 0.5) * (-t).exp() * series
+}
+
+/// check if the whole 'word' is an operator eg. rem
+/// so that it is not mistaken for a name
+pub fn is_word_operator(word: &[char]) -> bool {
+    !word.is_empty() && AllMatcher.match_operator(word, 0) == Some(word.len())
+}
+
+/// split the eq at the last operator of the 'matcher' precedence
+/// an operator at the start is not split eg. |1 is a single value
+fn split_at_last<M: MatchOperator>(
+    eq: &[char],
+    matcher: M,
+) -> Option<(EquationString, EquationString, Operators)> {
+    let (index, operator) = OperatorFinder::find_last(eq.to_vec(), matcher, None)?;
+
+    if index == 0 {
+        return Some((eq.to_vec(), Vec::new(), Operators::None));
+    }
+
+    let right = eq[index + operator.symbol_len()..].to_vec();
+    Some((eq[..index].to_vec(), right, operator))
 }
 
 pub trait SplitOperator {
-    fn split_by_precedence(&self) -> (Self, Self, Operators)
+    fn split_by_precedence(&self, options: ParseOptions) -> (Self, Self, Operators)
     where
         Self: Sized;
 }
@@ -114,42 +524,65 @@ pub trait SplitOperator {
 impl SplitOperator for EquationString {
     /// split the equation into two halfs according to precedence
     /// this assumes that the equation doesnt have parentheses
-    fn split_by_precedence(&self) -> (Self, Self, Operators) {
+    fn split_by_precedence(&self, options: ParseOptions) -> (Self, Self, Operators) {
+        // match for to and in, the conversion of the whole value eg. 1 m + 20 cm to inch
+        if let Some(split) = split_at_last(self, ConversionMatcher) {
+            return split;
+        }
+
+        // match for || and then &&
+        // the last one is split so that 1||2||3 is (1||2)||3
+        for matcher in LOGICAL_MATCHERS {
+            if let Some(split) = split_at_last(self, matcher) {
+                return split;
+            }
+        }
+
+        // not is applied after the comparisons eg. not 1>2 = not (1>2)
+        // so this is a single value with the not handled by the callee
+        if NotMatcher.match_operator(self, 0).is_some() {
+            return (self.to_vec(), Vec::new(), Operators::None);
+        }
+
+        // match for == and != and then <, <=, > and >=
+        for matcher in COMPARISON_MATCHERS {
+            if let Some(split) = split_at_last(self, matcher) {
+                return split;
+            }
+        }
+
+        // match for |, xor, &, << and >> from the lowest precedence
+        for matcher in BITWISE_MATCHERS {
+            if let Some(split) = split_at_last(self, matcher) {
+                return split;
+            }
+        }
+
         // loop from front for + and -
+        // the last one is split so that 1-2+3 is (1-2)+3
+        // a + or - right after another operator is a sign eg. 1*-2
         {
-            let mut left: Vec<char> = Vec::new();
-            let mut right = self.to_vec();
-
             let all_matcher = AllMatcher;
             let matcher = LowPrecedenceMatcher;
-
-            loop {
-                if right.len() == 0 {
-                    break;
+            let mut start_from = 1;
+            let mut last_operator: Option<(usize, Operators)> = None;
+
+            while let Some((index, operator)) =
+                OperatorFinder::find_first(self.to_vec(), matcher, Some(start_from))
+            {
+                if !all_matcher.match_operator_before(self, index) {
+                    last_operator = Some((index, operator));
                 }
+                start_from = index + 1;
+            }
 
-                let found_operator_res =
-                    OperatorFinder::find_first(right.to_vec(), matcher, Some(1));
-
-                if let Some((index, operator)) = found_operator_res {
-                    let left_char = right[index - 1];
-                    let is_left_an_operator = all_matcher.match_operator(left_char);
-                    if !is_left_an_operator {
-                        left.append(&mut right[..index].to_vec());
-
-                        let operator = Operators::to_enum(operator);
-                        return (left, right[index + 1..].to_vec(), operator);
-                    } else {
-                        left.append(&mut right[..index].to_vec());
-                        right = right[index..].to_vec();
-                    }
-                } else {
-                    break;
-                }
+            if let Some((index, operator)) = last_operator {
+                let right = self[index + operator.symbol_len()..].to_vec();
+                return (self[..index].to_vec(), right, operator);
             }
         }
 
-        // match for * and /
+        // match for *, /, //, % and rem
         {
             let eq = self.to_vec();
 
@@ -158,27 +591,42 @@ impl SplitOperator for EquationString {
 
             if let Some((index, operator)) = found_operator_res {
                 if index == 0 {
-                    return (eq.clone(), Vec::new() , Operators::None);
+                    return (eq.clone(), Vec::new(), Operators::None);
                 }
 
-                let operator = Operators::to_enum(operator);
-                return (eq[..index].to_vec(), eq[index + 1..].to_vec(), operator);
+                let right = eq[index + operator.symbol_len()..].to_vec();
+                return (eq[..index].to_vec(), right, operator);
             }
         }
 
+        // the sign is applied after the ^ eg. -3^2 = -(3^2)
+        // so this is a single value with the signs handled by the callee
+        let starts_with_sign = self
+            .first()
+            .is_some_and(|c| UnaryOperators::to_enum(*c).is_some());
+        if options.exp_before_sign && starts_with_sign {
+            return (self.to_vec(), Vec::new(), Operators::None);
+        }
+
         // match for ^
+        // the first one is split when ^ is right associative eg. 2^3^2 = 2^(3^2)
         {
             let eq = self.to_vec();
 
             let matcher = HighPrecedenceMatcher;
-            let found_operator_res = OperatorFinder::find_last(eq.to_vec(), matcher, None);
+            let found_operator_res = if options.right_associative_exp {
+                OperatorFinder::find_first(eq.to_vec(), matcher, None)
+            } else {
+                OperatorFinder::find_last(eq.to_vec(), matcher, None)
+            };
 
             if let Some((index, operator)) = found_operator_res {
                 if index == 0 {
-                    return (eq.clone(), Vec::new() , Operators::None);
+                    return (eq.clone(), Vec::new(), Operators::None);
                 }
-                let operator = Operators::to_enum(operator);
-                return (eq[..index].to_vec(), eq[index + 1..].to_vec(), operator);
+
+                let right = eq[index + operator.symbol_len()..].to_vec();
+                return (eq[..index].to_vec(), right, operator);
             }
         }
 
@@ -210,19 +658,19 @@ mod tests {
         let eq = "123456789+1*1-1/1";
         let eq = eq.chars().collect::<EquationString>();
         let (index, operator) = OperatorFinder::find_first(eq.clone(), all_operator, None).unwrap();
-        assert_eq!((index, operator), (9, '+'));
+        assert_eq!((index, operator), (9, Operators::Plus));
 
         let (index, operator) =
             OperatorFinder::find_first(eq.clone(), low_precedence, None).unwrap();
-        assert_eq!((index, operator), (9, '+'));
+        assert_eq!((index, operator), (9, Operators::Plus));
 
         let (index, operator) = OperatorFinder::find_first(eq, medium_precedence, None).unwrap();
-        assert_eq!((index, operator), (11, '*'));
+        assert_eq!((index, operator), (11, Operators::Mult));
 
         let eq = "123456789+1*1-1/1^234";
         let eq = eq.chars().collect::<EquationString>();
         let (index, operator) = OperatorFinder::find_first(eq, high_precedence, None).unwrap();
-        assert_eq!((index, operator), (17, '^'));
+        assert_eq!((index, operator), (17, Operators::Exp));
 
         let eq = "13434323";
         let eq = eq.chars().collect::<EquationString>();
@@ -236,12 +684,12 @@ mod tests {
         let eq = "123456789+1*1-1/1";
         let eq = eq.chars().collect::<EquationString>();
         let (index, operator) = OperatorFinder::find_first(eq, all_operator, Some(1)).unwrap();
-        assert_eq!((index, operator), (9, '+'));
+        assert_eq!((index, operator), (9, Operators::Plus));
 
         let eq = "-123456789+1*1-1/1";
         let eq = eq.chars().collect::<EquationString>();
         let (index, operator) = OperatorFinder::find_first(eq, all_operator, Some(1)).unwrap();
-        assert_eq!((index, operator), (10, '+'));
+        assert_eq!((index, operator), (10, Operators::Plus));
 
         let eq = "-123456789";
         let eq = eq.chars().collect::<EquationString>();
@@ -255,51 +703,158 @@ mod tests {
         let eq = "5/2*3";
         let eq = eq.chars().collect::<EquationString>();
         let (index, operator) = OperatorFinder::find_last(eq, medium_precedence, None).unwrap();
-        assert_eq!((index, operator), (3, '*'));
+        assert_eq!((index, operator), (3, Operators::Mult));
+
+        let eq = "5*2//3";
+        let eq = eq.chars().collect::<EquationString>();
+        let (index, operator) = OperatorFinder::find_last(eq, medium_precedence, None).unwrap();
+        assert_eq!((index, operator), (3, Operators::IntDiv));
+
+        let eq = "5%2 rem 3";
+        let eq = eq.chars().collect::<EquationString>();
+        let (index, operator) = OperatorFinder::find_last(eq, medium_precedence, None).unwrap();
+        assert_eq!((index, operator), (4, Operators::Rem));
+
+        let eq = "5*premium";
+        let eq = eq.chars().collect::<EquationString>();
+        let (index, operator) = OperatorFinder::find_last(eq, medium_precedence, None).unwrap();
+        assert_eq!((index, operator), (1, Operators::Mult));
     }
 
     #[test]
     pub fn test_split_by_precedence() {
         let eq = "1234";
         let eq = EquationString::remove_whitespaces(eq);
-        let (left, right, operator) = eq.split_by_precedence();
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
         assert_eq!(left.to_string(), "1234");
         assert_eq!(right.to_string(), "");
         assert_eq!(operator, Operators::None);
 
         let eq = "-1234";
         let eq = EquationString::remove_whitespaces(eq);
-        let (left, right, operator) = eq.split_by_precedence();
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
         assert_eq!(left.to_string(), "-1234");
         assert_eq!(right.to_string(), "");
         assert_eq!(operator, Operators::None);
 
         let eq = "-1234+134-+2";
         let eq = EquationString::remove_whitespaces(eq);
-        let (left, right, operator) = eq.split_by_precedence();
-        assert_eq!(left.to_string(), "-1234");
-        assert_eq!(right.to_string(), "134-+2");
-        assert_eq!(operator, Operators::Plus);
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
+        assert_eq!(left.to_string(), "-1234+134");
+        assert_eq!(right.to_string(), "+2");
+        assert_eq!(operator, Operators::Minus);
 
         let eq = "134-+2";
         let eq = EquationString::remove_whitespaces(eq);
-        let (left, right, operator) = eq.split_by_precedence();
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
         assert_eq!(left.to_string(), "134");
         assert_eq!(right.to_string(), "+2");
         assert_eq!(operator, Operators::Minus);
 
         let eq = "-134*+2";
         let eq = EquationString::remove_whitespaces(eq);
-        let (left, right, operator) = eq.split_by_precedence();
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
         assert_eq!(left.to_string(), "-134");
         assert_eq!(right.to_string(), "+2");
         assert_eq!(operator, Operators::Mult);
 
         let eq = "-3^2";
         let eq = EquationString::remove_whitespaces(eq);
-        let (left, right, operator) = eq.split_by_precedence();
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
+        assert_eq!(left.to_string(), "-3^2");
+        assert_eq!(right.to_string(), "");
+        assert_eq!(operator, Operators::None);
+
+        let eq = "-3^2";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::legacy());
         assert_eq!(left.to_string(), "-3");
         assert_eq!(right.to_string(), "2");
         assert_eq!(operator, Operators::Exp);
+
+        let eq = "1-2+3";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
+        assert_eq!(left.to_string(), "1-2");
+        assert_eq!(right.to_string(), "3");
+        assert_eq!(operator, Operators::Plus);
+
+        let eq = "2^3^2";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
+        assert_eq!(left.to_string(), "2");
+        assert_eq!(right.to_string(), "3^2");
+        assert_eq!(operator, Operators::Exp);
+
+        let eq = "2^3^2";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::legacy());
+        assert_eq!(left.to_string(), "2^3");
+        assert_eq!(right.to_string(), "2");
+        assert_eq!(operator, Operators::Exp);
+    }
+    #[test]
+    pub fn test_split_by_bitwise_precedence() {
+        let eq = "1|2 xor 3&4<<1";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
+        assert_eq!(left.to_string(), "1");
+        assert_eq!(right.to_string(), "2 xor 3&4<<1");
+        assert_eq!(operator, Operators::BitOr);
+
+        let eq = "2 xor 3&4<<1+1";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
+        assert_eq!(left.to_string(), "2 ");
+        assert_eq!(right.to_string(), " 3&4<<1+1");
+        assert_eq!(operator, Operators::BitXor);
+
+        let eq = "4<<1+1>>-2";
+        let eq = EquationString::remove_whitespaces(eq);
+        let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
+        assert_eq!(left.to_string(), "4<<1+1");
+        assert_eq!(right.to_string(), "-2");
+        assert_eq!(operator, Operators::ShiftRight);
+    }
+
+    #[test]
+    pub fn test_split_by_logical_precedence() {
+        let cases = [
+            ("x<1&&y>=2||z", "x<1&&y>=2", "z", Operators::Or),
+            ("x<1&&not y>=2", "x<1", "not y>=2", Operators::And),
+            ("not y>=2", "not y>=2", "", Operators::None),
+            ("1<2!=3", "1<2", "3", Operators::NotEqual),
+            ("1!=2<3", "1", "2<3", Operators::NotEqual),
+            ("1<2<3", "1<2", "3", Operators::Less),
+            ("5!==120", "5!", "120", Operators::Equal),
+            ("1<<2<=3|4", "1<<2", "3|4", Operators::LessEqual),
+            ("x>-1", "x", "-1", Operators::Greater),
+        ];
+
+        for (eq, left_after, right_after, operator_after) in cases {
+            let eq = EquationString::remove_whitespaces(eq);
+            let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
+            assert_eq!(left.to_string(), left_after);
+            assert_eq!(right.to_string(), right_after);
+            assert_eq!(operator, operator_after);
+        }
+    }
+
+    #[test]
+    pub fn test_split_by_conversion_precedence() {
+        let cases = [
+            ("5 ft to m", "5ft ", " m", Operators::Convert),
+            ("1 m + 2 cm in mm", "1m+2cm ", " mm", Operators::Convert),
+            ("x || y to m/s", "x||y ", " m/s", Operators::Convert),
+            ("tons + into", "tons", "into", Operators::Plus),
+        ];
+
+        for (eq, left_after, right_after, operator_after) in cases {
+            let eq = EquationString::remove_whitespaces(eq);
+            let (left, right, operator) = eq.split_by_precedence(ParseOptions::default());
+            assert_eq!(left.to_string(), left_after);
+            assert_eq!(right.to_string(), right_after);
+            assert_eq!(operator, operator_after);
+        }
     }
 }
@@ -0,0 +1,29 @@
+/// options that change how an equation is split into operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// 2^3^2 is 2^(3^2) instead of (2^3)^2
+    pub right_associative_exp: bool,
+    /// -3^2 is -(3^2) instead of (-3)^2
+    pub exp_before_sign: bool,
+}
+
+impl ParseOptions {
+    /// the semantics of the earlier versions of the calculator
+    /// where 2^3^2 = (2^3)^2 and -3^2 = (-3)^2
+    pub fn legacy() -> Self {
+        Self {
+            right_associative_exp: false,
+            exp_before_sign: false,
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    /// the usual math conventions where 2^3^2 = 2^(3^2) and -3^2 = -(3^2)
+    fn default() -> Self {
+        Self {
+            right_associative_exp: true,
+            exp_before_sign: true,
+        }
+    }
+}
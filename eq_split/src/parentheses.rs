@@ -1,7 +1,15 @@
 use crate::{
+    error::{EquationError, Span},
+    operators::UnaryOperators,
+    options::ParseOptions,
     precedences::{
-        all_matcher::AllMatcher, high_precedence::HighPrecedenceMatcher,
-        low_precedence::LowPrecedenceMatcher, medium_precedence::MediumPrecendenceMatcher,
+        all_matcher::{AllMatcher, MAX_OPERATOR_LEN},
+        bitwise_precedence::BITWISE_MATCHERS,
+        conversion_precedence::ConversionMatcher,
+        high_precedence::HighPrecedenceMatcher,
+        logical_precedence::{NotMatcher, COMPARISON_MATCHERS, LOGICAL_MATCHERS},
+        low_precedence::LowPrecedenceMatcher,
+        medium_precedence::MediumPrecendenceMatcher,
         traits::MatchOperator,
     },
     EquationString,
@@ -11,39 +19,40 @@ use crate::{
 pub struct ParenthesesFinder {}
 
 pub trait FindParentheses {
-    fn find_first(eq: EquationString) -> Result<Option<(usize, usize)>, String>;
-    fn find_last(eq: EquationString) -> Result<Option<(usize, usize)>, String>;
+    fn find_first(eq: EquationString) -> Result<Option<(usize, usize)>, EquationError>;
+    fn find_last(eq: EquationString) -> Result<Option<(usize, usize)>, EquationError>;
 }
 
 impl FindParentheses for ParenthesesFinder {
-    fn find_first(eq: crate::EquationString) -> Result<Option<(usize, usize)>, String> {
-        let mut opened_parentheses = 0;
+    fn find_first(eq: crate::EquationString) -> Result<Option<(usize, usize)>, EquationError> {
+        let mut opened_parentheses: Vec<usize> = Vec::new();
         let mut left_parentheses_index: Option<usize> = None;
         let mut right_parentheses_index: Option<usize> = None;
 
         for (i, c) in eq.iter().enumerate() {
             if *c == '(' {
-                opened_parentheses = opened_parentheses + 1;
-                if left_parentheses_index == None {
+                opened_parentheses.push(i);
+                if left_parentheses_index.is_none() {
                     left_parentheses_index = Some(i)
                 }
             } else if *c == ')' {
-                if opened_parentheses == 0 {
-                    return Err(String::from("Close bracket found without open brackets"));
+                if opened_parentheses.pop().is_none() {
+                    // close bracket found without open brackets
+                    return Err(EquationError::UnbalancedParenthesis(Span::at(i)));
                 }
-                opened_parentheses = opened_parentheses - 1;
 
-                if right_parentheses_index.is_none() && opened_parentheses == 0 {
+                if right_parentheses_index.is_none() && opened_parentheses.is_empty() {
                     right_parentheses_index = Some(i);
                 }
             }
         }
 
-        if opened_parentheses > 0 {
-            return Err(String::from("Open bracket not closed"));
+        if let Some(index) = opened_parentheses.first() {
+            // open bracket not closed
+            return Err(EquationError::UnbalancedParenthesis(Span::at(*index)));
         }
 
-        if left_parentheses_index == None && right_parentheses_index == None {
+        if left_parentheses_index.is_none() && right_parentheses_index.is_none() {
             return Ok(None);
         }
 
@@ -53,36 +62,37 @@ impl FindParentheses for ParenthesesFinder {
         )))
     }
 
-    fn find_last(eq: crate::EquationString) -> Result<Option<(usize, usize)>, String> {
-        let mut closed_parentheses = 0;
+    fn find_last(eq: crate::EquationString) -> Result<Option<(usize, usize)>, EquationError> {
+        let mut closed_parentheses: Vec<usize> = Vec::new();
         let mut left_parentheses_index: Option<usize> = None;
         let mut right_parentheses_index: Option<usize> = None;
 
-        let last_index = eq.len() - 1;
+        let last_index = eq.len().saturating_sub(1);
 
         for (i, c) in eq.iter().rev().enumerate() {
             if *c == ')' {
-                closed_parentheses = closed_parentheses + 1;
-                if right_parentheses_index == None {
+                closed_parentheses.push(last_index - i);
+                if right_parentheses_index.is_none() {
                     right_parentheses_index = Some(last_index - i)
                 }
             } else if *c == '(' {
-                if closed_parentheses == 0 {
-                    return Err(String::from("Close bracket found without open brackets"));
+                if closed_parentheses.pop().is_none() {
+                    // open bracket found without close brackets
+                    return Err(EquationError::UnbalancedParenthesis(Span::at(last_index - i)));
                 }
-                closed_parentheses = closed_parentheses - 1;
 
-                if left_parentheses_index.is_none() && closed_parentheses == 0 {
+                if left_parentheses_index.is_none() && closed_parentheses.is_empty() {
                     left_parentheses_index = Some(last_index - i);
                 }
             }
         }
 
-        if closed_parentheses > 0 {
-            return Err(String::from("Open bracket not closed"));
+        if let Some(index) = closed_parentheses.first() {
+            // close bracket not opened
+            return Err(EquationError::UnbalancedParenthesis(Span::at(*index)));
         }
 
-        if left_parentheses_index == None && right_parentheses_index == None {
+        if left_parentheses_index.is_none() && right_parentheses_index.is_none() {
             return Ok(None);
         }
 
@@ -98,6 +108,7 @@ pub trait SplitParentheses {
         &self,
         left_parentheses_index: usize,
         right_parentheses_index: usize,
+        options: ParseOptions,
     ) -> (Self, Self)
     where
         Self: Sized;
@@ -108,6 +119,7 @@ impl SplitParentheses for EquationString {
         &self,
         left_parentheses_index: usize,
         right_parentheses_index: usize,
+        options: ParseOptions,
     ) -> (Self, Self) {
 
         #[allow(unused_assignments)]
@@ -131,282 +143,207 @@ impl SplitParentheses for EquationString {
         }
 
         // regroup according to precedence
-        regroup_according_to_precedence(left, right)
+        regroup_according_to_precedence(left, right, options)
     }
 }
 
 /// split the eq that contains parentheses into
 /// a left half and a right half
+/// the operator to split at can be in either of them eg. 1|(2)+3, (1+3)*3+5
 fn regroup_according_to_precedence(
     left: EquationString,
     right: EquationString,
+    options: ParseOptions,
 ) -> (EquationString, EquationString) {
-    // if right is not empty, we will gather all the higher precedence and
-    // put it to the left
-    let mut left = left;
-    let mut right = right;
+    let mut eq = left;
+    eq.extend_from_slice(&right);
 
-    if !right.is_empty() {
-        (left, right) = regroup_to_left(left.clone(), right.clone());
-    }
-
-    // then check if right is empty again, as the operation before has regroup
-    // some of right to left
-    // if so, we split the left and fill in the right
-
-    if right.is_empty() {
-        (left, right) = regroup_to_right(left.clone());
-    }
-
-    (left, right)
+    regroup_to_right(eq, options)
 }
 
-/// group the operator of the same precedence to the left
-/// for this case, it is assumed that both left and right are not empty
-/// checking has been done prior to calling this function
-fn regroup_to_left(
+/// splitting left to right according to precedence
+/// right is assumed to be empty
+fn regroup_to_right(
     left: EquationString,
-    right: EquationString,
+    options: ParseOptions,
 ) -> (EquationString, EquationString) {
-    let all_matcher = AllMatcher {};
-    let low_precedence_matcher = LowPrecedenceMatcher {};
-
-    // first, check for all + and - and split it
-    // check from front and move the front to the left
-    // to treat items in the parentheses as a single entity
-    // if split occur return
-
-    {
-        let mut left_after = left.clone();
-        let mut right_after = right.clone();
-        let mut previous_char: Option<char> = None;
-        loop {
-            if right_after.len() == 0 {
-                break;
-            }
-
-            let current_char = right_after[0];
-            let is_low_precedence_operator = low_precedence_matcher.match_operator(current_char);
+    // to and in, the conversion of the whole value eg. max(1,2) m to cm
+    if let Some(index) = find_last_operator(&left, ConversionMatcher) {
+        return (left[..index].to_vec(), left[index..].to_vec());
+    }
 
-            // check if this is a negative or positive number
-            // we check if the previous char is None, or an operator, if so this is just a sign for -ve or +ve
-            if is_low_precedence_operator {
-                if previous_char.is_some() && all_matcher.match_operator(previous_char.unwrap()) {
-                    // just a -ve or +ve sign
-                    // we update the previous_char, and move this char to the left
-                    previous_char = Some(current_char);
-                    left_after.push(current_char);
-                    right_after = right_after[1..].to_vec();
-                } else {
-                    // found it, we split here
-                    return (left_after, right_after);
-                }
-            } else {
-                // check if it is a parentheses
-                // if so we find the end of the parenthese and move the entire block to the left
-                // else, we update the previous_char, and move this char to the left
-                if current_char == '(' {
-                    let (left_parentheses_index, right_parentheses_index) =
-                        ParenthesesFinder::find_first(right_after.clone())
-                            .unwrap()
-                            .unwrap();
-
-                    previous_char = Some(right_after[right_parentheses_index]);
-                    left_after.append(
-                        &mut right_after[left_parentheses_index..right_parentheses_index + 1]
-                            .to_vec(),
-                    );
-                    right_after = right_after[right_parentheses_index + 1..].to_vec();
-                } else {
-                    previous_char = Some(current_char);
-                    left_after.push(current_char);
-                    right_after = right_after[1..].to_vec();
-                }
-            }
-        }
+    // || and then && treating parentheses as a unit
+    let logical_index = LOGICAL_MATCHERS
+        .iter()
+        .find_map(|matcher| find_last_operator(&left, matcher));
+    if let Some(index) = logical_index {
+        return (left[..index].to_vec(), left[index..].to_vec());
     }
 
-    // whatever that is left has to be *,/ or ^
-    // we just group everything to the left
+    // not is applied after the comparisons eg. not (1)>2 = not ((1)>2)
+    if NotMatcher.match_operator(&left, 0).is_some() {
+        return (left, EquationString::new());
+    }
 
-    let mut left_after = left.clone();
-    let mut right_after = right.clone();
-    left_after.append(&mut right_after);
-    (left_after, EquationString::new())
-}
+    // == and != and then <, <=, > and >=
+    let comparison_index = COMPARISON_MATCHERS
+        .iter()
+        .find_map(|matcher| find_last_operator(&left, matcher));
+    if let Some(index) = comparison_index {
+        return (left[..index].to_vec(), left[index..].to_vec());
+    }
 
-/// splitting left to right according to precedence
-/// right is assumed to be empty
-fn regroup_to_right(left: EquationString) -> (EquationString, EquationString) {
-    let all_matcher = AllMatcher {};
-    let low_precedence_matcher = LowPrecedenceMatcher {};
-    let medium_precedence_matcher = MediumPrecendenceMatcher {};
-    let high_precedence_matcher = HighPrecedenceMatcher {};
+    // the bitwise operators from |, the lowest precedence, to << and >>
+    if let Some(index) = find_last_bitwise(&left) {
+        return (left[..index].to_vec(), left[index..].to_vec());
+    }
 
-    // we will first loop from left to right to check for + and -
+    // we will then loop from left to right to check for + and -
     // checking for negatives and treating parentheses as a unit
     // if we are able to split, we return
 
-    {
-        let mut left_after: EquationString = Vec::new();
-        let mut right_after = left.clone();
-        let mut previous_char: Option<char> = None;
+    // the signs at the start of the eq are not split eg. --2*(3)
+    if let Some(index) = find_last_low_precedence(&left, 0, true) {
+        return (left[..index].to_vec(), left[index..].to_vec());
+    }
 
-        // we can ignore the first char as it could be -ve or +ve or number
-        // it doesnt matter to us
-        let first_char = right_after[0];
+    // loop from right to left to check for *, /, //, % and rem
+    // treating parentheses as a unit
+    // if we are able to split, we return
+    if let Some(index) = find_last_operator(&left, MediumPrecendenceMatcher) {
+        return (left[..index].to_vec(), left[index..].to_vec());
+    }
 
-        if first_char != '(' {
-            left_after.push(first_char);
-            right_after = right_after[1..].to_vec();
-        }
+    // the sign is applied after the ^ eg. -(3)^2 = -((3)^2)
+    // so this is a single value with the signs handled by the callee
+    if options.exp_before_sign && UnaryOperators::to_enum(left[0]).is_some() {
+        return (left, EquationString::new());
+    }
 
-        loop {
-            if right_after.len() == 0 {
-                break;
-            }
+    // check for ^ treating parentheses as a unit
+    // from left to right when ^ is right associative eg. 2^(3)^2 = 2^((3)^2)
+    // else from right to left
+    //if we are able to split, we return
+    let high_precedence_index = if options.right_associative_exp {
+        find_first_operator(&left, HighPrecedenceMatcher)
+    } else {
+        find_last_operator(&left, HighPrecedenceMatcher)
+    };
+    if let Some(index) = high_precedence_index {
+        return (left[..index].to_vec(), left[index..].to_vec());
+    }
 
-            let current_char = right_after[0];
-            let is_low_precedence_operator = low_precedence_matcher.match_operator(current_char);
+    // no operator found, this is a single value eg. sin(2)
+    (left, EquationString::new())
+}
 
+/// find the last + or - from 'start' that is not a sign
+/// 'previous_is_operator' tells if there is an operator right before 'start'
+/// the parentheses are skipped as a single entity
+fn find_last_low_precedence(
+    eq: &[char],
+    start: usize,
+    previous_is_operator: bool,
+) -> Option<usize> {
+    let all_matcher = AllMatcher;
+    let low_precedence_matcher = LowPrecedenceMatcher;
+
+    let mut previous_is_operator = previous_is_operator;
+    let mut last_index = None;
+    let mut index = start;
+
+    while index < eq.len() {
+        if eq[index] == '~' {
+            // the bitwise not is a sign eg. 1-~-2
+            previous_is_operator = true;
+            index += 1;
+        } else if low_precedence_matcher.match_operator(eq, index).is_some() {
             // check if this is a negative or positive number
-            // we check if the previous char is None, or an operator, if so this is just a sign for -ve or +ve
-            if is_low_precedence_operator {
-                if previous_char.is_some() && all_matcher.match_operator(previous_char.unwrap()) {
-                    // just a -ve or +ve sign
-                    // we update the previous_char, and move this char to the left
-                    previous_char = Some(current_char);
-                    left_after.push(current_char);
-                    right_after = right_after[1..].to_vec();
-                } else {
-                    // found it, we split here
-                    return (left_after, right_after);
-                }
-            } else {
-                // check if it is a parentheses
-                // if so we find the end of the parenthese and move the entire block to the left
-                // else, we update the previous_char, and move this char to the left
-                if current_char == '(' {
-                    let (left_parentheses_index, right_parentheses_index) =
-                        ParenthesesFinder::find_first(right_after.clone())
-                            .unwrap()
-                            .unwrap();
-
-                    previous_char = Some(right_after[right_parentheses_index]);
-                    left_after.append(
-                        &mut right_after[left_parentheses_index..right_parentheses_index + 1]
-                            .to_vec(),
-                    );
-                    right_after = right_after[right_parentheses_index + 1..].to_vec();
-                } else {
-                    previous_char = Some(current_char);
-                    left_after.push(current_char);
-                    right_after = right_after[1..].to_vec();
-                }
+            // if the previous is an operator, this is just a sign for -ve or +ve
+            if !previous_is_operator {
+                last_index = Some(index);
             }
+            previous_is_operator = true;
+            index += 1;
+        } else if eq[index] == '(' {
+            // skip the entire parentheses block
+            let right_parentheses_index = skip_parentheses(&eq[index..]);
+
+            previous_is_operator = false;
+            index += right_parentheses_index + 1;
+        } else if let Some(len) = all_matcher.match_operator(eq, index) {
+            previous_is_operator = true;
+            index += len;
+        } else {
+            previous_is_operator = false;
+            index += 1;
         }
     }
 
-    // loop from right to left to check for * and /
-    // treating parentheses as a unit
-    // if we are able to split, we return
+    last_index
+}
+
+/// find the start of the last bitwise operator of the lowest precedence
+/// eg. the | in 1|(2)&3|4&5
+fn find_last_bitwise(eq: &[char]) -> Option<usize> {
+    BITWISE_MATCHERS
+        .iter()
+        .find_map(|matcher| find_last_operator(eq, matcher))
+}
 
-    {
-        let mut left_after = left.clone();
-        let mut right_after: EquationString = Vec::new();
+/// find the start of the first operator of the 'matcher' precedence
+/// looping from left to right, the parentheses are skipped as a single entity
+fn find_first_operator<M: MatchOperator>(eq: &[char], matcher: M) -> Option<usize> {
+    let all_matcher = AllMatcher;
+    let mut index = 0;
 
-        loop {
-            let left_len = left_after.len();
-            if left_len == 0 {
-                break;
-            }
+    while index < eq.len() {
+        if matcher.match_operator(eq, index).is_some() {
+            return Some(index);
+        }
 
-            let current_index = left_len - 1;
-            let current_char = left_after[current_index];
-            let is_medium_precendence_operator =
-                medium_precedence_matcher.match_operator(current_char);
-            if is_medium_precendence_operator {
-                // move char to right (front)
-                // remove char from left_after
-                // return
-
-                right_after.insert(0, current_char);
-                left_after.pop();
-
-                return (left_after, right_after);
-            } else {
-                // detect for closing bracket
-                // if so we detect for the opening bracket and move everythig to the right
-                // update temp_left and right_after (front) accordingly
-                if current_char == ')' {
-                    let (left_parentheses_index, right_parentheses_index) =
-                        ParenthesesFinder::find_last(left_after.clone())
-                            .unwrap()
-                            .unwrap();
-
-                    let mut parentheses_vec =
-                        left_after[left_parentheses_index..right_parentheses_index + 1].to_vec();
-                    parentheses_vec.extend_from_slice(&right_after);
-                    right_after = parentheses_vec;
-                    left_after = left_after[..left_parentheses_index].to_vec();
-                } else {
-                    right_after.insert(0, current_char);
-                    left_after.pop();
-                }
-            }
+        if eq[index] == '(' {
+            index += skip_parentheses(&eq[index..]) + 1;
+        } else {
+            index += all_matcher.match_operator(eq, index).unwrap_or(1);
         }
     }
 
-    // loop from right to left to check for ^
-    // treating parentheses as a unit
-    //if we are able to split, we return
+    None
+}
+
+/// the index of the bracket that closes the bracket at the start of 'eq'
+fn skip_parentheses(eq: &[char]) -> usize {
+    let (_, right_parentheses_index) = ParenthesesFinder::find_first(eq.to_vec()).unwrap().unwrap();
+    right_parentheses_index
+}
 
-    {
-        let mut left_after = left.clone();
-        let mut right_after: EquationString = Vec::new();
+/// find the start of the last operator of the 'matcher' precedence
+/// looping from right to left, the parentheses are skipped as a single entity
+fn find_last_operator<M: MatchOperator>(eq: &[char], matcher: M) -> Option<usize> {
+    let mut end = eq.len();
 
-        loop {
-            let left_len = left_after.len();
-            if left_len == 0 {
-                break;
-            }
+    while end > 0 {
+        if eq[end - 1] == ')' {
+            let (left_parentheses_index, _) = ParenthesesFinder::find_last(eq[..end].to_vec())
+                .unwrap()
+                .unwrap();
 
-            let current_index = left_len - 1;
-            let current_char = left_after[current_index];
-            let is_high_precendence_operator = high_precedence_matcher.match_operator(current_char);
-            if is_high_precendence_operator {
-                // move char to right (front)
-                // remove char from left_after
-                // return
-
-                right_after.insert(0, current_char);
-                left_after.pop();
-
-                return (left_after, right_after);
-            } else {
-                // detect for closing bracket
-                // if so we detect for the opening bracket and move everythig to the right
-                // update temp_left and right_after (front) accordingly
-                if current_char == ')' {
-                    let (left_parentheses_index, right_parentheses_index) =
-                        ParenthesesFinder::find_last(left_after.clone())
-                            .unwrap()
-                            .unwrap();
-
-                    let mut parentheses_vec =
-                        left_after[left_parentheses_index..right_parentheses_index + 1].to_vec();
-                    parentheses_vec.extend_from_slice(&right_after);
-                    right_after = parentheses_vec;
-                    left_after = left_after[..left_parentheses_index].to_vec();
-                } else {
-                    right_after.insert(0, current_char);
-                    left_after.pop();
-                }
-            }
+            end = left_parentheses_index;
+            continue;
+        }
+
+        // an operator made of many chars is matched from its first char eg. the // in 5//2
+        let operator_start = (end.saturating_sub(MAX_OPERATOR_LEN)..end)
+            .find(|start| matcher.match_operator(eq, *start) == Some(end - start));
+
+        if operator_start.is_some() {
+            return operator_start;
         }
+        end -= 1;
     }
 
-    (left.clone(), left.clone())
+    None
 }
 
 #[cfg(test)]
@@ -459,73 +396,83 @@ mod tests {
     }
 
     #[test]
-    pub fn test_regroup_to_left_low_precedence() {
-        let left = "(1+3)";
-        let right = "-1*55";
-        let left = EquationString::remove_whitespaces(left);
-        let right = EquationString::remove_whitespaces(right);
-        let (left_after, right_after) = regroup_to_left(left, right);
-        assert_eq!(left_after.to_string(), "(1+3)");
-        assert_eq!(right_after.to_string(), "-1*55");
+    pub fn test_unbalanced_parentheses_position() {
+        let eq = "1+(3+5))";
+        let eq = eq.chars().collect::<EquationString>();
+        let error = ParenthesesFinder::find_first(eq).unwrap_err();
+        assert_eq!(error, EquationError::UnbalancedParenthesis(Span::at(7)));
 
-        let left = "(1+3)";
-        let right = "*1*-2^2+13+5";
-        let left = EquationString::remove_whitespaces(left);
-        let right = EquationString::remove_whitespaces(right);
-        let (left_after, right_after) = regroup_to_left(left, right);
-        assert_eq!(left_after.to_string(), "(1+3)*1*-2^2");
-        assert_eq!(right_after.to_string(), "+13+5");
+        let eq = "(1+(3+5)";
+        let eq = eq.chars().collect::<EquationString>();
+        let error = ParenthesesFinder::find_first(eq).unwrap_err();
+        assert_eq!(error, EquationError::UnbalancedParenthesis(Span::at(0)));
 
-        let left = "(1+3)";
-        let right = "*1*-2^2/(13+5)+1";
-        let left = EquationString::remove_whitespaces(left);
-        let right = EquationString::remove_whitespaces(right);
-        let (left_after, right_after) = regroup_to_left(left, right);
-        assert_eq!(left_after.to_string(), "(1+3)*1*-2^2/(13+5)");
-        assert_eq!(right_after.to_string(), "+1");
+        let eq = "(1+(3+5)";
+        let eq = eq.chars().collect::<EquationString>();
+        let error = ParenthesesFinder::find_last(eq).unwrap_err();
+        assert_eq!(error, EquationError::UnbalancedParenthesis(Span::at(0)));
+
+        let eq = "(1+3)+5)";
+        let eq = eq.chars().collect::<EquationString>();
+        let error = ParenthesesFinder::find_last(eq).unwrap_err();
+        assert_eq!(error, EquationError::UnbalancedParenthesis(Span::at(7)));
     }
 
     #[test]
-    pub fn test_regroup_to_left_medium_precedence() {
-        let left = "(1+3)";
-        let right = "*1*-2^2/(13+5)^1";
+    pub fn test_regroup_according_to_precedence() {
+        let cases = [
+            ("(1+3)", "-1*55", "(1+3)", "-1*55"),
+            ("(1+3)", "*1*-2^2+13+5", "(1+3)*1*-2^2+13", "+5"),
+            ("(1+3)", "*1*-2^2/(13+5)+1", "(1+3)*1*-2^2/(13+5)", "+1"),
+            ("(1+3)", "*1*-2^2/(13+5)^1", "(1+3)*1*-2^2", "/(13+5)^1"),
+            ("(1+3)", "*1*-2^2/-2^1", "(1+3)*1*-2^2", "/-2^1"),
+            ("(1+3)", "^1^(1/3)", "(1+3)", "^1^(1/3)"),
+            ("1|(2)", "+3", "1", "|(2)+3"),
+            ("(1|2)", "&3<<(4)+1", "(1|2)", "&3<<(4)+1"),
+        ];
+
+        for (left, right, left_after, right_after) in cases {
+            let left = EquationString::remove_whitespaces(left);
+            let right = EquationString::remove_whitespaces(right);
+            let (left, right) =
+                regroup_according_to_precedence(left, right, ParseOptions::default());
+            assert_eq!(left.to_string(), left_after);
+            assert_eq!(right.to_string(), right_after);
+        }
+    }
+
+    #[test]
+    pub fn test_regroup_to_right_logical_precedence() {
+        let left = "(1)<2&&not(x)||y";
         let left = EquationString::remove_whitespaces(left);
-        let right = EquationString::remove_whitespaces(right);
-        let (left_after, right_after) = regroup_to_left(left, right);
-        assert_eq!(left_after.to_string(), "(1+3)*1*-2^2/(13+5)^1");
-        assert_eq!(right_after.to_string(), "");
+        let (left_after, right_after) = regroup_to_right(left, ParseOptions::default());
+        assert_eq!(left_after.to_string(), "(1)<2&&not(x)");
+        assert_eq!(right_after.to_string(), "||y");
 
-        let left = "(1+3)";
-        let right = "*1*-2^2/-2^1";
+        let left = "not(1)>2&(3)";
         let left = EquationString::remove_whitespaces(left);
-        let right = EquationString::remove_whitespaces(right);
-        let (left_after, right_after) = regroup_to_left(left, right);
-        assert_eq!(left_after.to_string(), "(1+3)*1*-2^2/-2^1");
+        let (left_after, right_after) = regroup_to_right(left, ParseOptions::default());
+        assert_eq!(left_after.to_string(), "not(1)>2&(3)");
         assert_eq!(right_after.to_string(), "");
-    }
 
-    #[test]
-    pub fn test_regroup_to_left_high_precedence() {
-        let left = "(1+3)";
-        let right = "^1^(1/3)";
+        let left = "(1)!=2<(3)|4";
         let left = EquationString::remove_whitespaces(left);
-        let right = EquationString::remove_whitespaces(right);
-        let (left_after, right_after) = regroup_to_left(left, right);
-        assert_eq!(left_after.to_string(), "(1+3)^1^(1/3)");
-        assert_eq!(right_after.to_string(), "");
+        let (left_after, right_after) = regroup_to_right(left, ParseOptions::default());
+        assert_eq!(left_after.to_string(), "(1)");
+        assert_eq!(right_after.to_string(), "!=2<(3)|4");
     }
 
     #[test]
     pub fn test_regroup_to_right_low_precedence() {
         let left = "-2*2/5^7+(1+3)";
         let left = EquationString::remove_whitespaces(left);
-        let (left_after, right_after) = regroup_to_right(left);
+        let (left_after, right_after) = regroup_to_right(left, ParseOptions::default());
         assert_eq!(left_after.to_string(), "-2*2/5^7");
         assert_eq!(right_after.to_string(), "+(1+3)");
 
         let left = "-2*2/5^7*(5+2)+(1+3)";
         let left = EquationString::remove_whitespaces(left);
-        let (left_after, right_after) = regroup_to_right(left);
+        let (left_after, right_after) = regroup_to_right(left, ParseOptions::default());
         assert_eq!(left_after.to_string(), "-2*2/5^7*(5+2)");
         assert_eq!(right_after.to_string(), "+(1+3)");
     }
@@ -534,13 +481,13 @@ mod tests {
     pub fn test_regroup_to_right_medium_precedence() {
         let left = "-2*2/5^7*(5+2)^(1+3)";
         let left = EquationString::remove_whitespaces(left);
-        let (left_after, right_after) = regroup_to_right(left);
+        let (left_after, right_after) = regroup_to_right(left, ParseOptions::default());
         assert_eq!(left_after.to_string(), "-2*2/5^7");
         assert_eq!(right_after.to_string(), "*(5+2)^(1+3)");
 
         let left = "-2*2/5^7*5/2^(1+3)";
         let left = EquationString::remove_whitespaces(left);
-        let (left_after, right_after) = regroup_to_right(left);
+        let (left_after, right_after) = regroup_to_right(left, ParseOptions::default());
         assert_eq!(left_after.to_string(), "-2*2/5^7*5");
         assert_eq!(right_after.to_string(), "/2^(1+3)");
     }
@@ -549,7 +496,19 @@ mod tests {
     pub fn test_regroup_to_right_high_precedence() {
         let left = "5^7^-(5+2/1+3)";
         let left = EquationString::remove_whitespaces(left);
-        let (left_after, right_after) = regroup_to_right(left);
+        let (left_after, right_after) = regroup_to_right(left, ParseOptions::default());
+        assert_eq!(left_after.to_string(), "5");
+        assert_eq!(right_after.to_string(), "^7^-(5+2/1+3)");
+
+        let left = "-(5)^7";
+        let left = EquationString::remove_whitespaces(left);
+        let (left_after, right_after) = regroup_to_right(left, ParseOptions::default());
+        assert_eq!(left_after.to_string(), "-(5)^7");
+        assert_eq!(right_after.to_string(), "");
+
+        let left = "5^7^-(5+2/1+3)";
+        let left = EquationString::remove_whitespaces(left);
+        let (left_after, right_after) = regroup_to_right(left, ParseOptions::legacy());
         assert_eq!(left_after.to_string(), "5^7");
         assert_eq!(right_after.to_string(), "^-(5+2/1+3)");
     }
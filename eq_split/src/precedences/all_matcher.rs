@@ -1,14 +1,48 @@
-use super::traits::MatchOperator;
+use super::{
+    bitwise_precedence::{BitwiseAndMatcher, BitwiseOrMatcher, BitwiseXorMatcher, ShiftMatcher},
+    conversion_precedence::ConversionMatcher,
+    high_precedence::HighPrecedenceMatcher,
+    logical_precedence::{AndMatcher, EqualityMatcher, NotMatcher, OrMatcher, RelationalMatcher},
+    low_precedence::LowPrecedenceMatcher,
+    medium_precedence::MediumPrecendenceMatcher,
+    traits::MatchOperator,
+};
 
+/// the most chars that an operator can have eg. rem
+pub const MAX_OPERATOR_LEN: usize = 3;
 
 #[derive(Debug, Clone, Copy)]
 pub struct AllMatcher;
 
 impl MatchOperator for AllMatcher {
-    fn match_operator(&self, c: char) -> bool {
-        match c {
-            '+' | '-' | '*' | '/' | '^' => true,
-            _ => false,
-        }
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        ConversionMatcher
+            .match_operator(eq, index)
+            .or_else(|| OrMatcher.match_operator(eq, index))
+            .or_else(|| AndMatcher.match_operator(eq, index))
+            .or_else(|| NotMatcher.match_operator(eq, index))
+            .or_else(|| EqualityMatcher.match_operator(eq, index))
+            .or_else(|| RelationalMatcher.match_operator(eq, index))
+            .or_else(|| BitwiseOrMatcher.match_operator(eq, index))
+            .or_else(|| BitwiseXorMatcher.match_operator(eq, index))
+            .or_else(|| BitwiseAndMatcher.match_operator(eq, index))
+            .or_else(|| ShiftMatcher.match_operator(eq, index))
+            .or_else(|| LowPrecedenceMatcher.match_operator(eq, index))
+            .or_else(|| MediumPrecendenceMatcher.match_operator(eq, index))
+            .or_else(|| HighPrecedenceMatcher.match_operator(eq, index))
+    }
+}
+
+impl AllMatcher {
+    /// check if an operator ends right before 'index' eg. the * in 1*-2
+    /// or the bitwise not ~ that is a sign itself eg. ~-2
+    /// the word not is matched as an operator eg. not -2
+    /// a modulo kept apart by a space is an operator too eg. 10% -3
+    /// this means that a + or - at 'index' is a sign
+    pub fn match_operator_before(&self, eq: &[char], index: usize) -> bool {
+        (index > 0 && eq[index - 1] == '~')
+            || (index > 1 && eq[index - 2..index] == ['%', ' '])
+            || (index.saturating_sub(MAX_OPERATOR_LEN)..index)
+                .any(|start| self.match_operator(eq, start) == Some(index - start))
     }
 }
@@ -0,0 +1,47 @@
+use super::traits::{match_single_symbol, match_symbol, match_word, MatchOperator};
+
+/// the bitwise operators are calculated after + and -
+/// from the highest precedence << and >>, then &, then xor and lastly |
+/// eg. 1|2 xor 3&4<<1 = 1|(2 xor (3&(4<<1)))
+pub const BITWISE_MATCHERS: [&dyn MatchOperator; 4] = [
+    &BitwiseOrMatcher,
+    &BitwiseXorMatcher,
+    &BitwiseAndMatcher,
+    &ShiftMatcher,
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct BitwiseOrMatcher;
+
+impl MatchOperator for BitwiseOrMatcher {
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        match_single_symbol(eq, index, '|')
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BitwiseXorMatcher;
+
+impl MatchOperator for BitwiseXorMatcher {
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        match_word(eq, index, "xor")
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BitwiseAndMatcher;
+
+impl MatchOperator for BitwiseAndMatcher {
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        match_single_symbol(eq, index, '&')
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ShiftMatcher;
+
+impl MatchOperator for ShiftMatcher {
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        match_symbol(eq, index, "<<").or_else(|| match_symbol(eq, index, ">>"))
+    }
+}
@@ -0,0 +1,13 @@
+use super::traits::{match_word, MatchOperator};
+
+/// the conversion of a unit is calculated after everything else
+/// eg. 1 m + 20 cm to inch = (1 m + 20 cm) to inch
+/// to and in are the same operator eg. 5 ft in m
+#[derive(Debug, Clone, Copy)]
+pub struct ConversionMatcher;
+
+impl MatchOperator for ConversionMatcher {
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        match_word(eq, index, "to").or_else(|| match_word(eq, index, "in"))
+    }
+}
@@ -1,13 +1,10 @@
-use super::traits::MatchOperator;
+use super::traits::{match_symbol, MatchOperator};
 
 #[derive(Debug, Clone, Copy)]
 pub struct HighPrecedenceMatcher;
 
 impl MatchOperator for HighPrecedenceMatcher {
-    fn match_operator(&self, c: char) -> bool {
-        match c {
-            '^' => true,
-            _ => false,
-        }
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        match_symbol(eq, index, "^")
     }
 }
@@ -0,0 +1,60 @@
+use super::traits::{match_single_symbol, match_symbol, match_word, MatchOperator};
+
+/// the comparisons and the logical operators are calculated after the bitwise operators
+/// from the highest precedence, <, <=, > and >=, then == and !=, then not, then && and lastly ||
+/// eg. not x < 1 == y && z || w = ((not ((x < 1) == y)) && z) || w
+pub const LOGICAL_MATCHERS: [&dyn MatchOperator; 2] = [&OrMatcher, &AndMatcher];
+
+pub const COMPARISON_MATCHERS: [&dyn MatchOperator; 2] = [&EqualityMatcher, &RelationalMatcher];
+
+#[derive(Debug, Clone, Copy)]
+pub struct OrMatcher;
+
+impl MatchOperator for OrMatcher {
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        match_symbol(eq, index, "||")
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AndMatcher;
+
+impl MatchOperator for AndMatcher {
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        match_symbol(eq, index, "&&")
+    }
+}
+
+/// not is written in front of a value eg. not x > 1
+#[derive(Debug, Clone, Copy)]
+pub struct NotMatcher;
+
+impl MatchOperator for NotMatcher {
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        match_word(eq, index, "not")
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EqualityMatcher;
+
+impl MatchOperator for EqualityMatcher {
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        // != followed by = is a factorial eg. 5!==120 is 5! == 120
+        match_symbol(eq, index, "==")
+            .or_else(|| match_symbol(eq, index, "!=").filter(|_| eq.get(index + 2) != Some(&'=')))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RelationalMatcher;
+
+impl MatchOperator for RelationalMatcher {
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        // <= and >= have to be matched before < and >
+        match_symbol(eq, index, "<=")
+            .or_else(|| match_symbol(eq, index, ">="))
+            .or_else(|| match_single_symbol(eq, index, '<'))
+            .or_else(|| match_single_symbol(eq, index, '>'))
+    }
+}
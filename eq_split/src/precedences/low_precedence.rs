@@ -1,13 +1,20 @@
-use super::traits::MatchOperator;
+use crate::numbers::is_exponent;
+
+use super::traits::{match_symbol, MatchOperator};
 
 #[derive(Debug, Clone, Copy)]
 pub struct LowPrecedenceMatcher;
 
 impl MatchOperator for LowPrecedenceMatcher {
-    fn match_operator(&self, c: char) -> bool {
-        match c {
-            '+' | '-' => true,
-            _ => false,
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        // the sign of an exponent is a part of the number eg. 6.02e-23
+        if is_exponent(eq, index) {
+            return None;
         }
+
+        // ± gives the interval around the left eg. 1.25 ± 0.05 = [1.2, 1.3]
+        match_symbol(eq, index, "+")
+            .or_else(|| match_symbol(eq, index, "-"))
+            .or_else(|| match_symbol(eq, index, "±"))
     }
 }
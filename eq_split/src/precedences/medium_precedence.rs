@@ -1,13 +1,22 @@
-use super::traits::MatchOperator;
+use super::{
+    postfix::PostfixMatcher,
+    traits::{match_symbol, match_word, MatchOperator},
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct MediumPrecendenceMatcher;
 
 impl MatchOperator for MediumPrecendenceMatcher {
-    fn match_operator(&self, c: char) -> bool {
-        match c {
-            '*' | '/' => true,
-            _ => false,
-        }
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        // // has to be matched before / as it starts with /
+        // % at the end of a value is a percent and not a modulo eg. 50%*2
+        match_symbol(eq, index, "*")
+            .or_else(|| match_symbol(eq, index, "//"))
+            .or_else(|| match_symbol(eq, index, "/"))
+            .or_else(|| {
+                match_symbol(eq, index, "%")
+                    .filter(|_| PostfixMatcher.match_operator(eq, index).is_none())
+            })
+            .or_else(|| match_word(eq, index, "rem"))
     }
 }
@@ -1,5 +1,9 @@
 pub mod all_matcher;
+pub mod bitwise_precedence;
+pub mod conversion_precedence;
 pub mod high_precedence;
+pub mod logical_precedence;
 pub mod low_precedence;
 pub mod medium_precedence;
-pub mod traits;
\ No newline at end of file
+pub mod postfix;
+pub mod traits;
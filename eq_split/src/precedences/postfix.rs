@@ -0,0 +1,39 @@
+use super::{
+    all_matcher::AllMatcher,
+    logical_precedence::EqualityMatcher,
+    traits::{match_symbol, MatchOperator},
+};
+
+/// the operators written after a value eg. 5!, 12.5%
+/// the ! of a != is not a factorial eg. 5!=3
+#[derive(Debug, Clone, Copy)]
+pub struct PostfixMatcher;
+
+impl MatchOperator for PostfixMatcher {
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        match_symbol(eq, index, "!")
+            .filter(|_| EqualityMatcher.match_operator(eq, index).is_none())
+            .or_else(|| match_percent(eq, index))
+    }
+}
+
+/// % is a percent when nothing is calculated with the value after it eg. 50%, 50%*2, 2-(50%)
+/// else it is a modulo eg. 7%3
+fn match_percent(eq: &[char], index: usize) -> Option<usize> {
+    let len = match_symbol(eq, index, "%")?;
+    let next_index = index + len;
+
+    let is_end_of_value = match eq.get(next_index) {
+        None | Some(')') | Some(',') => true,
+        Some(_) => {
+            AllMatcher.match_operator(eq, next_index).is_some()
+                || PostfixMatcher.match_operator(eq, next_index).is_some()
+        }
+    };
+
+    if is_end_of_value {
+        Some(len)
+    } else {
+        None
+    }
+}
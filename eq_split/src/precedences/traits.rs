@@ -1,3 +1,53 @@
+use crate::identifiers::is_identifier_char;
+
 pub trait MatchOperator {
-    fn match_operator(&self, c: char) -> bool;
-}
\ No newline at end of file
+    /// the length of the operator of this precedence that starts at 'index' of the eq
+    /// None if there is no such operator at 'index'
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize>;
+}
+
+/// so that the matchers of the different precedences can be kept in a list
+impl<M: MatchOperator + ?Sized> MatchOperator for &M {
+    fn match_operator(&self, eq: &[char], index: usize) -> Option<usize> {
+        (**self).match_operator(eq, index)
+    }
+}
+
+/// match an operator that is made of chars eg. //
+pub fn match_symbol(eq: &[char], index: usize, symbol: &str) -> Option<usize> {
+    let len = symbol.chars().count();
+    let is_match =
+        eq.len() >= index + len && eq[index..index + len].iter().copied().eq(symbol.chars());
+
+    if is_match {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+/// match an operator of a single char that is not doubled
+/// eg. the & in 1&2 but not in 1&&2, the < in 1<2 but not in 1<<2
+pub fn match_single_symbol(eq: &[char], index: usize, symbol: char) -> Option<usize> {
+    let is_doubled = (index > 0 && eq[index - 1] == symbol) || eq.get(index + 1) == Some(&symbol);
+
+    if eq.get(index) == Some(&symbol) && !is_doubled {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+/// match an operator that is a word eg. rem
+/// the word cannot be a part of a longer name eg. the rem in premium
+pub fn match_word(eq: &[char], index: usize, word: &str) -> Option<usize> {
+    let len = match_symbol(eq, index, word)?;
+    let is_part_of_name = (index > 0 && is_identifier_char(eq[index - 1]))
+        || eq.get(index + len).is_some_and(|c| is_identifier_char(*c));
+
+    if is_part_of_name {
+        None
+    } else {
+        Some(len)
+    }
+}
@@ -0,0 +1,136 @@
+use crate::identifiers::is_identifier_char;
+
+/// the powers of the base dimensions of a unit in the order length, mass, time,
+/// electric current, temperature, amount of substance and luminous intensity
+/// eg. [1, 0, -2, 0, 0, 0, 0] for an acceleration
+pub type Dimension = [i32; 7];
+
+pub const DIMENSIONLESS: Dimension = [0; 7];
+const LENGTH: Dimension = [1, 0, 0, 0, 0, 0, 0];
+const AREA: Dimension = [2, 0, 0, 0, 0, 0, 0];
+const VOLUME: Dimension = [3, 0, 0, 0, 0, 0, 0];
+const MASS: Dimension = [0, 1, 0, 0, 0, 0, 0];
+const TIME: Dimension = [0, 0, 1, 0, 0, 0, 0];
+const FREQUENCY: Dimension = [0, 0, -1, 0, 0, 0, 0];
+const CURRENT: Dimension = [0, 0, 0, 1, 0, 0, 0];
+const TEMPERATURE: Dimension = [0, 0, 0, 0, 1, 0, 0];
+const AMOUNT: Dimension = [0, 0, 0, 0, 0, 1, 0];
+const LUMINOSITY: Dimension = [0, 0, 0, 0, 0, 0, 1];
+const FORCE: Dimension = [1, 1, -2, 0, 0, 0, 0];
+const ENERGY: Dimension = [2, 1, -2, 0, 0, 0, 0];
+const POWER: Dimension = [2, 1, -3, 0, 0, 0, 0];
+const PRESSURE: Dimension = [-1, 1, -2, 0, 0, 0, 0];
+const CHARGE: Dimension = [0, 0, 1, 1, 0, 0, 0];
+const VOLTAGE: Dimension = [2, 1, -3, -1, 0, 0, 0];
+
+/// a unit of measure eg. km, degF
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    pub name: &'static str,
+    /// the size of the unit in the SI units eg. 1000 for km
+    pub factor: f64,
+    /// the SI value of the zero of the unit, only the temperatures have one eg. 273.15 for degC
+    pub offset: f64,
+    pub dimension: Dimension,
+}
+
+const fn unit(name: &'static str, factor: f64, dimension: Dimension) -> Unit {
+    Unit {
+        name,
+        factor,
+        offset: 0.0,
+        dimension,
+    }
+}
+
+/// inch is not called in as in is the conversion operator eg. 5 ft in inch
+pub const UNITS: [Unit; 43] = [
+    unit("m", 1.0, LENGTH),
+    unit("km", 1e3, LENGTH),
+    unit("cm", 1e-2, LENGTH),
+    unit("mm", 1e-3, LENGTH),
+    unit("um", 1e-6, LENGTH),
+    unit("nm", 1e-9, LENGTH),
+    unit("inch", 0.0254, LENGTH),
+    unit("ft", 0.3048, LENGTH),
+    unit("yd", 0.9144, LENGTH),
+    unit("mi", 1609.344, LENGTH),
+    unit("ha", 1e4, AREA),
+    unit("L", 1e-3, VOLUME),
+    unit("mL", 1e-6, VOLUME),
+    unit("gal", 3.785_411_784e-3, VOLUME),
+    unit("kg", 1.0, MASS),
+    unit("g", 1e-3, MASS),
+    unit("mg", 1e-6, MASS),
+    unit("lb", 0.453_592_37, MASS),
+    unit("oz", 0.028_349_523_125, MASS),
+    unit("s", 1.0, TIME),
+    unit("ms", 1e-3, TIME),
+    unit("min", 60.0, TIME),
+    unit("h", 3600.0, TIME),
+    unit("day", 86400.0, TIME),
+    unit("Hz", 1.0, FREQUENCY),
+    unit("A", 1.0, CURRENT),
+    unit("K", 1.0, TEMPERATURE),
+    Unit {
+        name: "degC",
+        factor: 1.0,
+        offset: 273.15,
+        dimension: TEMPERATURE,
+    },
+    Unit {
+        name: "degF",
+        factor: 5.0 / 9.0,
+        offset: 459.67 * 5.0 / 9.0,
+        dimension: TEMPERATURE,
+    },
+    unit("mol", 1.0, AMOUNT),
+    unit("cd", 1.0, LUMINOSITY),
+    unit("N", 1.0, FORCE),
+    unit("kN", 1e3, FORCE),
+    unit("J", 1.0, ENERGY),
+    unit("kJ", 1e3, ENERGY),
+    unit("kWh", 3.6e6, ENERGY),
+    unit("W", 1.0, POWER),
+    unit("kW", 1e3, POWER),
+    unit("Pa", 1.0, PRESSURE),
+    unit("kPa", 1e3, PRESSURE),
+    unit("bar", 1e5, PRESSURE),
+    unit("C", 1.0, CHARGE),
+    unit("V", 1.0, VOLTAGE),
+];
+
+pub fn find_unit(name: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|unit| unit.name == name)
+}
+
+/// check if a whole unit starts at 'index' of the eq, a number can be right before it
+/// eg. the m in 3m and in 5e-3m but not in 3mx or in km
+pub fn is_unit(eq: &[char], index: usize) -> bool {
+    let is_name_start =
+        index == 0 || eq[index - 1].is_ascii_digit() || !is_identifier_char(eq[index - 1]);
+    let name = eq[index..]
+        .iter()
+        .take_while(|c| is_identifier_char(**c))
+        .collect::<String>();
+
+    is_name_start && find_unit(&name).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EquationString;
+
+    #[test]
+    pub fn test_is_unit() {
+        let eq = "3m+2km/h+mx+2inch".chars().collect::<EquationString>();
+        assert!(is_unit(&eq, 1));
+        assert!(!is_unit(&eq, 0));
+        assert!(is_unit(&eq, 4));
+        assert!(!is_unit(&eq, 5));
+        assert!(is_unit(&eq, 7));
+        assert!(!is_unit(&eq, 9));
+        assert!(is_unit(&eq, 13));
+    }
+}
@@ -0,0 +1,121 @@
+use eq_split::operators::{Operators, PostfixOperators, UnaryOperators};
+use num_complex::Complex64;
+
+use crate::{CalculationError, Value};
+
+/// a part smaller than this times the size of the number is not shown
+/// eg. e^(i*pi) = -1 instead of -1+0.00000000000000012246467991473532i
+const DISPLAY_EPSILON: f64 = 1e-15;
+
+/// how a complex number is shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ComplexFormat {
+    /// eg. 1+2i
+    #[default]
+    Rectangular,
+    /// the size and the angle in radians eg. 2*e^(1.5707963267948966i)
+    Polar,
+}
+
+/// calculate the result of the operation with complex numbers
+/// only + - * / ^ and == != work on numbers that are not real
+pub fn calculate_complex(
+    operator: Operators,
+    left: Complex64,
+    right: Complex64,
+) -> Result<Value, CalculationError> {
+    let result = match operator {
+        Operators::Equal => return Ok(Value::Boolean(left == right)),
+        Operators::NotEqual => return Ok(Value::Boolean(left != right)),
+        Operators::Plus | Operators::Minus | Operators::Mult | Operators::Div | Operators::Exp => {
+            operator.calculate(left, right)
+        }
+        _ => {
+            let left = as_real(left)?;
+            let right = as_real(right)?;
+            if let Some(result) = operator.compare(&left, &right) {
+                return Ok(Value::Boolean(result));
+            }
+            operator.calculate(left, right).map(Complex64::from)
+        }
+    };
+
+    result
+        .map(Value::Complex)
+        .ok_or_else(|| CalculationError::NoResult(operator.symbol().to_string()))
+}
+
+/// calculate the result of the sign with complex numbers eg. -i
+pub fn calculate_complex_unary(
+    operator: UnaryOperators,
+    value: Complex64,
+) -> Result<Complex64, CalculationError> {
+    match operator {
+        UnaryOperators::Negate | UnaryOperators::Plus => Ok(operator.calculate(value)),
+        _ => Ok(Complex64::from(operator.calculate(as_real(value)?))),
+    }
+}
+
+/// calculate the result of the postfix operator with complex numbers eg. 50%
+/// the factorial only works on real numbers
+pub fn calculate_complex_postfix(
+    operator: PostfixOperators,
+    value: Complex64,
+) -> Result<Complex64, CalculationError> {
+    match operator {
+        PostfixOperators::Percent => Ok(value / 100.0),
+        PostfixOperators::Factorial => {
+            let result = operator.calculate(as_real(value)?);
+            Ok(Complex64::from(result.unwrap_or(f64::NAN)))
+        }
+    }
+}
+
+/// the complex number as it is shown with 'format'
+pub fn format_complex(value: Complex64, format: ComplexFormat) -> String {
+    let size = value.norm();
+    let clean = |part: f64| {
+        if part.abs() < size * DISPLAY_EPSILON {
+            0.0
+        } else {
+            part
+        }
+    };
+
+    match format {
+        ComplexFormat::Rectangular => {
+            let (re, im) = (clean(value.re), clean(value.im));
+            if im == 0.0 {
+                return format!("{}", re);
+            }
+
+            let imaginary = match im.abs() {
+                1.0 => String::from("i"),
+                abs => format!("{}i", abs),
+            };
+            match (re == 0.0, im < 0.0) {
+                (true, true) => format!("-{}", imaginary),
+                (true, false) => imaginary,
+                (false, true) => format!("{}-{}", re, imaginary),
+                (false, false) => format!("{}+{}", re, imaginary),
+            }
+        }
+        ComplexFormat::Polar => {
+            let angle = clean(value.arg());
+            if angle == 0.0 {
+                format!("{}", size)
+            } else {
+                format!("{}*e^({}i)", size, angle)
+            }
+        }
+    }
+}
+
+/// the real number when the imaginary part is 0
+pub fn as_real(value: Complex64) -> Result<f64, CalculationError> {
+    if value.im == 0.0 {
+        Ok(value.re)
+    } else {
+        Err(CalculationError::ExpectedReal(Value::Complex(value)))
+    }
+}
@@ -0,0 +1,227 @@
+use bigdecimal::{
+    num_bigint::BigInt, BigDecimal, Context, One, RoundingMode, Signed, ToPrimitive, Zero,
+};
+use eq_split::{
+    arithmetic::{Number, MAX_FACTORIAL},
+    numbers::parse_decimal_literal,
+    operators::{Operators, PostfixOperators, UnaryOperators},
+};
+
+use crate::{math_characters::MathConstant, CalculationError, Value};
+
+/// literals with a larger exponent are calculated with f64
+/// as adding them to another number writes out all of their digits eg. 1e99999999999 + 1
+const MAX_LITERAL_EXPONENT: u64 = 100_000;
+
+/// the constant rounded to the precision of 'ctx'
+pub fn decimal_constant(constant: MathConstant, ctx: &Context) -> Option<BigDecimal> {
+    let value = match constant {
+        MathConstant::Pi => BigDecimal::pi()?,
+        MathConstant::E => BigDecimal::e()?,
+    };
+
+    Some(round_to_precision(value, ctx))
+}
+
+/// the decimal of a number literal eg. 0.1, 6.02e-23, 0xFF
+/// None when its exponent is too large, see 'MAX_LITERAL_EXPONENT'
+pub fn decimal_literal(literal: &str) -> Option<BigDecimal> {
+    parse_decimal_literal(literal)
+        .filter(|value| value.fractional_digit_count().unsigned_abs() <= MAX_LITERAL_EXPONENT)
+}
+
+/// the f64 written as a decimal with the shortest digits that give it back eg. 0.1
+pub fn float_to_decimal(value: f64) -> Result<BigDecimal, CalculationError> {
+    BigDecimal::from_float(value).ok_or(CalculationError::NotADecimal(value))
+}
+
+/// calculate the result of the operation with decimals
+/// the result is rounded to the precision of 'ctx'
+/// / and ^ are worked out to the precision of 'ctx' instead of the 100 digits of 'Number'
+/// and ^ with an exponent that is not whole gives an f64, as it has no exact decimal
+pub fn calculate_decimal(
+    operator: Operators,
+    left: BigDecimal,
+    right: BigDecimal,
+    ctx: &Context,
+) -> Result<Value, CalculationError> {
+    if let Some(result) = operator.compare(&left, &right) {
+        return Ok(Value::Boolean(result));
+    }
+    if operator.divides_by_zero(&left, &right) {
+        return Err(CalculationError::DivisionByZero);
+    }
+
+    let result = match operator {
+        Operators::Div => divide(&left, &right, ctx),
+        Operators::IntDiv => floor_divide(&left, &right),
+        Operators::Exp => match right.to_i64().filter(|_| right.is_integer()) {
+            Some(exponent) if exponent < 0 => match exponent.checked_neg() {
+                Some(exponent) => divide(&BigDecimal::one(), &power(&left, exponent, ctx), ctx),
+                None => return Ok(Value::Number(to_float(&left).powf(to_float(&right)))),
+            },
+            Some(exponent) => power(&left, exponent, ctx),
+            None => return Ok(Value::Number(to_float(&left).powf(to_float(&right)))),
+        },
+        _ => operator
+            .calculate(left, right)
+            .ok_or_else(|| CalculationError::NoResult(operator.symbol().to_string()))?,
+    };
+
+    Ok(Value::from(round_to_precision(result, ctx)))
+}
+
+/// calculate the result of the sign or ~ with decimals eg. -x
+pub fn calculate_decimal_unary(
+    operator: UnaryOperators,
+    value: BigDecimal,
+    ctx: &Context,
+) -> BigDecimal {
+    round_to_precision(operator.calculate(value), ctx)
+}
+
+/// calculate the result of the postfix operator with decimals eg. 5!, 12.5%
+/// the factorial of a number that is not whole gives an f64 as it is calculated with f64
+pub fn calculate_decimal_postfix(
+    operator: PostfixOperators,
+    value: BigDecimal,
+    ctx: &Context,
+) -> Value {
+    let result = match operator {
+        PostfixOperators::Percent => divide(&value, &BigDecimal::from(100), ctx),
+        PostfixOperators::Factorial => match value.to_u64().filter(|_| value.is_integer()) {
+            Some(n) if n <= MAX_FACTORIAL => (2..=n).fold(BigDecimal::one(), |product, i| {
+                round_to_precision(product * BigDecimal::from(i), ctx)
+            }),
+            _ => {
+                let result = operator.calculate(to_float(&value));
+                return Value::Number(result.unwrap_or(f64::NAN));
+            }
+        },
+    };
+
+    Value::from(round_to_precision(result, ctx))
+}
+
+/// the builtin functions with a result to the precision of 'ctx' eg. sqrt, abs, max
+/// None for the other functions, when an argument is not a decimal
+/// or when the result is not a real number eg. sqrt(-1)
+pub fn call_decimal_function(name: &str, args: &[Value], ctx: &Context) -> Option<BigDecimal> {
+    let args = args
+        .iter()
+        .map(|arg| match arg {
+            Value::Decimal(value) => Some(value.as_ref()),
+            _ => None,
+        })
+        .collect::<Option<Vec<&BigDecimal>>>()?;
+
+    let result = match (name, &args[..]) {
+        ("sqrt", [value]) => trim_root(value.sqrt_with_context(ctx)?, value, 2),
+        ("cbrt", [value]) => trim_root(value.cbrt_with_context(ctx), value, 3),
+        ("abs", [value]) => value.abs(),
+        ("floor", [value]) => value.with_scale_round(0, RoundingMode::Floor),
+        ("ceil", [value]) => value.with_scale_round(0, RoundingMode::Ceiling),
+        // halves are rounded away from zero as with f64 eg. round(-2.5) = -3
+        ("round", [value]) => value.with_scale_round(0, RoundingMode::HalfUp),
+        ("min", [left, right]) => BigDecimal::clone(left.min(right)),
+        ("max", [left, right]) => BigDecimal::clone(left.max(right)),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// 'value' rounded when it has more digits than the precision of 'ctx'
+/// the digits of a value that fits are kept eg. 1.10 * 3 = 3.30
+pub fn round_to_precision(value: BigDecimal, ctx: &Context) -> BigDecimal {
+    if value.digits() > ctx.precision().get() {
+        ctx.round_decimal(value)
+    } else {
+        value
+    }
+}
+
+pub fn to_float(value: &BigDecimal) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+/// 'left' / 'right' rounded to the precision of 'ctx'
+/// the quotient is worked out to two more digits than needed
+/// and a digit is added when it is not exact so that it is rounded the right way
+fn divide(left: &BigDecimal, right: &BigDecimal, ctx: &Context) -> BigDecimal {
+    let (left_digits, left_scale) = left.as_bigint_and_exponent();
+    let (right_digits, right_scale) = right.as_bigint_and_exponent();
+
+    let precision = ctx.precision().get() as i64;
+    let shift = (precision + 2 + right.digits() as i64 - left.digits() as i64).max(0);
+    let numerator = left_digits.abs() * BigInt::from(10).pow(shift as u32);
+    let denominator = right_digits.abs();
+
+    let mut quotient = &numerator / &denominator;
+    let mut scale = left_scale - right_scale + shift;
+    let is_exact = (numerator % denominator).is_zero();
+    if !is_exact {
+        quotient = quotient * 10 + 1;
+        scale += 1;
+    }
+
+    if left_digits.is_negative() != right_digits.is_negative() {
+        quotient = -quotient;
+    }
+
+    // the zeros added to work out an exact quotient are not kept eg. 1/4 = 0.25
+    let mut result = BigDecimal::new(quotient, scale);
+    if is_exact {
+        result = result.normalized();
+        if result.fractional_digit_count() < 0 {
+            result = result.with_scale(0);
+        }
+    }
+    round_to_precision(result, ctx)
+}
+
+/// 'value' to the power of the whole number 'exponent' rounded to the precision of 'ctx'
+/// the zeros added by the rounding are not kept eg. 1.5^2 = 2.25
+fn power(value: &BigDecimal, exponent: i64, ctx: &Context) -> BigDecimal {
+    let result = value.powi_with_context(exponent, ctx);
+    let scale = value
+        .fractional_digit_count()
+        .max(0)
+        .saturating_mul(exponent);
+    let trimmed = result.with_scale(scale.min(result.fractional_digit_count()));
+    if trimmed == result {
+        trimmed
+    } else {
+        result
+    }
+}
+
+/// the zeros of a root are not kept when it is exact eg. sqrt(2.25) = 1.5
+fn trim_root(root: BigDecimal, value: &BigDecimal, degree: i64) -> BigDecimal {
+    let mut trimmed = root.normalized();
+    if trimmed.fractional_digit_count() < 0 {
+        trimmed = trimmed.with_scale(0);
+    }
+
+    if trimmed.powi(degree) == *value {
+        trimmed
+    } else {
+        root
+    }
+}
+
+/// the quotient rounded down eg. -7 // 2 = -4
+fn floor_divide(left: &BigDecimal, right: &BigDecimal) -> BigDecimal {
+    let scale = left
+        .fractional_digit_count()
+        .max(right.fractional_digit_count());
+    let (left_digits, _) = left.with_scale(scale).into_bigint_and_exponent();
+    let (right_digits, _) = right.with_scale(scale).into_bigint_and_exponent();
+
+    let quotient = &left_digits / &right_digits;
+    let has_remainder = !(&left_digits % &right_digits).is_zero();
+    if has_remainder && left_digits.is_negative() != right_digits.is_negative() {
+        BigDecimal::from(quotient - 1)
+    } else {
+        BigDecimal::from(quotient)
+    }
+}
@@ -0,0 +1,539 @@
+use eq_split::{
+    numbers::parse_number_literal,
+    operators::{Operators, PostfixOperators, UnaryOperators},
+    EquationString,
+};
+
+use crate::{
+    functions::check_arity, CalculationError, Environment, MathConstant, NodeValue, OperationNode,
+};
+
+impl OperationNode {
+    /// the derivative of the tree by the variable 'var' eg. 2*x*cos(x) for x^2 by x
+    /// the sum, product, quotient, power and chain rules are applied and the result is simplified
+    /// the parts with 'var' that have no derivative eg. x > 1, x & 1, x! give an error
+    pub fn derivative(&self, var: &str) -> Result<OperationNode, CalculationError> {
+        self.derivative_with(var, &Environment::new())
+    }
+
+    /// the calls of the functions defined in 'env' are replaced by their bodies
+    /// eg. 2*x for f(x) by x with f(x) = x^2
+    pub fn derivative_with(
+        &self,
+        var: &str,
+        env: &Environment,
+    ) -> Result<OperationNode, CalculationError> {
+        let node = inline_calls(self, env, &[])?;
+        Ok(node.differentiate(var)?.simplify())
+    }
+
+    /// the derivative before it is simplified, only the obvious parts eg. x*1, x+0 are left out
+    /// a part without 'var' does not change eg. 5!, y > 1
+    fn differentiate(&self, var: &str) -> Result<OperationNode, CalculationError> {
+        if !contains_variable(self, var) {
+            return Ok(number(0.0));
+        }
+        if self.operation == Operators::None {
+            return value_derivative(&self.left_node, var);
+        }
+
+        let left = OperationNode::from_value(&self.left_node);
+        let right = OperationNode::from_value(&self.right_node);
+        let left_derivative = left.differentiate(var)?;
+        let right_derivative = right.differentiate(var)?;
+
+        let derivative = match self.operation {
+            Operators::Plus => add(left_derivative, right_derivative),
+            Operators::Minus => sub(left_derivative, right_derivative),
+            // the interval around a value changes as the value does
+            Operators::PlusMinus => left_derivative,
+            Operators::Mult => add(mul(left_derivative, right), mul(left, right_derivative)),
+            Operators::Div => div(
+                sub(
+                    mul(left_derivative, right.clone()),
+                    mul(left, right_derivative),
+                ),
+                pow(right, number(2.0)),
+            ),
+            Operators::Exp => power_derivative(left, right, left_derivative, right_derivative),
+            // the whole quotient does not change between its steps
+            Operators::IntDiv => number(0.0),
+            Operators::Modulo | Operators::Rem if is_number(&right_derivative, 0.0) => {
+                left_derivative
+            }
+            _ => return Err(CalculationError::NoDerivative(self.to_string())),
+        };
+        Ok(derivative)
+    }
+}
+
+/// the derivative of a single value
+fn value_derivative(value: &NodeValue, var: &str) -> Result<OperationNode, CalculationError> {
+    let derivative = match value {
+        NodeValue::Variable(name) if name == var => number(1.0),
+        NodeValue::Call { name, args } => call_derivative(name, args, var)?,
+        NodeValue::Unary {
+            operator: UnaryOperators::Negate,
+            node,
+        } => neg(node.differentiate(var)?),
+        NodeValue::Unary {
+            operator: UnaryOperators::Plus,
+            node,
+        } => node.differentiate(var)?,
+        NodeValue::Postfix {
+            operator: PostfixOperators::Percent,
+            node,
+        } => div(node.differentiate(var)?, number(100.0)),
+        NodeValue::OperationValue(node) => node.differentiate(var)?,
+        value => {
+            let node = OperationNode::leaf(value.clone());
+            return Err(CalculationError::NoDerivative(node.to_string()));
+        }
+    };
+    Ok(derivative)
+}
+
+/// check if the variable 'var' is used anywhere in the tree
+fn contains_variable(node: &OperationNode, var: &str) -> bool {
+    [&node.left_node, &node.right_node]
+        .iter()
+        .any(|value| match value.as_ref() {
+            NodeValue::Variable(name) => name == var,
+            NodeValue::Call { args, .. } => args.iter().any(|arg| contains_variable(arg, var)),
+            NodeValue::Unary { node, .. }
+            | NodeValue::Postfix { node, .. }
+            | NodeValue::OperationValue(node) => contains_variable(node, var),
+            _ => false,
+        })
+}
+
+/// the tree with the calls of the functions defined in 'env' replaced by their bodies
+/// the parameters in a body are replaced by the arguments eg. (2*y)^2 for f(2*y) with f(x) = x^2
+/// 'calling' are the functions being replaced, one that calls itself would be replaced forever
+fn inline_calls(
+    node: &OperationNode,
+    env: &Environment,
+    calling: &[&str],
+) -> Result<OperationNode, CalculationError> {
+    replace_values(node, &|value| {
+        let NodeValue::Call { name, args } = value else {
+            return Ok(None);
+        };
+        let Some(function) = env.get_function(name) else {
+            return Ok(None);
+        };
+        check_arity(name, function.params.len(), args)?;
+        if calling.contains(&name.as_str()) {
+            return Err(CalculationError::RecursionLimit(name.to_string()));
+        }
+
+        let args = args
+            .iter()
+            .map(|arg| inline_calls(arg, env, calling))
+            .collect::<Result<Vec<OperationNode>, CalculationError>>()?;
+        let body = replace_values(&function.body, &|value| match value {
+            NodeValue::Variable(name) => Ok(function
+                .params
+                .iter()
+                .position(|param| param == name)
+                .map(|i| args[i].clone())),
+            _ => Ok(None),
+        })?;
+
+        inline_calls(&body, env, &[calling, &[name.as_str()]].concat()).map(Some)
+    })
+}
+
+/// the tree with the values that 'replace' gives a node for replaced by that node
+/// the nodes inside the other values are replaced in turn eg. the arguments of a call
+fn replace_values(
+    node: &OperationNode,
+    replace: &dyn Fn(&NodeValue) -> Result<Option<OperationNode>, CalculationError>,
+) -> Result<OperationNode, CalculationError> {
+    let replace_value = |value: &NodeValue| -> Result<NodeValue, CalculationError> {
+        if let Some(node) = replace(value)? {
+            return Ok(NodeValue::OperationValue(node));
+        }
+
+        let value = match value {
+            NodeValue::Call { name, args } => NodeValue::Call {
+                name: name.clone(),
+                args: args
+                    .iter()
+                    .map(|arg| replace_values(arg, replace))
+                    .collect::<Result<Vec<OperationNode>, CalculationError>>()?,
+            },
+            NodeValue::Unary { operator, node } => NodeValue::Unary {
+                operator: *operator,
+                node: replace_values(node, replace)?,
+            },
+            NodeValue::Postfix { operator, node } => NodeValue::Postfix {
+                operator: *operator,
+                node: replace_values(node, replace)?,
+            },
+            NodeValue::OperationValue(node) => {
+                NodeValue::OperationValue(replace_values(node, replace)?)
+            }
+            value => value.clone(),
+        };
+        Ok(value)
+    };
+
+    Ok(OperationNode {
+        operation: node.operation,
+        left_node: Box::new(replace_value(&node.left_node)?),
+        right_node: Box::new(replace_value(&node.right_node)?),
+    })
+}
+
+/// d(a^b) is b*a^(b-1)*da when b is a constant, a^b*ln(a)*db when a is a constant
+/// and a^b*(db*ln(a) + b*da/a) otherwise
+fn power_derivative(
+    base: OperationNode,
+    exponent: OperationNode,
+    base_derivative: OperationNode,
+    exponent_derivative: OperationNode,
+) -> OperationNode {
+    if is_number(&exponent_derivative, 0.0) {
+        let power = pow(base, sub(exponent.clone(), number(1.0)));
+        return mul(mul(exponent, power), base_derivative);
+    }
+
+    let log = ln(base.clone());
+    if is_number(&base_derivative, 0.0) {
+        return mul(mul(pow(base, exponent), log), exponent_derivative);
+    }
+
+    let power = pow(base.clone(), exponent.clone());
+    let sum = add(
+        mul(exponent_derivative, log),
+        div(mul(exponent, base_derivative), base),
+    );
+    mul(power, sum)
+}
+
+/// the chain rule f'(u)*du for the builtin functions
+/// min, max and if pick the derivative of the argument they give
+/// a function without a known derivative eg. one that is not defined gives an error
+fn call_derivative(
+    name: &str,
+    args: &[OperationNode],
+    var: &str,
+) -> Result<OperationNode, CalculationError> {
+    let derivatives = args
+        .iter()
+        .map(|arg| arg.differentiate(var))
+        .collect::<Result<Vec<OperationNode>, CalculationError>>()?;
+
+    let derivative = match (name, args, &derivatives[..]) {
+        ("if", [condition, _, _], [_, then_derivative, else_derivative]) => Some(call(
+            "if",
+            vec![
+                condition.clone(),
+                then_derivative.clone(),
+                else_derivative.clone(),
+            ],
+        )),
+        ("min" | "max", [a, b], [a_derivative, b_derivative]) => {
+            let operator = match name {
+                "min" => Operators::Less,
+                _ => Operators::Greater,
+            };
+            Some(call(
+                "if",
+                vec![
+                    OperationNode::binary(operator, a.clone(), b.clone()),
+                    a_derivative.clone(),
+                    b_derivative.clone(),
+                ],
+            ))
+        }
+        // d(atan2(y, x)) = (x*dy - y*dx)/(x^2 + y^2)
+        ("atan2", [y, x], [y_derivative, x_derivative]) => Some(div(
+            sub(
+                mul(x.clone(), y_derivative.clone()),
+                mul(y.clone(), x_derivative.clone()),
+            ),
+            add(pow(x.clone(), number(2.0)), pow(y.clone(), number(2.0))),
+        )),
+        (_, [u], [u_derivative]) => {
+            outer_derivative(name, u.clone()).map(|outer| mul(outer, u_derivative.clone()))
+        }
+        _ => None,
+    };
+    derivative.ok_or_else(|| CalculationError::NoDerivative(call(name, args.to_vec()).to_string()))
+}
+
+/// the derivative of the function 'name' at 'u'
+/// floor, ceil and round do not change between their steps
+fn outer_derivative(name: &str, u: OperationNode) -> Option<OperationNode> {
+    let derivative = match name {
+        "sin" => call("cos", vec![u]),
+        "cos" => neg(call("sin", vec![u])),
+        "tan" => div(number(1.0), pow(call("cos", vec![u]), number(2.0))),
+        "asin" => div(number(1.0), sqrt(sub(number(1.0), pow(u, number(2.0))))),
+        "acos" => neg(div(
+            number(1.0),
+            sqrt(sub(number(1.0), pow(u, number(2.0)))),
+        )),
+        "atan" => div(number(1.0), add(number(1.0), pow(u, number(2.0)))),
+        "sinh" => call("cosh", vec![u]),
+        "cosh" => call("sinh", vec![u]),
+        "tanh" => div(number(1.0), pow(call("cosh", vec![u]), number(2.0))),
+        "sqrt" => div(number(1.0), mul(number(2.0), sqrt(u))),
+        "cbrt" => div(
+            number(1.0),
+            mul(number(3.0), pow(call("cbrt", vec![u]), number(2.0))),
+        ),
+        "exp" => call("exp", vec![u]),
+        "ln" => div(number(1.0), u),
+        "log" => div(number(1.0), mul(u, ln(number(10.0)))),
+        "log2" => div(number(1.0), mul(u, ln(number(2.0)))),
+        "abs" => div(u.clone(), call("abs", vec![u])),
+        "re" | "conj" => number(1.0),
+        "floor" | "ceil" | "round" | "im" | "arg" => number(0.0),
+        _ => return None,
+    };
+    Some(derivative)
+}
+
+/// the number of a node that is only a number eg. 2, -0.5
+fn as_number(node: &OperationNode) -> Option<f64> {
+    if node.operation != Operators::None {
+        return None;
+    }
+
+    match node.left_node.as_ref() {
+        NodeValue::UnitValue(value) => Some(*value),
+        NodeValue::NumberLiteral(literal) => {
+            parse_number_literal(&literal.chars().collect::<EquationString>())
+        }
+        NodeValue::Unary {
+            operator: UnaryOperators::Negate,
+            node,
+        } => as_number(node).map(|value| -value),
+        NodeValue::OperationValue(node) => as_number(node),
+        _ => None,
+    }
+}
+
+fn is_number(node: &OperationNode, value: f64) -> bool {
+    as_number(node) == Some(value)
+}
+
+/// the node of a number, a negative number is a sign in front of a literal eg. -(2)
+fn number(value: f64) -> OperationNode {
+    if value < 0.0 {
+        return neg(number(-value));
+    }
+    if value == 0.0 {
+        // -0 is written as 0
+        return OperationNode::leaf(NodeValue::NumberLiteral(String::from("0")));
+    }
+    if !value.is_finite() {
+        return OperationNode::leaf(NodeValue::UnitValue(value));
+    }
+
+    OperationNode::leaf(NodeValue::NumberLiteral(value.to_string()))
+}
+
+fn call(name: &str, args: Vec<OperationNode>) -> OperationNode {
+    OperationNode::leaf(NodeValue::Call {
+        name: name.to_string(),
+        args,
+    })
+}
+
+fn sqrt(value: OperationNode) -> OperationNode {
+    call("sqrt", vec![value])
+}
+
+/// the natural logarithm, ln(e) is 1
+fn ln(value: OperationNode) -> OperationNode {
+    if matches!(
+        value.left_node.as_ref(),
+        NodeValue::Constant(MathConstant::E)
+    ) {
+        return number(1.0);
+    }
+    call("ln", vec![value])
+}
+
+fn neg(value: OperationNode) -> OperationNode {
+    if let Some(number_value) = as_number(&value).filter(|value| *value <= 0.0) {
+        return number(-number_value);
+    }
+    if value.operation == Operators::None {
+        if let NodeValue::Unary {
+            operator: UnaryOperators::Negate,
+            node,
+        } = value.left_node.as_ref()
+        {
+            return node.clone();
+        }
+    }
+
+    OperationNode::leaf(NodeValue::Unary {
+        operator: UnaryOperators::Negate,
+        node: value,
+    })
+}
+
+fn add(left: OperationNode, right: OperationNode) -> OperationNode {
+    match (as_number(&left), as_number(&right)) {
+        (Some(left), Some(right)) => number(left + right),
+        (Some(0.0), _) => right,
+        (_, Some(0.0)) => left,
+        _ => OperationNode::binary(Operators::Plus, left, right),
+    }
+}
+
+fn sub(left: OperationNode, right: OperationNode) -> OperationNode {
+    match (as_number(&left), as_number(&right)) {
+        (Some(left), Some(right)) => number(left - right),
+        (Some(0.0), _) => neg(right),
+        (_, Some(0.0)) => left,
+        _ => OperationNode::binary(Operators::Minus, left, right),
+    }
+}
+
+fn mul(left: OperationNode, right: OperationNode) -> OperationNode {
+    match (as_number(&left), as_number(&right)) {
+        (Some(left), Some(right)) => number(left * right),
+        (Some(0.0), _) | (_, Some(0.0)) => number(0.0),
+        (Some(1.0), _) => right,
+        (_, Some(1.0)) => left,
+        (Some(-1.0), _) => neg(right),
+        (_, Some(-1.0)) => neg(left),
+        _ => OperationNode::binary(Operators::Mult, left, right),
+    }
+}
+
+/// a division of numbers is only done when the result is whole eg. 4/2 but not 1/3
+fn div(left: OperationNode, right: OperationNode) -> OperationNode {
+    match (as_number(&left), as_number(&right)) {
+        (Some(left), Some(right)) if right != 0.0 && (left / right).fract() == 0.0 => {
+            number(left / right)
+        }
+        (Some(0.0), _) => number(0.0),
+        (_, Some(1.0)) => left,
+        _ => OperationNode::binary(Operators::Div, left, right),
+    }
+}
+
+/// a power of numbers is only done when the result is whole eg. 2^3 but not 2^0.5
+fn pow(left: OperationNode, right: OperationNode) -> OperationNode {
+    match (as_number(&left), as_number(&right)) {
+        (Some(left), Some(right)) if left.powf(right).fract() == 0.0 => number(left.powf(right)),
+        (_, Some(0.0)) => number(1.0),
+        (_, Some(1.0)) => left,
+        _ => OperationNode::binary(Operators::Exp, left, right),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Environment;
+
+    #[test]
+    pub fn test_derivative() {
+        let cases = [
+            ("x^2*sin(x)", "x", "2*x*sin(x) + x^2*cos(x)"),
+            ("3*x + 2", "x", "3"),
+            ("x/y", "x", "1/y"),
+            ("x/x", "x", "0"),
+            ("(x + 1)/(x + 1)", "x", "0"),
+            ("x^2/x", "x", "1"),
+            ("y^2 + x", "y", "2*y"),
+            ("e^(2*x)", "x", "2*e^(2*x)"),
+            ("2^x", "x", "2^x*ln(2)"),
+            ("ln(cos(x))", "x", "-(sin(x)/cos(x))"),
+            ("-sqrt(x)", "x", "-(1/(2*sqrt(x)))"),
+            ("max(x, 1)", "x", "if(x > 1, 1, 0)"),
+            ("5", "x", "0"),
+            ("x + 5!", "x", "1"),
+            ("5!", "x", "0"),
+            ("y!", "x", "0"),
+            ("x + (1 < 2)", "x", "1"),
+            ("x*(y > 1)", "x", "y > 1"),
+        ];
+
+        for (eq, var, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let derivative = operation_node.derivative(var).unwrap();
+            assert_eq!(derivative.to_string(), result, "{}", eq);
+        }
+    }
+
+    #[test]
+    pub fn test_derivative_value() {
+        // the derivative is checked against the slope of the function
+        let cases = [
+            "x^2*sin(x)",
+            "x^x",
+            "(x^2 + 1)/(x - 3)",
+            "tan(x)^3",
+            "atan2(x, 2)",
+            "log(x) + log2(x) + cbrt(x)",
+            "abs(x - 5) + acos(x/2) + asin(x/3)",
+            "tanh(x) + sinh(x)*cosh(x) + atan(x)",
+            "exp(-x^2) + 50%*x",
+        ];
+
+        for eq in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let derivative = operation_node.derivative("x").unwrap();
+            let at = |node: &OperationNode, x: f64| {
+                let mut env = Environment::new();
+                env.set("x", x);
+                node.calculate_with(&env).unwrap()
+            };
+
+            let step = 1e-6;
+            let slope =
+                (at(&operation_node, 1.2 + step) - at(&operation_node, 1.2 - step)) / (2.0 * step);
+            let value = at(&derivative, 1.2);
+            assert!(
+                (value - slope).abs() < 1e-6 * slope.abs().max(1.0),
+                "{}",
+                eq
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_derivative_functions() {
+        let mut env = Environment::new();
+        let body = OperationNode::new("x^2 + a").unwrap();
+        env.define_function("f", vec![String::from("x")], body);
+        env.define_function(
+            "g",
+            vec![String::from("t")],
+            OperationNode::new("g(t)").unwrap(),
+        );
+
+        let derivative = |eq: &str| OperationNode::new(eq).unwrap().derivative_with("x", &env);
+        assert_eq!(derivative("f(x)").unwrap().to_string(), "2*x");
+        assert_eq!(derivative("f(3*x)").unwrap().to_string(), "18*x");
+        assert_eq!(derivative("f(y) + x").unwrap().to_string(), "1");
+        assert_eq!(
+            derivative("g(x)"),
+            Err(CalculationError::RecursionLimit(String::from("g")))
+        );
+        assert!(matches!(
+            derivative("f(x, 1)"),
+            Err(CalculationError::ArityMismatch { .. })
+        ));
+    }
+
+    #[test]
+    pub fn test_no_derivative() {
+        let no_derivative = |node: &str| Err(CalculationError::NoDerivative(String::from(node)));
+
+        let derivative = |eq: &str| OperationNode::new(eq).unwrap().derivative("x");
+        assert_eq!(derivative("x > 1"), no_derivative("x > 1"));
+        assert_eq!(derivative("2*x!"), no_derivative("x!"));
+        assert_eq!(derivative("f(x)"), no_derivative("f(x)"));
+    }
+}
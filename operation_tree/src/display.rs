@@ -0,0 +1,282 @@
+use std::fmt;
+
+use eq_split::operators::{Operators, UnaryOperators};
+
+use crate::{NodeValue, OperationNode};
+
+impl fmt::Display for OperationNode {
+    /// the tree written as an eq eg. 2*x*sin(x) + x^2*cos(x)
+    /// only the parentheses that the precedence of the operators needs are written
+    /// so that parsing the eq gives the same tree
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.operation == Operators::None {
+            return write!(f, "{}", self.left_node);
+        }
+
+        // ^ is right associative eg. 2^3^2 = 2^(3^2)
+        // and the other operators are left associative eg. 1-2-3 = (1-2)-3
+        let precedence = self.operation.precedence();
+        let (left_precedence, mut right_precedence) = match self.operation {
+            Operators::Exp => (precedence + 1, precedence),
+            _ => (precedence, precedence + 1),
+        };
+
+        let sign = UnaryOperators::Negate.precedence();
+        if value_precedence(&self.right_node) == sign {
+            match self.operation {
+                // a sign after ^ is a part of the exponent eg. 2^-1
+                Operators::Exp => right_precedence = sign,
+                // a % followed by a sign is a percent eg. 5%-1 = 5% - 1
+                Operators::Modulo => right_precedence = sign + 1,
+                _ => {}
+            }
+        }
+
+        let left = Operand(
+            &*self.left_node,
+            value_precedence(&self.left_node),
+            left_precedence,
+        );
+        let right = Operand(
+            &*self.right_node,
+            value_precedence(&self.right_node),
+            right_precedence,
+        );
+        let symbol = self.operation.symbol();
+        match self.operation {
+            Operators::Mult | Operators::Div | Operators::IntDiv | Operators::Exp => {
+                write!(f, "{}{}{}", left, symbol, right)
+            }
+            _ => write!(f, "{} {} {}", left, symbol, right),
+        }
+    }
+}
+
+impl fmt::Display for NodeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnitValue(value) => write!(f, "{}", value),
+            Self::NumberLiteral(literal) => write!(f, "{}", literal),
+            Self::Constant(constant) => write!(f, "{}", constant.name()),
+            Self::BooleanValue(value) => write!(f, "{}", value),
+            Self::Variable(name) => write!(f, "{}", name),
+            Self::Call { name, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<String>>();
+                write!(f, "{}({})", name, args.join(", "))
+            }
+            Self::Unary { operator, node } => {
+                // the signs can follow each other eg. --x
+                let node = Operand(node, node_precedence(node), operator.precedence());
+                match operator {
+                    UnaryOperators::Not => write!(f, "not {}", node),
+                    _ => write!(f, "{}{}", operator.symbol(), node),
+                }
+            }
+            Self::Postfix { operator, node } => {
+                let node = Operand(node, node_precedence(node), operator.precedence());
+                write!(f, "{}{}", node, operator.symbol())
+            }
+            Self::OperationValue(node) => write!(f, "{}", node),
+        }
+    }
+}
+
+/// a value with the precedence of its last operation and the least precedence it can have
+/// without parentheses eg. the x+1 of 2*(x+1) but not the x*2 of x*2+1
+struct Operand<'a>(&'a dyn fmt::Display, u8, u8);
+
+impl fmt::Display for Operand<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Operand(value, precedence, least_precedence) = self;
+        if precedence < least_precedence {
+            write!(f, "({})", value)
+        } else {
+            write!(f, "{}", value)
+        }
+    }
+}
+
+/// the precedence of the operation that is done last in the value
+/// a single value has the highest eg. 2, x, sin(x) and a negative number the one of a sign
+pub(crate) fn value_precedence(value: &NodeValue) -> u8 {
+    match value {
+        NodeValue::UnitValue(value) if value.is_sign_negative() && !value.is_nan() => {
+            UnaryOperators::Negate.precedence()
+        }
+        NodeValue::Unary { operator, .. } => operator.precedence(),
+        NodeValue::Postfix { operator, .. } => operator.precedence(),
+        NodeValue::OperationValue(node) => node_precedence(node),
+        _ => u8::MAX,
+    }
+}
+
+fn node_precedence(node: &OperationNode) -> u8 {
+    match node.operation {
+        Operators::None => value_precedence(&node.left_node),
+        operation => operation.precedence(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use eq_split::operators::PostfixOperators;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::MathConstant;
+
+    #[test]
+    pub fn test_display() {
+        let cases = [
+            "2*x*sin(x) + x^2*cos(x)",
+            "1 - (2 - 3)",
+            "2^3^2",
+            "(2^3)^2",
+            "-x^2",
+            "(-x)^2",
+            "2^-x",
+            "x*-y",
+            "--x",
+            "5% - 1",
+            "5 % (-1)",
+            "(x + 1)!",
+            "-x!",
+            "not x > 1 && y",
+            "not (x && y)",
+            "(x to m) + 1",
+            "1 + 2 xor 3 & 4 << 1",
+            "(1 | 2) & 3",
+            "x == y == (z != 1)",
+            "max(x + 1, -2)",
+        ];
+
+        for eq in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(operation_node.to_string(), eq);
+        }
+
+        let operation_node = OperationNode::new("((x))*(((2)))+(y)").unwrap();
+        assert_eq!(operation_node.to_string(), "x*2 + y");
+    }
+
+    #[test]
+    pub fn test_display_signs() {
+        let cases = [
+            ("-x", "-x"),
+            ("+x", "+x"),
+            ("--1", "--1"),
+            ("-+-x", "-+-x"),
+            ("-(1+2)", "-(1 + 2)"),
+            ("2*-x", "2*-x"),
+            ("-x - -y", "-x - -y"),
+            ("~x & -1", "~x & -1"),
+            ("-(-x)^2", "-(-x)^2"),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(operation_node.to_string(), result);
+        }
+    }
+
+    /// the node of the operation the way the parser makes it, with each value in a leaf
+    fn binary(operation: Operators, left: OperationNode, right: OperationNode) -> OperationNode {
+        OperationNode {
+            operation,
+            left_node: Box::new(NodeValue::OperationValue(left)),
+            right_node: Box::new(NodeValue::OperationValue(right)),
+        }
+    }
+
+    fn value() -> impl Strategy<Value = OperationNode> {
+        let literals = ["0", "2", "0.5", "1.25", "6.02e-23", "400"];
+        prop_oneof![
+            prop::sample::select(vec!["x", "y", "rate"])
+                .prop_map(|name| NodeValue::Variable(name.to_string())),
+            prop::sample::select(literals.to_vec())
+                .prop_map(|literal| NodeValue::NumberLiteral(literal.to_string())),
+            prop::sample::select(vec![MathConstant::Pi, MathConstant::E])
+                .prop_map(NodeValue::Constant),
+            any::<bool>().prop_map(NodeValue::BooleanValue),
+        ]
+        .prop_map(OperationNode::leaf)
+    }
+
+    fn tree() -> impl Strategy<Value = OperationNode> {
+        let operators = vec![
+            Operators::Plus,
+            Operators::Minus,
+            Operators::PlusMinus,
+            Operators::Mult,
+            Operators::Div,
+            Operators::Modulo,
+            Operators::IntDiv,
+            Operators::Rem,
+            Operators::Exp,
+            Operators::BitAnd,
+            Operators::BitOr,
+            Operators::BitXor,
+            Operators::ShiftLeft,
+            Operators::ShiftRight,
+            Operators::Less,
+            Operators::LessEqual,
+            Operators::Greater,
+            Operators::GreaterEqual,
+            Operators::Equal,
+            Operators::NotEqual,
+            Operators::And,
+            Operators::Or,
+            Operators::Convert,
+        ];
+        let unary_operators = vec![
+            UnaryOperators::Negate,
+            UnaryOperators::Plus,
+            UnaryOperators::BitNot,
+            UnaryOperators::Not,
+        ];
+        let postfix_operators = vec![PostfixOperators::Factorial, PostfixOperators::Percent];
+
+        value().prop_recursive(4, 32, 3, move |node| {
+            prop_oneof![
+                (
+                    prop::sample::select(operators.clone()),
+                    node.clone(),
+                    node.clone()
+                )
+                    .prop_map(|(operation, left, right)| binary(operation, left, right)),
+                (prop::sample::select(unary_operators.clone()), node.clone()).prop_map(
+                    |(operator, node)| OperationNode::leaf(NodeValue::Unary { operator, node })
+                ),
+                (
+                    prop::sample::select(postfix_operators.clone()),
+                    node.clone()
+                )
+                    .prop_map(|(operator, node)| OperationNode::leaf(
+                        NodeValue::Postfix { operator, node }
+                    )),
+                (
+                    prop::sample::select(vec!["sin", "max"]),
+                    prop::collection::vec(node, 1..3)
+                )
+                    .prop_map(|(name, args)| OperationNode::leaf(
+                        NodeValue::Call {
+                            name: name.to_string(),
+                            args
+                        }
+                    )),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_display_parses_to_the_same_tree(operation_node in tree()) {
+            let eq = operation_node.to_string();
+            let parsed = OperationNode::new(&eq);
+            prop_assert!(parsed.as_ref() == Ok(&operation_node), "{} is {:?}", eq, parsed);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{functions::Function, NumberMode, OperationNode, Value};
+
+/// calls deeper than this are treated as a function calling itself forever
+const MAX_CALL_DEPTH: usize = 256;
+
+/// values of the variables and the functions defined by the user
+/// used when calculating an operation tree
+/// the same tree can be calculated with different environments
+#[derive(Debug, Clone, Default)]
+pub struct Environment {
+    variables: HashMap<String, Value>,
+    /// the parameters of the function being called, only seen by its body
+    params: HashMap<String, Value>,
+    functions: HashMap<String, Rc<Function>>,
+    mode: NumberMode,
+    call_depth: usize,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// give the variable 'name' a value, replacing its previous value
+    pub fn set(&mut self, name: &str, value: impl Into<Value>) {
+        self.variables.insert(name.to_string(), value.into());
+    }
+
+    /// a parameter of the function being called is used before a variable with the same name
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.params
+            .get(name)
+            .or_else(|| self.variables.get(name))
+            .cloned()
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Value> {
+        self.variables.remove(name)
+    }
+
+    /// calculate with f64 or with decimals eg. 0.1+0.2 = 0.3
+    pub fn set_mode(&mut self, mode: NumberMode) {
+        self.mode = mode;
+    }
+
+    pub fn mode(&self) -> &NumberMode {
+        &self.mode
+    }
+
+    /// define the function 'name' eg. f(x, y) = x^2 + y
+    /// replacing its previous definition
+    pub fn define_function(&mut self, name: &str, params: Vec<String>, body: OperationNode) {
+        let function = Function { params, body };
+        self.functions.insert(name.to_string(), Rc::new(function));
+    }
+
+    pub fn get_function(&self, name: &str) -> Option<Rc<Function>> {
+        self.functions.get(name).cloned()
+    }
+
+    /// the environment used inside a call of 'function'
+    /// the parameters are given the values of 'args' and the parameters of the caller are not seen
+    /// eg. f(1) is 11 inside g(y) = f(1) for f(x) = x + y and y = 10
+    /// returns None if there are too many nested calls
+    pub(crate) fn enter_call(&self, function: &Function, args: &[Value]) -> Option<Self> {
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return None;
+        }
+
+        let mut env = self.clone();
+        env.call_depth += 1;
+        env.params = function
+            .params
+            .iter()
+            .cloned()
+            .zip(args.iter().cloned())
+            .collect();
+        Some(env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_environment() {
+        let mut env = Environment::new();
+        assert_eq!(env.get("x"), None);
+
+        env.set("x", 2.0);
+        env.set("rate", 0.5);
+        assert_eq!(env.get("x"), Some(Value::Number(2.0)));
+
+        env.set("x", true);
+        assert_eq!(env.get("x"), Some(Value::Boolean(true)));
+        assert_eq!(env.remove("rate"), Some(Value::Number(0.5)));
+        assert_eq!(env.get("rate"), None);
+    }
+
+    #[test]
+    pub fn test_enter_call() {
+        let mut env = Environment::new();
+        env.set("x", 1.0);
+        env.set("y", 2.0);
+
+        let body = OperationNode::new("x+y").unwrap();
+        env.define_function("f", vec![String::from("x")], body);
+        let function = env.get_function("f").unwrap();
+
+        let args = [Value::Number(5.0)];
+        let call_env = env.enter_call(&function, &args).unwrap();
+        assert_eq!(call_env.get("x"), Some(Value::Number(5.0)));
+        assert_eq!(call_env.get("y"), Some(Value::Number(2.0)));
+        assert_eq!(env.get("x"), Some(Value::Number(1.0)));
+
+        // the parameters of a caller are not seen inside another call
+        let body = OperationNode::new("y").unwrap();
+        env.define_function("g", vec![String::from("z")], body);
+        let inner = env.get_function("g").unwrap();
+        let inner_env = call_env.enter_call(&inner, &args).unwrap();
+        assert_eq!(inner_env.get("y"), Some(Value::Number(2.0)));
+        assert_eq!(inner_env.get("x"), Some(Value::Number(1.0)));
+
+        let mut call_env = env;
+        for _ in 0..MAX_CALL_DEPTH {
+            call_env = call_env.enter_call(&function, &args).unwrap();
+        }
+        assert!(call_env.enter_call(&function, &args).is_none());
+    }
+}
@@ -0,0 +1,119 @@
+use std::{error::Error, fmt};
+
+use crate::Value;
+
+/// errors that can happen while calculating an operation tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalculationError {
+    /// a variable that has no value in the environment
+    UnboundVariable(String),
+    /// a number literal of a tree that is not a number eg. NodeValue::NumberLiteral("1.2.3")
+    InvalidLiteral(String),
+    /// a call to a function that does not exist
+    UnknownFunction(String),
+    /// a call to a function with the wrong number of arguments
+    ArityMismatch {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    /// a function that keeps calling itself without end
+    RecursionLimit(String),
+    /// a bitwise operator used on a number that is not whole eg. 1.5 & 1
+    NonIntegerOperand { operator: String, value: f64 },
+    /// a bitwise operator with a result that does not fit in an i64 eg. 1 << 63
+    BitwiseOverflow(String),
+    /// a boolean used where a number is needed eg. (1 < 2) + 1
+    ExpectedNumber(Value),
+    /// a number used where a boolean is needed eg. if(1, 2, 3)
+    ExpectedBoolean(Value),
+    /// a division by zero in the decimal and rational modes, which have no infinity
+    DivisionByZero,
+    /// a result of f64 that has no decimal eg. sqrt(-1) in the decimal mode
+    NotADecimal(f64),
+    /// a complex number used where a real number is needed eg. i < 1, i & 1
+    ExpectedReal(Value),
+    /// the imaginary unit i used outside of the complex mode without a variable i
+    ComplexModeRequired,
+    /// an assignment to i or a parameter named i in the complex mode, where i is the imaginary unit
+    ImaginaryUnitAssignment,
+    /// an interval eg. [1, 2] or 1 ± 0.1 used outside of the interval mode
+    IntervalModeRequired,
+    /// a comparison of intervals that is true for some of their numbers and false for others
+    /// eg. [1, 3] < 2, the operator is kept
+    UncertainComparison(String),
+    /// an operator, a constant or a function that has no result with the number type
+    /// eg. 1/0 or pi with the fractions of 'evaluate_as'
+    NoResult(String),
+    /// values with units of different dimensions eg. 3 m + 2 s, 5 ft to kg
+    /// the units are kept with the operator eg. m + s, a number has the unit 1
+    IncompatibleUnits(String),
+    /// a unit to a power that does not give whole powers eg. sqrt(2 m)
+    UnitPower { unit: String, power: f64 },
+    /// a conversion to something that is not a unit eg. 5 m to 3
+    ExpectedUnit(Value),
+    /// a part of a derivative that has no derivative by the variable eg. x > 1, x!
+    NoDerivative(String),
+}
+
+impl fmt::Display for CalculationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnboundVariable(name) => write!(f, "Variable '{}' has no value", name),
+            Self::InvalidLiteral(literal) => write!(f, "'{}' is not a number", literal),
+            Self::UnknownFunction(name) => write!(f, "Function '{}' does not exist", name),
+            Self::ArityMismatch {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Function '{}' takes {} argument(s) but {} were given",
+                name, expected, found
+            ),
+            Self::RecursionLimit(name) => {
+                write!(f, "Function '{}' calls itself too many times", name)
+            }
+            Self::NonIntegerOperand { operator, value } => write!(
+                f,
+                "Operator '{}' needs whole numbers but got {}",
+                operator, value
+            ),
+            Self::BitwiseOverflow(operator) => {
+                write!(f, "The result of '{}' does not fit in 64 bits", operator)
+            }
+            Self::ExpectedNumber(value) => write!(f, "Expected a number but got {}", value),
+            Self::ExpectedBoolean(value) => {
+                write!(f, "Expected true or false but got {}", value)
+            }
+            Self::DivisionByZero => write!(f, "Division by zero"),
+            Self::NotADecimal(value) => write!(f, "{} cannot be written as a decimal", value),
+            Self::ExpectedReal(value) => write!(f, "Expected a real number but got {}", value),
+            Self::ComplexModeRequired => write!(f, "i can only be used in the complex mode"),
+            Self::ImaginaryUnitAssignment => {
+                write!(f, "i is the imaginary unit and cannot be assigned")
+            }
+            Self::IntervalModeRequired => {
+                write!(f, "Intervals can only be used in the interval mode")
+            }
+            Self::UncertainComparison(operator) => write!(
+                f,
+                "Comparison '{}' is true for some values of the intervals and false for others",
+                operator
+            ),
+            Self::NoResult(name) => write!(f, "'{}' has no result with this number type", name),
+            Self::IncompatibleUnits(units) => write!(f, "The units of '{}' do not match", units),
+            Self::UnitPower { unit, power } => {
+                write!(
+                    f,
+                    "Unit '{}' cannot be raised to the power of {}",
+                    unit, power
+                )
+            }
+            Self::ExpectedUnit(value) => write!(f, "Expected a unit but got {}", value),
+            Self::NoDerivative(node) => write!(f, "'{}' has no derivative", node),
+        }
+    }
+}
+
+impl Error for CalculationError {}
@@ -0,0 +1,264 @@
+use eq_split::arithmetic::Number;
+use num_complex::Complex64;
+
+use crate::{
+    decimal::{call_decimal_function, round_to_precision},
+    integer::whole_or_fraction,
+    interval::{call_interval_function, Interval},
+    units::call_quantity_function,
+    BigRational, CalculationError, Environment, NumberMode, OperationNode, Value,
+};
+
+/// a function defined by the user eg. f(x, y) = x^2 + y
+/// the body is calculated with the parameters set to the arguments of the call
+#[derive(Debug)]
+pub struct Function {
+    pub params: Vec<String>,
+    pub body: OperationNode,
+}
+
+/// a function that is always available eg. sin(x), max(x,y)
+/// with the number of arguments it takes
+type BuiltinFunction = (usize, fn(&[f64]) -> f64);
+
+/// log is the logarithm to base 10 and ln is the natural logarithm
+/// the trigonometric functions work in radians
+/// re, im, arg and conj are the parts of a complex number, a real number has no imaginary part
+pub fn match_builtin_function(name: &str) -> Option<BuiltinFunction> {
+    let function: BuiltinFunction = match name {
+        "sin" => (1, |args| args[0].sin()),
+        "cos" => (1, |args| args[0].cos()),
+        "tan" => (1, |args| args[0].tan()),
+        "asin" => (1, |args| args[0].asin()),
+        "acos" => (1, |args| args[0].acos()),
+        "atan" => (1, |args| args[0].atan()),
+        "sinh" => (1, |args| args[0].sinh()),
+        "cosh" => (1, |args| args[0].cosh()),
+        "tanh" => (1, |args| args[0].tanh()),
+        "sqrt" => (1, |args| args[0].sqrt()),
+        "cbrt" => (1, |args| args[0].cbrt()),
+        "ln" => (1, |args| args[0].ln()),
+        "log" => (1, |args| args[0].log10()),
+        "log2" => (1, |args| args[0].log2()),
+        "exp" => (1, |args| args[0].exp()),
+        "abs" => (1, |args| args[0].abs()),
+        "floor" => (1, |args| args[0].floor()),
+        "ceil" => (1, |args| args[0].ceil()),
+        "round" => (1, |args| args[0].round()),
+        "min" => (2, |args| args[0].min(args[1])),
+        "max" => (2, |args| args[0].max(args[1])),
+        "atan2" => (2, |args| args[0].atan2(args[1])),
+        "re" => (1, |args| args[0]),
+        "im" => (1, |_| 0.0),
+        "arg" => (1, |args| 0.0_f64.atan2(args[0])),
+        "conj" => (1, |args| args[0]),
+        _ => return None,
+    };
+
+    Some(function)
+}
+
+/// check if 'name' is a function that is always there eg. sin, max, interval
+pub fn is_builtin_function(name: &str) -> bool {
+    name == "interval" || match_builtin_function(name).is_some()
+}
+
+/// the builtin functions are calculated with f64 in every mode but the interval mode
+/// unless they have an exact result in the mode eg. sqrt(2) to the precision of the decimal mode
+/// interval(a, b) is the interval from a to b, which is what [a, b] is written as
+/// kept apart from 'call_function' so that the recursive calls use less of the stack
+pub fn call_builtin_function(
+    name: &str,
+    args: &[Value],
+    env: &Environment,
+) -> Result<Value, CalculationError> {
+    if name == "interval" {
+        return call_interval(args, env);
+    }
+
+    let (arity, function) = match_builtin_function(name)
+        .ok_or_else(|| CalculationError::UnknownFunction(name.to_string()))?;
+    check_arity(name, arity, args)?;
+
+    if args.iter().any(|arg| matches!(arg, Value::Quantity(_))) {
+        return call_quantity_function(name, args);
+    }
+
+    if let NumberMode::Rational | NumberMode::Integer = env.mode() {
+        if let Some(result) = call_rational_builtin_function(name, args) {
+            return match env.mode() {
+                NumberMode::Integer => Ok(whole_or_fraction(result)),
+                _ => Ok(Value::from(result)),
+            };
+        }
+    }
+
+    if let NumberMode::Decimal(ctx) = env.mode() {
+        if let Some(result) = call_decimal_function(name, args, ctx) {
+            return Ok(Value::from(round_to_precision(result, ctx)));
+        }
+    }
+
+    if let NumberMode::Interval = env.mode() {
+        let args = args
+            .iter()
+            .map(|arg| arg.as_interval())
+            .collect::<Result<Vec<Interval>, CalculationError>>()?;
+        return call_interval_function(name, &args, function).map(Value::Interval);
+    }
+
+    if let NumberMode::Complex = env.mode() {
+        let args = args
+            .iter()
+            .map(|arg| arg.as_complex())
+            .collect::<Result<Vec<_>, CalculationError>>()?;
+        if let Some(result) = Complex64::call_function(name, &args) {
+            return Ok(Value::Complex(result));
+        }
+    }
+
+    let args = args
+        .iter()
+        .map(|arg| arg.as_number())
+        .collect::<Result<Vec<f64>, CalculationError>>()?;
+
+    // a result of f64 is kept as it is in the decimal mode so that it is not shown as exact
+    let result = function(&args);
+    match env.mode() {
+        NumberMode::Complex => Ok(Value::Complex(result.into())),
+        _ => Ok(Value::Number(result)),
+    }
+}
+
+/// the smallest interval holding both arguments eg. interval(1, 2) = [1, 2]
+fn call_interval(args: &[Value], env: &Environment) -> Result<Value, CalculationError> {
+    check_arity("interval", 2, args)?;
+    if !matches!(env.mode(), NumberMode::Interval) {
+        return Err(CalculationError::IntervalModeRequired);
+    }
+
+    let start = args[0].as_interval()?;
+    let end = args[1].as_interval()?;
+    Ok(Value::Interval(start.hull(&end)))
+}
+
+/// the builtin functions with an exact result in the integer and rational modes eg. abs, floor, max
+/// None when an argument is not a whole number or a fraction
+fn call_rational_builtin_function(name: &str, args: &[Value]) -> Option<BigRational> {
+    let args = args
+        .iter()
+        .map(|arg| match arg {
+            Value::Rational(value) => Some(*value.clone()),
+            Value::Integer(value) => Some(BigRational::from_integer(*value.clone())),
+            _ => None,
+        })
+        .collect::<Option<Vec<BigRational>>>()?;
+    BigRational::call_function(name, &args)
+}
+
+pub fn check_arity<T>(name: &str, arity: usize, args: &[T]) -> Result<(), CalculationError> {
+    if args.len() != arity {
+        return Err(CalculationError::ArityMismatch {
+            name: name.to_string(),
+            expected: arity,
+            found: args.len(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::call_function;
+
+    fn numbers(values: &[f64]) -> Vec<Value> {
+        values.iter().map(|value| Value::Number(*value)).collect()
+    }
+
+    #[test]
+    pub fn test_call_function() {
+        let env = Environment::new();
+        assert_eq!(
+            call_function("sqrt", numbers(&[16.0]), &env),
+            Ok(Value::Number(4.0))
+        );
+        assert_eq!(
+            call_function("log", numbers(&[1000.0]), &env),
+            Ok(Value::Number(3.0))
+        );
+        assert_eq!(
+            call_function("max", numbers(&[1.0, -2.0]), &env),
+            Ok(Value::Number(1.0))
+        );
+        assert_eq!(
+            call_function("abs", numbers(&[-2.5]), &env),
+            Ok(Value::Number(2.5))
+        );
+
+        assert_eq!(
+            call_function("foo", numbers(&[1.0]), &env),
+            Err(CalculationError::UnknownFunction(String::from("foo")))
+        );
+        assert_eq!(
+            call_function("sin", numbers(&[1.0, 2.0]), &env),
+            Err(CalculationError::ArityMismatch {
+                name: String::from("sin"),
+                expected: 1,
+                found: 2
+            })
+        );
+        assert_eq!(
+            call_function("sqrt", vec![Value::Boolean(true)], &env),
+            Err(CalculationError::ExpectedNumber(Value::Boolean(true)))
+        );
+    }
+
+    #[test]
+    pub fn test_call_user_function() {
+        let mut env = Environment::new();
+        let params = vec![String::from("x"), String::from("y")];
+        let body = OperationNode::new("x^2+y").unwrap();
+        env.define_function("f", params, body);
+
+        assert_eq!(
+            call_function("f", numbers(&[3.0, 1.0]), &env),
+            Ok(Value::Number(10.0))
+        );
+        assert_eq!(
+            call_function("f", numbers(&[3.0]), &env),
+            Err(CalculationError::ArityMismatch {
+                name: String::from("f"),
+                expected: 2,
+                found: 1
+            })
+        );
+
+        // functions defined by the user are used before the builtin functions
+        let body = OperationNode::new("x+1").unwrap();
+        env.define_function("abs", vec![String::from("x")], body);
+        assert_eq!(
+            call_function("abs", numbers(&[-2.0]), &env),
+            Ok(Value::Number(-1.0))
+        );
+
+        let body = OperationNode::new("g(x)+1").unwrap();
+        env.define_function("g", vec![String::from("x")], body);
+        assert_eq!(
+            call_function("g", numbers(&[1.0]), &env),
+            Err(CalculationError::RecursionLimit(String::from("g")))
+        );
+
+        // a parameter that shadows a variable is only seen by its own body
+        env.set("y", 10.0);
+        let body = OperationNode::new("x + y").unwrap();
+        env.define_function("h", vec![String::from("x")], body);
+        let body = OperationNode::new("h(1)").unwrap();
+        env.define_function("k", vec![String::from("y")], body);
+        assert_eq!(
+            call_function("k", numbers(&[2.0]), &env),
+            Ok(Value::Number(11.0))
+        );
+    }
+}
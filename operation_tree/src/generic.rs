@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+
+use eq_split::{
+    arithmetic::Number,
+    operators::{Operators, PostfixOperators, UnaryOperators},
+};
+
+use crate::{
+    functions::{check_arity, match_builtin_function, Function},
+    math_characters::{unbound_variable, IMAGINARY_UNIT},
+    scope::{evaluate, Scope},
+    CalculationError, Environment, MathConstant, OperationNode, Value,
+};
+
+impl OperationNode {
+    /// calculate the tree with the number type 'N' eg. f32, fractions or the number type of another crate
+    /// the variables are taken from 'variables' and a result that is true or false is 1 or 0
+    /// the builtin functions that 'N' does not have are calculated with f64
+    pub fn evaluate_as<N: Number>(
+        &self,
+        variables: &HashMap<String, N>,
+    ) -> Result<N, CalculationError> {
+        self.evaluate_as_with(variables, &Environment::new())
+    }
+
+    /// the functions defined in 'env' can be called eg. f(x) = x^2
+    /// the variables and the mode of 'env' are not used
+    pub fn evaluate_as_with<N: Number>(
+        &self,
+        variables: &HashMap<String, N>,
+        env: &Environment,
+    ) -> Result<N, CalculationError> {
+        let scope = NumberScope {
+            variables,
+            params: HashMap::new(),
+            env: env.clone(),
+        };
+
+        match evaluate(self, &scope)? {
+            GenericValue::Number(value) => Ok(value),
+            GenericValue::Boolean(value) => Ok(N::from_whole(i64::from(value))),
+        }
+    }
+}
+
+/// a value calculated with the number type 'N'
+/// the booleans are kept apart as with 'evaluate_with' eg. (1 < 2) + 1 is an error
+#[derive(Clone)]
+enum GenericValue<N> {
+    Number(N),
+    Boolean(bool),
+}
+
+impl<N: Number> GenericValue<N> {
+    fn into_number(self) -> Result<N, CalculationError> {
+        match self {
+            Self::Number(value) => Ok(value),
+            Self::Boolean(value) => Err(CalculationError::ExpectedNumber(Value::Boolean(value))),
+        }
+    }
+
+    fn as_boolean(&self) -> Result<bool, CalculationError> {
+        match self {
+            Self::Boolean(value) => Ok(*value),
+            Self::Number(value) => Err(CalculationError::ExpectedBoolean(Value::Number(
+                value.to_float(),
+            ))),
+        }
+    }
+}
+
+/// the variables of 'evaluate_as' and the parameters of the calls of the functions of 'env'
+struct NumberScope<'a, N> {
+    variables: &'a HashMap<String, N>,
+    params: HashMap<String, GenericValue<N>>,
+    env: Environment,
+}
+
+impl<N: Number> Scope for NumberScope<'_, N> {
+    type Value = GenericValue<N>;
+
+    fn boolean(value: bool) -> GenericValue<N> {
+        GenericValue::Boolean(value)
+    }
+
+    fn as_boolean(value: &GenericValue<N>) -> Result<bool, CalculationError> {
+        value.as_boolean()
+    }
+
+    fn number(&self, value: f64) -> Result<GenericValue<N>, CalculationError> {
+        N::from_float(value)
+            .map(GenericValue::Number)
+            .ok_or_else(|| no_result(&value.to_string()))
+    }
+
+    fn literal(&self, literal: &str) -> Result<GenericValue<N>, CalculationError> {
+        N::parse_literal(literal)
+            .map(GenericValue::Number)
+            .ok_or_else(|| no_result(literal))
+    }
+
+    fn constant(&self, constant: MathConstant) -> Result<GenericValue<N>, CalculationError> {
+        let value = match constant {
+            MathConstant::Pi => N::pi(),
+            MathConstant::E => N::e(),
+        };
+        value
+            .map(GenericValue::Number)
+            .ok_or_else(|| no_result(constant.name()))
+    }
+
+    /// i is the imaginary unit of the number types that have one eg. Complex64
+    fn variable(&self, name: &str) -> Result<GenericValue<N>, CalculationError> {
+        let unit = N::imaginary_unit().filter(|_| name == IMAGINARY_UNIT);
+        if let Some(unit) = unit {
+            return Ok(GenericValue::Number(unit));
+        }
+
+        self.params
+            .get(name)
+            .cloned()
+            .or_else(|| self.variables.get(name).cloned().map(GenericValue::Number))
+            .ok_or_else(|| unbound_variable(name))
+    }
+
+    fn calculate_operation(
+        &self,
+        operator: Operators,
+        left_value: GenericValue<N>,
+        right_value: GenericValue<N>,
+    ) -> Result<GenericValue<N>, CalculationError> {
+        calculate_operation(operator, left_value, right_value)
+    }
+
+    fn calculate_unary(
+        &self,
+        operator: UnaryOperators,
+        value: GenericValue<N>,
+    ) -> Result<GenericValue<N>, CalculationError> {
+        let value = value.into_number()?;
+        if operator.is_bitwise() {
+            OperationNode::check_integer(operator.symbol(), value.to_float())?;
+        }
+        Ok(GenericValue::Number(operator.calculate(value)))
+    }
+
+    fn calculate_postfix(
+        &self,
+        operator: PostfixOperators,
+        value: GenericValue<N>,
+    ) -> Result<GenericValue<N>, CalculationError> {
+        operator
+            .calculate(value.into_number()?)
+            .map(GenericValue::Number)
+            .ok_or_else(|| no_result(operator.symbol()))
+    }
+
+    fn call_builtin_function(
+        &self,
+        name: &str,
+        args: Vec<GenericValue<N>>,
+    ) -> Result<GenericValue<N>, CalculationError> {
+        let args = args
+            .into_iter()
+            .map(GenericValue::into_number)
+            .collect::<Result<Vec<N>, CalculationError>>()?;
+        call_builtin_function(name, &args).map(GenericValue::Number)
+    }
+
+    fn environment(&self) -> &Environment {
+        &self.env
+    }
+
+    /// the parameters of the caller are not seen in the body
+    fn call_scope(&self, function: &Function, args: Vec<GenericValue<N>>) -> Option<Self> {
+        Some(NumberScope {
+            variables: self.variables,
+            params: function.params.iter().cloned().zip(args).collect(),
+            env: self.env.enter_call(function, &[])?,
+        })
+    }
+}
+
+/// only == and != work on booleans as with 'evaluate_with'
+/// the bitwise operators only work on whole numbers that fit in an i64
+fn calculate_operation<N: Number>(
+    operator: Operators,
+    left_value: GenericValue<N>,
+    right_value: GenericValue<N>,
+) -> Result<GenericValue<N>, CalculationError> {
+    if let (Operators::Equal | Operators::NotEqual, GenericValue::Boolean(left_bool)) =
+        (operator, &left_value)
+    {
+        let is_equal = *left_bool == right_value.as_boolean()?;
+        return Ok(GenericValue::Boolean(
+            is_equal == (operator == Operators::Equal),
+        ));
+    }
+
+    let left_sum = left_value.into_number()?;
+    let right_sum = right_value.into_number()?;
+    if operator.is_bitwise() {
+        let (left_float, right_float) = (left_sum.to_float(), right_sum.to_float());
+        OperationNode::check_integer(operator.symbol(), left_float)?;
+        OperationNode::check_integer(operator.symbol(), right_float)?;
+        OperationNode::check_shift(operator, left_float as i64, right_float as i64)?;
+    }
+
+    if let Some(result) = operator.compare(&left_sum, &right_sum) {
+        return Ok(GenericValue::Boolean(result));
+    }
+
+    operator
+        .calculate(left_sum, right_sum)
+        .map(GenericValue::Number)
+        .ok_or_else(|| no_result(operator.symbol()))
+}
+
+/// the builtin function calculated with 'N' when it has it, else with f64
+fn call_builtin_function<N: Number>(name: &str, args: &[N]) -> Result<N, CalculationError> {
+    let (arity, function) = match_builtin_function(name)
+        .ok_or_else(|| CalculationError::UnknownFunction(name.to_string()))?;
+    check_arity(name, arity, args)?;
+
+    if let Some(result) = N::call_function(name, args) {
+        return Ok(result);
+    }
+
+    let args = args.iter().map(N::to_float).collect::<Vec<f64>>();
+    N::from_float(function(&args)).ok_or_else(|| no_result(name))
+}
+
+fn no_result(name: &str) -> CalculationError {
+    CalculationError::NoResult(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BigDecimal, BigRational, Complex64};
+
+    #[test]
+    pub fn test_evaluate_as() {
+        let variables = HashMap::from([(String::from("x"), 1.5_f32)]);
+        let operation_node = OperationNode::new("2*x^2 + sqrt(4)").unwrap();
+        assert_eq!(operation_node.evaluate_as(&variables), Ok(6.5_f32));
+
+        let operation_node = OperationNode::new("1/3 + 1/6 + 0.1").unwrap();
+        let value = operation_node.evaluate_as::<BigRational>(&HashMap::new());
+        assert_eq!(value.unwrap().to_string(), "3/5");
+
+        let operation_node = OperationNode::new("(8/27)^(1/3) + max(1/3, 1/4)").unwrap();
+        let value = operation_node.evaluate_as::<BigRational>(&HashMap::new());
+        assert_eq!(value.unwrap().to_string(), "1");
+
+        let operation_node = OperationNode::new("0.1 + 0.2 == 0.3").unwrap();
+        let value = operation_node.evaluate_as::<BigDecimal>(&HashMap::new());
+        assert_eq!(value, Ok(BigDecimal::from(1)));
+
+        let operation_node = OperationNode::new("sqrt(-4) + (1+2i)*i").unwrap();
+        let value = operation_node.evaluate_as::<Complex64>(&HashMap::new());
+        assert_eq!(value, Ok(Complex64::new(-2.0, 3.0)));
+
+        // i is only the imaginary unit of the number types that have one
+        let operation_node = OperationNode::new("i + 1").unwrap();
+        let variables = HashMap::from([(String::from("i"), 3.0)]);
+        assert_eq!(operation_node.evaluate_as(&variables), Ok(4.0));
+
+        let operation_node = OperationNode::new("if(x > 1, 7 // 2, 0) + 5!").unwrap();
+        let variables = HashMap::from([(String::from("x"), 2.0)]);
+        assert_eq!(operation_node.evaluate_as(&variables), Ok(123.0));
+    }
+
+    #[test]
+    pub fn test_evaluate_as_no_result() {
+        let cases = [
+            ("1/0", CalculationError::NoResult(String::from("/"))),
+            ("pi", CalculationError::NoResult(String::from("pi"))),
+            ("i", CalculationError::ComplexModeRequired),
+            (
+                "y + 1",
+                CalculationError::UnboundVariable(String::from("y")),
+            ),
+        ];
+
+        for (eq, error) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let value = operation_node.evaluate_as::<BigRational>(&HashMap::new());
+            assert_eq!(value, Err(error), "{}", eq);
+        }
+
+        // the booleans are not numbers as with 'evaluate_with'
+        let cases = [
+            (
+                "(1 < 2) + 1",
+                CalculationError::ExpectedNumber(Value::Boolean(true)),
+            ),
+            (
+                "if(1, 2, 3)",
+                CalculationError::ExpectedBoolean(Value::Number(1.0)),
+            ),
+            (
+                "1.5 & 1",
+                CalculationError::NonIntegerOperand {
+                    operator: String::from("&"),
+                    value: 1.5,
+                },
+            ),
+            (
+                "1 << 63",
+                CalculationError::BitwiseOverflow(String::from("<<")),
+            ),
+        ];
+
+        for (eq, error) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let value = operation_node.evaluate_as::<BigRational>(&HashMap::new());
+            assert_eq!(value, Err(error), "{}", eq);
+        }
+
+        // a function without an exact fraction is calculated with f64
+        let operation_node = OperationNode::new("sqrt(1/4)").unwrap();
+        let value = operation_node.evaluate_as::<BigRational>(&HashMap::new());
+        assert_eq!(value.unwrap().to_string(), "1/2");
+    }
+
+    #[test]
+    pub fn test_evaluate_as_logical() {
+        // the right of && is not calculated when the left is false
+        let variables = HashMap::from([(String::from("x"), BigRational::from_integer(0.into()))]);
+        let operation_node = OperationNode::new("x != 0 && 1/x > 2").unwrap();
+        assert_eq!(
+            operation_node.evaluate_as(&variables).unwrap().to_string(),
+            "0"
+        );
+
+        let operation_node = OperationNode::new("x == 0 || 1/x > 2").unwrap();
+        assert_eq!(
+            operation_node.evaluate_as(&variables).unwrap().to_string(),
+            "1"
+        );
+
+        let operation_node = OperationNode::new("not (x > 1) == true").unwrap();
+        assert_eq!(
+            operation_node.evaluate_as(&variables).unwrap().to_string(),
+            "1"
+        );
+    }
+
+    #[test]
+    pub fn test_evaluate_as_with() {
+        let mut env = Environment::new();
+        let body = OperationNode::new("x^2 + y").unwrap();
+        env.define_function("f", vec![String::from("x")], body);
+        let body = OperationNode::new("if(n <= 1, 1, n*fact(n - 1))").unwrap();
+        env.define_function("fact", vec![String::from("n")], body);
+
+        let variables = HashMap::from([(String::from("y"), 0.5_f32)]);
+        let operation_node = OperationNode::new("f(3) + fact(5)").unwrap();
+        assert_eq!(
+            operation_node.evaluate_as_with(&variables, &env),
+            Ok(129.5_f32)
+        );
+
+        // the parameter y of g is not seen inside f
+        let body = OperationNode::new("f(1)").unwrap();
+        env.define_function("g", vec![String::from("y")], body);
+        let operation_node = OperationNode::new("g(10)").unwrap();
+        assert_eq!(
+            operation_node.evaluate_as_with(&variables, &env),
+            Ok(1.5_f32)
+        );
+
+        let operation_node = OperationNode::new("f(1, 2)").unwrap();
+        assert!(matches!(
+            operation_node.evaluate_as_with(&variables, &env),
+            Err(CalculationError::ArityMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+use bigdecimal::{num_bigint::BigInt, One, Signed, ToPrimitive, Zero};
+use eq_split::{
+    arithmetic::{integer_factorial, Number},
+    operators::{Operators, PostfixOperators, UnaryOperators},
+};
+use num_rational::BigRational;
+
+use crate::{rational::calculate_rational, CalculationError, Value};
+
+/// the results of ^ and << with more bits than this are calculated with f64
+/// eg. 2^82589933 - 1, the largest known prime, still fits
+const MAX_BITS: u64 = 1 << 27;
+
+/// parse a number literal straight into a whole number eg. 42, 1e30, 0xFFFFFFFFFFFFFFFFFF
+/// a literal with a fraction is a fraction eg. 1.5 = 3/2
+pub fn parse_integer_literal(literal: &str) -> Option<Value> {
+    BigRational::parse_literal(literal).map(whole_or_fraction)
+}
+
+/// the whole number when there is no fraction eg. 6/3 = 2 but 7/2 stays a fraction
+pub fn whole_or_fraction(value: BigRational) -> Value {
+    if value.is_integer() {
+        Value::from(value.to_integer())
+    } else {
+        Value::from(value)
+    }
+}
+
+/// calculate the result of the operation with whole numbers
+/// a division without a whole result gives a fraction eg. 7/2, 2^-1
+/// ^ and the bitwise operators work on whole numbers of any size eg. 2^100, 1 << 100
+/// the other operators are calculated as with fractions
+pub fn calculate_integer(
+    operator: Operators,
+    left: &BigInt,
+    right: &BigInt,
+) -> Result<Value, CalculationError> {
+    let result = match operator {
+        Operators::Exp => return power(left, right),
+        Operators::BitAnd => left & right,
+        Operators::BitOr => left | right,
+        Operators::BitXor => left ^ right,
+        Operators::ShiftLeft => return shift_left(left, right),
+        Operators::ShiftRight => return shift_left(left, &-right),
+        _ => {
+            let left = BigRational::from_integer(left.clone());
+            let right = BigRational::from_integer(right.clone());
+            return match calculate_rational(operator, left, right)? {
+                Value::Rational(result) => Ok(whole_or_fraction(*result)),
+                result => Ok(result),
+            };
+        }
+    };
+
+    Ok(Value::from(result))
+}
+
+/// calculate the result of the sign or ~ with whole numbers eg. -x, ~5 = -6
+pub fn calculate_integer_unary(operator: UnaryOperators, value: BigInt) -> Value {
+    match operator {
+        UnaryOperators::Negate => Value::from(-value),
+        UnaryOperators::BitNot => Value::from(!value),
+        _ => Value::from(value),
+    }
+}
+
+/// calculate the result of the postfix operator with whole numbers eg. 30!, 50%
+/// the factorial of a negative number gives an f64
+pub fn calculate_integer_postfix(operator: PostfixOperators, value: BigInt) -> Value {
+    match operator {
+        PostfixOperators::Percent => whole_or_fraction(BigRational::new(value, BigInt::from(100))),
+        PostfixOperators::Factorial => match integer_factorial(&value) {
+            Some(result) => Value::from(result),
+            None => Value::Number(operator.calculate(to_float(&value)).unwrap_or(f64::NAN)),
+        },
+    }
+}
+
+pub fn to_float(value: &BigInt) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+/// 'base' to the power of 'exponent', a negative exponent gives a fraction eg. 2^-2 = 1/4
+fn power(base: &BigInt, exponent: &BigInt) -> Result<Value, CalculationError> {
+    if base.is_zero() && exponent.is_negative() {
+        return Err(CalculationError::DivisionByZero);
+    }
+
+    let bits = base.bits().max(1);
+    let whole = exponent
+        .abs()
+        .to_u32()
+        .filter(|whole| bits.saturating_mul(*whole as u64) <= MAX_BITS);
+    match whole {
+        Some(whole) if exponent.is_negative() => {
+            let fraction = BigRational::new(BigInt::one(), base.pow(whole));
+            Ok(whole_or_fraction(fraction))
+        }
+        Some(whole) => Ok(Value::from(base.pow(whole))),
+        None => Ok(Value::Number(to_float(base).powf(to_float(exponent)))),
+    }
+}
+
+/// 'value' shifted to the left, a negative 'shift' shifts to the right
+/// the sign is kept when shifting to the right eg. -8 >> 1 = -4
+fn shift_left(value: &BigInt, shift: &BigInt) -> Result<Value, CalculationError> {
+    let amount = shift.abs().to_u64().unwrap_or(u64::MAX);
+    if shift.is_negative() {
+        let amount = amount.min(value.bits());
+        return Ok(Value::from(value >> amount));
+    }
+
+    if value.bits().saturating_add(amount) > MAX_BITS {
+        let result = to_float(value) * 2.0_f64.powf(amount as f64);
+        return Ok(Value::Number(result));
+    }
+    Ok(Value::from(value << amount))
+}
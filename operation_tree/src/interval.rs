@@ -0,0 +1,663 @@
+use std::{
+    cmp::Ordering,
+    f64::consts::{FRAC_PI_2, PI, TAU},
+    fmt,
+};
+
+use eq_split::{
+    arithmetic::Number,
+    numbers::parse_number_literal,
+    operators::{Operators, PostfixOperators, UnaryOperators},
+    EquationString,
+};
+use num_rational::BigRational;
+
+use crate::{CalculationError, Value};
+
+/// the relative error allowed for the functions of the standard library eg. sin, powf
+/// which are not always rounded to the closest f64
+const FUNCTION_ERROR: f64 = 4.0 * f64::EPSILON;
+/// the relative error of the gamma function used for the factorial
+const FACTORIAL_ERROR: f64 = 1e-13;
+/// the factorial is the smallest at about 0.4616 where it is about 0.8856
+const FACTORIAL_MIN_AT: f64 = 0.4616;
+const FACTORIAL_MIN: f64 = 0.8856;
+/// a critical point of sin, cos or tan is taken to be in the interval when it is this close
+/// as the multiples of pi are not exact, a larger interval is still a bound
+const PERIOD_MARGIN: f64 = 1e-9;
+
+/// the range of real numbers that holds the exact value eg. [1.2, 1.3]
+/// the bounds are rounded outward so that the exact result is never outside of them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub lo: f64,
+    pub hi: f64,
+}
+
+impl Interval {
+    /// the interval from the smaller to the larger of 'a' and 'b'
+    pub fn new(a: f64, b: f64) -> Self {
+        Self {
+            lo: a.min(b),
+            hi: a.max(b),
+        }
+    }
+
+    /// the interval holding only 'value'
+    pub fn point(value: f64) -> Self {
+        Self {
+            lo: value,
+            hi: value,
+        }
+    }
+
+    /// the interval of all the numbers
+    pub fn entire() -> Self {
+        Self {
+            lo: f64::NEG_INFINITY,
+            hi: f64::INFINITY,
+        }
+    }
+
+    pub fn is_point(&self) -> bool {
+        self.lo == self.hi
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    /// the smallest interval holding both intervals
+    pub fn hull(&self, other: &Self) -> Self {
+        Self {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
+        }
+    }
+
+    /// the largest distance from 0 eg. 3 for [-3, 2]
+    fn magnitude(&self) -> f64 {
+        self.lo.abs().max(self.hi.abs())
+    }
+
+    /// the smallest distance from 0 eg. 0 for [-3, 2]
+    fn mignitude(&self) -> f64 {
+        if self.contains(0.0) {
+            0.0
+        } else {
+            self.lo.abs().min(self.hi.abs())
+        }
+    }
+
+    /// the interval of the number literal, which is the f64 when it is exact
+    /// eg. 0.5 = [0.5, 0.5] and 0.1 = [0.09999999999999999, 0.1]
+    pub fn from_literal(literal: &str) -> Self {
+        let chars = literal.chars().collect::<EquationString>();
+        let value = parse_number_literal(&chars).unwrap_or(f64::NAN);
+        let exact = BigRational::parse_literal(literal);
+        let float = BigRational::from_float(value);
+
+        match (exact, float) {
+            (Some(exact), Some(float)) => match exact.cmp(&float) {
+                Ordering::Less => Self::new(value.next_down(), value),
+                Ordering::Equal => Self::point(value),
+                Ordering::Greater => Self::new(value, value.next_up()),
+            },
+            // a literal without a fraction eg. 1e-99999999999 is somewhere around its f64
+            _ => Self::new(value.next_down(), value.next_up()),
+        }
+    }
+
+    /// the interval around the f64 of a constant eg. pi, which is never exact
+    pub fn from_constant(value: f64) -> Self {
+        Self::new(value.next_down(), value.next_up())
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{:?}, {:?}]", self.lo, self.hi)
+    }
+}
+
+/// the bounds of 'result' when the exact result is larger by 'error'
+/// an 'error' of NaN means that the size of the error is not known
+/// an infinite result of finite values is an overflow so the exact result is larger than f64::MAX
+fn rounded(result: f64, error: f64, is_finite: bool) -> (f64, f64) {
+    if is_finite && result == f64::INFINITY {
+        return (f64::MAX, f64::INFINITY);
+    }
+    if is_finite && result == f64::NEG_INFINITY {
+        return (f64::NEG_INFINITY, -f64::MAX);
+    }
+    if !is_finite || !result.is_finite() {
+        return (result, result);
+    }
+
+    if error.is_nan() {
+        (result.next_down(), result.next_up())
+    } else if error > 0.0 {
+        (result, result.next_up())
+    } else if error < 0.0 {
+        (result.next_down(), result)
+    } else {
+        (result, result)
+    }
+}
+
+/// the bounds of a result of the standard library that is not always rounded to the closest f64
+fn widened(result: f64, relative_error: f64) -> (f64, f64) {
+    if result == f64::INFINITY {
+        return (f64::MAX, f64::INFINITY);
+    }
+    if result == f64::NEG_INFINITY {
+        return (f64::NEG_INFINITY, -f64::MAX);
+    }
+
+    let margin = result.abs() * relative_error;
+    ((result - margin).next_down(), (result + margin).next_up())
+}
+
+/// the error of the sum is exact with the two-sum algorithm
+fn add(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_part = sum - a;
+    let error = (a - (sum - b_part)) + (b - b_part);
+    rounded(sum, error, a.is_finite() && b.is_finite())
+}
+
+/// the error of the product is exact with a fused multiply-add unless the product is too small
+/// 0 * inf is taken as 0, the limit of the bounds
+fn mul(a: f64, b: f64) -> (f64, f64) {
+    if a == 0.0 || b == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let product = a * b;
+    let error = if product.abs() < f64::MIN_POSITIVE {
+        f64::NAN
+    } else {
+        a.mul_add(b, -product)
+    };
+    rounded(product, error, a.is_finite() && b.is_finite())
+}
+
+/// the remainder a - q*b is exact with a fused multiply-add unless the quotient is too small
+fn div(a: f64, b: f64) -> (f64, f64) {
+    let quotient = a / b;
+    let error = if quotient.abs() < f64::MIN_POSITIVE && a != 0.0 {
+        f64::NAN
+    } else {
+        (-quotient).mul_add(b, a) * b.signum()
+    };
+    rounded(quotient, error, a.is_finite() && b.is_finite())
+}
+
+/// the remainder x - s*s is exact with a fused multiply-add
+fn sqrt(x: f64) -> (f64, f64) {
+    let root = x.sqrt();
+    rounded(root, (-root).mul_add(root, x), x.is_finite())
+}
+
+/// the bounds of the operation on all the corners of the intervals
+fn corners(a: Interval, b: Interval, operation: fn(f64, f64) -> (f64, f64)) -> Interval {
+    let bounds = [
+        operation(a.lo, b.lo),
+        operation(a.lo, b.hi),
+        operation(a.hi, b.lo),
+        operation(a.hi, b.hi),
+    ];
+    Interval {
+        lo: bounds
+            .iter()
+            .map(|bound| bound.0)
+            .fold(f64::INFINITY, f64::min),
+        hi: bounds
+            .iter()
+            .map(|bound| bound.1)
+            .fold(f64::NEG_INFINITY, f64::max),
+    }
+}
+
+fn add_interval(a: Interval, b: Interval) -> Interval {
+    Interval {
+        lo: add(a.lo, b.lo).0,
+        hi: add(a.hi, b.hi).1,
+    }
+}
+
+fn negate(a: Interval) -> Interval {
+    Interval {
+        lo: -a.hi,
+        hi: -a.lo,
+    }
+}
+
+fn mul_interval(a: Interval, b: Interval) -> Interval {
+    corners(a, b, mul)
+}
+
+/// a divisor holding 0 gives all the numbers
+fn div_interval(a: Interval, b: Interval) -> Result<Interval, CalculationError> {
+    if b.lo == 0.0 && b.hi == 0.0 {
+        return Err(CalculationError::DivisionByZero);
+    }
+    if b.contains(0.0) {
+        return Ok(Interval::entire());
+    }
+
+    Ok(corners(a, b, div))
+}
+
+/// 'value' to the power of 'n' for a value that is not negative
+/// 'bound' picks the lower or the upper bound of each product
+fn pow_bound(value: f64, n: u64, bound: fn((f64, f64)) -> f64) -> f64 {
+    let (mut base, mut n, mut result) = (value, n, 1.0);
+    while n > 0 {
+        if n % 2 == 1 {
+            result = bound(mul(result, base));
+        }
+        base = bound(mul(base, base));
+        n /= 2;
+    }
+    result
+}
+
+/// the interval to a whole power, an even power of an interval holding 0 starts at 0
+fn powi_interval(a: Interval, n: i64) -> Result<Interval, CalculationError> {
+    if n < 0 {
+        return div_interval(Interval::point(1.0), powi_interval(a, -n)?);
+    }
+
+    let n = n as u64;
+    let down = |value: f64| pow_bound(value, n, |bound| bound.0);
+    let up = |value: f64| pow_bound(value, n, |bound| bound.1);
+    let result = if n == 0 {
+        Interval::point(1.0)
+    } else if a.lo >= 0.0 {
+        Interval::new(down(a.lo), up(a.hi))
+    } else if n % 2 == 1 {
+        let hi = if a.hi >= 0.0 { up(a.hi) } else { -down(-a.hi) };
+        Interval::new(-up(-a.lo), hi)
+    } else if a.hi <= 0.0 {
+        Interval::new(down(-a.hi), up(-a.lo))
+    } else {
+        Interval::new(0.0, up(-a.lo).max(up(a.hi)))
+    };
+    Ok(result)
+}
+
+/// a whole power is calculated by multiplying so that any base works eg. [-2, 1]^3
+/// the other powers only work on the part of the base that is not negative
+fn pow_interval(a: Interval, b: Interval) -> Result<Interval, CalculationError> {
+    if b.is_point() && b.lo.fract() == 0.0 && b.lo.abs() <= i32::MAX as f64 {
+        return powi_interval(a, b.lo as i64);
+    }
+    if a.hi < 0.0 {
+        return Err(no_result("^"));
+    }
+
+    let base = Interval::new(a.lo.max(0.0), a.hi);
+    Ok(corners(base, b, |x, y| widened(x.powf(y), FUNCTION_ERROR)))
+}
+
+/// the operation of the integer division, the modulo or the remainder
+/// 'round' picks the whole quotient eg. floor for the modulo
+fn whole_quotient(
+    a: Interval,
+    b: Interval,
+    round: fn(f64) -> f64,
+) -> Result<Interval, CalculationError> {
+    let quotient = div_interval(a, b)?;
+    Ok(Interval::new(round(quotient.lo), round(quotient.hi)))
+}
+
+/// the modulo and the remainder are a - k*b when all of 'a' has the same whole quotient k
+/// else they are only bound by the size of 'b'
+fn remainder_interval(
+    operator: Operators,
+    a: Interval,
+    b: Interval,
+) -> Result<Interval, CalculationError> {
+    let round = match operator {
+        Operators::Modulo => f64::floor,
+        _ => f64::trunc,
+    };
+    let quotient = whole_quotient(a, b, round)?;
+    if b.is_point() && quotient.is_point() {
+        return Ok(add_interval(a, negate(mul_interval(quotient, b))));
+    }
+
+    let size = b.magnitude();
+    let result = match operator {
+        Operators::Modulo if b.lo > 0.0 => Interval::new(0.0, b.hi),
+        Operators::Modulo if b.hi < 0.0 => Interval::new(b.lo, 0.0),
+        Operators::Modulo => b,
+        _ if a.lo >= 0.0 => Interval::new(0.0, size),
+        _ if a.hi <= 0.0 => Interval::new(-size, 0.0),
+        _ => Interval::new(-size, size),
+    };
+    Ok(result)
+}
+
+/// the comparison when it is true or false for all the numbers of the intervals
+fn compare_interval(
+    operator: Operators,
+    a: Interval,
+    b: Interval,
+) -> Result<bool, CalculationError> {
+    let (is_true, is_false) = match operator {
+        Operators::Less => (a.hi < b.lo, a.lo >= b.hi),
+        Operators::LessEqual => (a.hi <= b.lo, a.lo > b.hi),
+        Operators::Greater => (a.lo > b.hi, a.hi <= b.lo),
+        Operators::GreaterEqual => (a.lo >= b.hi, a.hi < b.lo),
+        Operators::Equal | Operators::NotEqual => {
+            let is_equal = a.is_point() && a == b;
+            let is_apart = a.hi < b.lo || b.hi < a.lo;
+            match operator {
+                Operators::Equal => (is_equal, is_apart),
+                _ => (is_apart, is_equal),
+            }
+        }
+        _ => (false, false),
+    };
+
+    match (is_true, is_false) {
+        (true, _) => Ok(true),
+        (_, true) => Ok(false),
+        _ => Err(CalculationError::UncertainComparison(
+            operator.symbol().to_string(),
+        )),
+    }
+}
+
+/// calculate the result of the operation with intervals
+/// a comparison that is true for some of the numbers and false for others has no result
+/// the bitwise operators only work on intervals holding a single whole number
+pub fn calculate_interval(
+    operator: Operators,
+    left: Interval,
+    right: Interval,
+) -> Result<Value, CalculationError> {
+    if operator.is_comparison() {
+        return compare_interval(operator, left, right).map(Value::Boolean);
+    }
+
+    let result = match operator {
+        Operators::Plus => add_interval(left, right),
+        Operators::Minus => add_interval(left, negate(right)),
+        Operators::PlusMinus => {
+            let size = right.magnitude();
+            Interval {
+                lo: add(left.lo, -size).0,
+                hi: add(left.hi, size).1,
+            }
+        }
+        Operators::Mult => mul_interval(left, right),
+        Operators::Div => div_interval(left, right)?,
+        Operators::IntDiv => whole_quotient(left, right, f64::floor)?,
+        Operators::Modulo | Operators::Rem => remainder_interval(operator, left, right)?,
+        Operators::Exp => pow_interval(left, right)?,
+        Operators::None => left,
+        _ => {
+            let left = as_point(left)?;
+            let right = as_point(right)?;
+            let result = operator.calculate(left, right);
+            Interval::point(result.ok_or_else(|| no_result(operator.symbol()))?)
+        }
+    };
+    Ok(Value::Interval(result))
+}
+
+/// calculate the result of the sign with intervals eg. -[1, 2] = [-2, -1]
+pub fn calculate_interval_unary(
+    operator: UnaryOperators,
+    value: Interval,
+) -> Result<Interval, CalculationError> {
+    match operator {
+        UnaryOperators::Negate => Ok(negate(value)),
+        UnaryOperators::Plus => Ok(value),
+        _ => Ok(Interval::point(operator.calculate(as_point(value)?))),
+    }
+}
+
+/// calculate the result of the postfix operator with intervals eg. [1, 2]% = [0.01, 0.02]
+/// the factorial is only known to be larger than 0.8856 between 0 and 1
+/// and it is all the numbers for an interval with negative numbers
+pub fn calculate_interval_postfix(
+    operator: PostfixOperators,
+    value: Interval,
+) -> Result<Interval, CalculationError> {
+    match operator {
+        PostfixOperators::Percent => div_interval(value, Interval::point(100.0)),
+        PostfixOperators::Factorial => {
+            let factorial = |value: f64| factorial_bounds(operator, value);
+            let result = if value.lo >= FACTORIAL_MIN_AT {
+                Interval::new(factorial(value.lo).0, factorial(value.hi).1)
+            } else if value.lo >= 0.0 {
+                let hi = factorial(value.lo).1.max(factorial(value.hi).1);
+                Interval::new(FACTORIAL_MIN, hi)
+            } else if value.is_point() {
+                let (lo, hi) = factorial(value.lo);
+                Interval::new(lo, hi)
+            } else {
+                Interval::entire()
+            };
+            Ok(result)
+        }
+    }
+}
+
+/// the factorial of a whole number is the product with each step rounded outward
+/// the other numbers use the gamma function
+fn factorial_bounds(operator: PostfixOperators, value: f64) -> (f64, f64) {
+    if value >= 0.0 && value.fract() == 0.0 && value <= 171.0 {
+        return (2..=value as u32).fold((1.0, 1.0), |(lo, hi), i| {
+            (mul(lo, i as f64).0, mul(hi, i as f64).1)
+        });
+    }
+
+    widened(
+        operator.calculate(value).unwrap_or(f64::NAN),
+        FACTORIAL_ERROR,
+    )
+}
+
+/// the builtin function with the intervals as arguments
+/// the functions that are not known to be monotone or periodic only work on points
+pub fn call_interval_function(
+    name: &str,
+    args: &[Interval],
+    function: fn(&[f64]) -> f64,
+) -> Result<Interval, CalculationError> {
+    let increasing = |x: Interval, f: fn(f64) -> f64| {
+        Interval::new(
+            widened(f(x.lo), FUNCTION_ERROR).0,
+            widened(f(x.hi), FUNCTION_ERROR).1,
+        )
+    };
+
+    let result = match (name, args) {
+        ("sqrt", [x]) => {
+            let x = clamp(name, *x, 0.0, f64::INFINITY)?;
+            Interval::new(sqrt(x.lo).0, sqrt(x.hi).1)
+        }
+        ("ln", [x]) => increasing(clamp(name, *x, 0.0, f64::INFINITY)?, f64::ln),
+        ("log", [x]) => increasing(clamp(name, *x, 0.0, f64::INFINITY)?, f64::log10),
+        ("log2", [x]) => increasing(clamp(name, *x, 0.0, f64::INFINITY)?, f64::log2),
+        ("asin", [x]) => increasing(clamp(name, *x, -1.0, 1.0)?, f64::asin),
+        ("acos", [x]) => {
+            let x = clamp(name, *x, -1.0, 1.0)?;
+            Interval::new(
+                widened(x.hi.acos(), FUNCTION_ERROR).0,
+                widened(x.lo.acos(), FUNCTION_ERROR).1,
+            )
+        }
+        ("cbrt", [x]) => increasing(*x, f64::cbrt),
+        ("exp", [x]) => increasing(*x, f64::exp),
+        ("atan", [x]) => increasing(*x, f64::atan),
+        ("sinh", [x]) => increasing(*x, f64::sinh),
+        ("tanh", [x]) => increasing(*x, f64::tanh),
+        ("cosh", [x]) => increasing(Interval::new(x.mignitude(), x.magnitude()), f64::cosh),
+        ("sin", [x]) => periodic(*x, f64::sin, FRAC_PI_2),
+        ("cos", [x]) => periodic(*x, f64::cos, 0.0),
+        ("tan", [x]) => {
+            if has_multiple(*x, FRAC_PI_2, PI) {
+                Interval::entire()
+            } else {
+                increasing(*x, f64::tan)
+            }
+        }
+        ("abs", [x]) => Interval::new(x.mignitude(), x.magnitude()),
+        ("floor", [x]) => Interval::new(x.lo.floor(), x.hi.floor()),
+        ("ceil", [x]) => Interval::new(x.lo.ceil(), x.hi.ceil()),
+        ("round", [x]) => Interval::new(x.lo.round(), x.hi.round()),
+        ("re" | "conj", [x]) => *x,
+        ("im", [_]) => Interval::point(0.0),
+        ("arg", [x]) if x.lo >= 0.0 => Interval::point(0.0),
+        ("arg", [x]) if x.hi < 0.0 => Interval::from_constant(PI),
+        ("arg", [_]) => Interval::new(0.0, PI.next_up()),
+        ("min", [x, y]) => Interval::new(x.lo.min(y.lo), x.hi.min(y.hi)),
+        ("max", [x, y]) => Interval::new(x.lo.max(y.lo), x.hi.max(y.hi)),
+        _ => {
+            let args = args
+                .iter()
+                .map(|arg| as_point(*arg).map_err(|_| no_result(name)))
+                .collect::<Result<Vec<f64>, CalculationError>>()?;
+            let (lo, hi) = widened(function(&args), FUNCTION_ERROR);
+            Interval::new(lo, hi)
+        }
+    };
+    Ok(result)
+}
+
+/// the part of 'x' in the domain of the function 'name' eg. [0, 4] for sqrt([-1, 4])
+fn clamp(name: &str, x: Interval, min: f64, max: f64) -> Result<Interval, CalculationError> {
+    if x.hi < min || x.lo > max {
+        return Err(no_result(name));
+    }
+
+    Ok(Interval::new(x.lo.max(min), x.hi.min(max)))
+}
+
+/// check if 'x' may hold 'start' plus a multiple of 'period'
+fn has_multiple(x: Interval, start: f64, period: f64) -> bool {
+    let first = ((x.lo - start) / period - PERIOD_MARGIN).ceil();
+    let last = ((x.hi - start) / period + PERIOD_MARGIN).floor();
+    first <= last
+}
+
+/// sin or cos, which is 1 at 'max_at' and -1 half of the period later
+fn periodic(x: Interval, function: fn(f64) -> f64, max_at: f64) -> Interval {
+    if x.hi - x.lo >= TAU || !x.lo.is_finite() || !x.hi.is_finite() {
+        return Interval::new(-1.0, 1.0);
+    }
+
+    let (lo_min, lo_max) = widened(function(x.lo), FUNCTION_ERROR);
+    let (hi_min, hi_max) = widened(function(x.hi), FUNCTION_ERROR);
+    let mut result = Interval::new(lo_min.min(hi_min), lo_max.max(hi_max));
+    if has_multiple(x, max_at, TAU) {
+        result.hi = 1.0;
+    }
+    if has_multiple(x, max_at + PI, TAU) {
+        result.lo = -1.0;
+    }
+    Interval::new(result.lo.max(-1.0), result.hi.min(1.0))
+}
+
+/// the number of an interval holding a single number
+fn as_point(value: Interval) -> Result<f64, CalculationError> {
+    if value.is_point() {
+        Ok(value.lo)
+    } else {
+        Err(CalculationError::ExpectedNumber(Value::Interval(value)))
+    }
+}
+
+fn no_result(name: &str) -> CalculationError {
+    CalculationError::NoResult(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_outward_rounding() {
+        let third = div_interval(Interval::point(1.0), Interval::point(3.0)).unwrap();
+        assert_eq!(third.hi, third.lo.next_up());
+        assert!(mul_interval(third, Interval::point(3.0)).contains(1.0));
+
+        let tenth = Interval::from_literal("0.1");
+        assert_eq!(tenth, Interval::new(0.1_f64.next_down(), 0.1));
+        let sum = add_interval(add_interval(tenth, tenth), tenth);
+        assert!(sum.lo < 0.3 && sum.hi > 0.3);
+
+        assert_eq!(Interval::from_literal("0.5"), Interval::point(0.5));
+        assert_eq!(Interval::from_literal("0x10"), Interval::point(16.0));
+        let tiny = Interval::from_literal("1e-99999999999");
+        assert!(tiny.lo < 0.0 && tiny.hi > 0.0);
+
+        let root = call_interval_function("sqrt", &[Interval::point(2.0)], |_| 0.0).unwrap();
+        assert!(root.lo * root.lo <= 2.0 && root.hi * root.hi >= 2.0);
+        assert_eq!(root.hi, root.lo.next_up());
+
+        let large = mul_interval(Interval::point(f64::MAX), Interval::point(2.0));
+        assert_eq!(large, Interval::new(f64::MAX, f64::INFINITY));
+    }
+
+    #[test]
+    pub fn test_interval_operators() {
+        let a = Interval::new(-2.0, 3.0);
+        let b = Interval::new(1.0, 2.0);
+        let cases = [
+            (Operators::Mult, Interval::new(-4.0, 6.0)),
+            (Operators::Div, Interval::new(-2.0, 3.0)),
+            (Operators::IntDiv, Interval::new(-2.0, 3.0)),
+            (Operators::Modulo, Interval::new(0.0, 2.0)),
+            (Operators::Rem, Interval::new(-2.0, 2.0)),
+            (Operators::PlusMinus, Interval::new(-4.0, 5.0)),
+        ];
+
+        for (operator, result) in cases {
+            let value = calculate_interval(operator, a, b);
+            assert_eq!(value, Ok(Value::Interval(result)), "{:?}", operator);
+        }
+
+        assert_eq!(powi_interval(a, 2), Ok(Interval::new(0.0, 9.0)));
+        assert_eq!(powi_interval(a, 3), Ok(Interval::new(-8.0, 27.0)));
+        assert_eq!(
+            div_interval(b, a),
+            Ok(Interval::entire()),
+            "a divisor holding 0"
+        );
+
+        let value = calculate_interval(Operators::Less, b, Interval::new(2.5, 3.0));
+        assert_eq!(value, Ok(Value::Boolean(true)));
+        let value = calculate_interval(Operators::Less, a, b);
+        assert_eq!(
+            value,
+            Err(CalculationError::UncertainComparison(String::from("<")))
+        );
+    }
+
+    #[test]
+    pub fn test_interval_functions() {
+        let x = Interval::new(0.0, 4.0);
+        let value = call_interval_function("sin", &[x], |_| 0.0).unwrap();
+        assert_eq!(value.hi, 1.0);
+        assert!(value.lo <= 4.0_f64.sin() && value.lo > -1.0);
+
+        let value = call_interval_function("cos", &[x], |_| 0.0).unwrap();
+        assert_eq!(value.lo, -1.0);
+
+        let value = call_interval_function("abs", &[Interval::new(-3.0, 2.0)], |_| 0.0);
+        assert_eq!(value, Ok(Interval::new(0.0, 3.0)));
+
+        let value = call_interval_function("tan", &[Interval::new(1.0, 2.0)], |_| 0.0);
+        assert_eq!(value, Ok(Interval::entire()));
+
+        let value = call_interval_function("ln", &[Interval::new(-2.0, -1.0)], |_| 0.0);
+        assert_eq!(value, Err(no_result("ln")));
+    }
+}
@@ -0,0 +1,378 @@
+use eq_split::{
+    numbers::literal_radix,
+    operators::{Operators, PostfixOperators, UnaryOperators},
+    units::find_unit,
+};
+
+use crate::{display::value_precedence, MathConstant, NodeValue, OperationNode, Statement};
+
+impl OperationNode {
+    /// the tree written as LaTeX eg. \frac{2(\pi+3)^{e}}{7} for 2(π+3)^e/7
+    /// a division is a fraction, a power is a superscript and a number before a name
+    /// or parentheses is multiplied without a sign eg. 2x, the other products use \cdot
+    pub fn to_latex(&self) -> String {
+        node_latex(self)
+    }
+}
+
+impl Statement {
+    /// the statement written as LaTeX eg. f(x) = x^{2}, \frac{d}{dx}(x^{2})
+    pub fn to_latex(&self) -> String {
+        match self {
+            Self::Equation(node) => node.to_latex(),
+            Self::Assignment(name, node) => format!("{} = {}", name_latex(name), node.to_latex()),
+            Self::Definition(name, params, node) => {
+                let params = params
+                    .iter()
+                    .map(|param| name_latex(param))
+                    .collect::<Vec<String>>();
+                format!(
+                    "{}{} = {}",
+                    function_latex(name),
+                    parentheses(params.join(", ")),
+                    node.to_latex()
+                )
+            }
+            Self::Derivative(var, node) => {
+                let node = operand(node.to_latex(), node_precedence(node), u8::MAX);
+                format!("\\frac{{d}}{{d{}}}{}", name_latex(var), node)
+            }
+        }
+    }
+}
+
+fn node_latex(node: &OperationNode) -> String {
+    let (left, right) = (node.left_node.as_ref(), node.right_node.as_ref());
+    match node.operation {
+        Operators::None => value_latex(left),
+        Operators::Div => format!("\\frac{{{}}}{{{}}}", value_latex(left), value_latex(right)),
+        Operators::IntDiv => format!(
+            "\\left\\lfloor \\frac{{{}}}{{{}}} \\right\\rfloor",
+            value_latex(left),
+            value_latex(right)
+        ),
+        // the exponent is raised so it needs no parentheses
+        Operators::Exp => {
+            let base = operand(
+                value_latex(left),
+                precedence(left),
+                Operators::Exp.precedence() + 1,
+            );
+            format!("{}^{{{}}}", base, value_latex(right))
+        }
+        Operators::Mult => {
+            let precedence = Operators::Mult.precedence();
+            let right_latex = right_operand(right, precedence + 1);
+            if is_implicit_product(left, right) {
+                return format!("{}{}", value_latex(left), right_latex);
+            }
+            format!(
+                "{} \\cdot {}",
+                operand(value_latex(left), self::precedence(left), precedence),
+                right_latex
+            )
+        }
+        operation => {
+            let precedence = operation.precedence();
+            format!(
+                "{}{}{}",
+                operand(value_latex(left), self::precedence(left), precedence),
+                operator_latex(operation),
+                right_operand(right, precedence + 1)
+            )
+        }
+    }
+}
+
+fn value_latex(value: &NodeValue) -> String {
+    match value {
+        NodeValue::UnitValue(value) => float_latex(*value),
+        NodeValue::NumberLiteral(literal) => literal_latex(literal),
+        NodeValue::Constant(constant) => match constant {
+            MathConstant::Pi => String::from("\\pi"),
+            constant => constant.name().to_string(),
+        },
+        NodeValue::BooleanValue(value) => format!("\\mathrm{{{}}}", value),
+        NodeValue::Variable(name) => variable_latex(name),
+        NodeValue::Call { name, args } => call_latex(name, args),
+        NodeValue::Unary { operator, node } => {
+            let node = operand(
+                node_latex(node),
+                node_precedence(node),
+                operator.precedence(),
+            );
+            match operator {
+                UnaryOperators::Negate => format!("-{}", node),
+                UnaryOperators::Plus => format!("+{}", node),
+                UnaryOperators::BitNot => format!("\\sim {}", node),
+                UnaryOperators::Not => format!("\\neg {}", node),
+            }
+        }
+        NodeValue::Postfix { operator, node } => {
+            let node = operand(
+                node_latex(node),
+                node_precedence(node),
+                operator.precedence(),
+            );
+            match operator {
+                PostfixOperators::Factorial => format!("{}!", node),
+                PostfixOperators::Percent => format!("{}\\%", node),
+            }
+        }
+        NodeValue::OperationValue(node) => node_latex(node),
+    }
+}
+
+/// the functions with a sign of their own are written with it eg. \sqrt{x}, \left|x\right|
+/// and the others with their name in front of the arguments eg. \sin(x), \operatorname{area}(r)
+fn call_latex(name: &str, args: &[OperationNode]) -> String {
+    let args = args
+        .iter()
+        .map(|arg| arg.to_latex())
+        .collect::<Vec<String>>();
+    match (name, &args[..]) {
+        ("sqrt", [value]) => format!("\\sqrt{{{}}}", value),
+        ("cbrt", [value]) => format!("\\sqrt[3]{{{}}}", value),
+        ("abs", [value]) => format!("\\left|{}\\right|", value),
+        ("floor", [value]) => format!("\\left\\lfloor {} \\right\\rfloor", value),
+        ("ceil", [value]) => format!("\\left\\lceil {} \\right\\rceil", value),
+        ("exp", [value]) => format!("e^{{{}}}", value),
+        ("interval", [lower, upper]) => format!("\\left[{}, {}\\right]", lower, upper),
+        _ => format!("{}{}", function_latex(name), parentheses(args.join(", "))),
+    }
+}
+
+/// the name of a function eg. \sin, \arctan, \log_{10}, f
+fn function_latex(name: &str) -> String {
+    match name {
+        "sin" | "cos" | "tan" | "sinh" | "cosh" | "tanh" | "ln" | "arg" | "min" | "max" => {
+            format!("\\{}", name)
+        }
+        "asin" | "acos" | "atan" => format!("\\arc{}", &name[1..]),
+        "log" => String::from("\\log_{10}"),
+        "log2" => String::from("\\log_{2}"),
+        _ if name.chars().count() == 1 => name.to_string(),
+        _ => format!("\\operatorname{{{}}}", name.replace('_', "\\_")),
+    }
+}
+
+/// a variable with the name of a unit is written upright as the unit eg. 3\mathrm{m}, \mathrm{kg}
+fn variable_latex(name: &str) -> String {
+    if find_unit(name).is_some() {
+        return format!("\\mathrm{{{}}}", name);
+    }
+    name_latex(name)
+}
+
+/// a name of a single letter is written as it is and a longer one upright eg. x, \mathrm{rate}
+fn name_latex(name: &str) -> String {
+    if name.chars().count() == 1 {
+        return name.to_string();
+    }
+    format!("\\mathrm{{{}}}", name.replace('_', "\\_"))
+}
+
+fn operator_latex(operation: Operators) -> &'static str {
+    match operation {
+        Operators::Plus => "+",
+        Operators::Minus => "-",
+        Operators::PlusMinus => " \\pm ",
+        Operators::Modulo => " \\bmod ",
+        Operators::Rem => " \\operatorname{rem} ",
+        Operators::BitAnd => " \\mathbin{\\&} ",
+        Operators::BitOr => " \\mathbin{|} ",
+        Operators::BitXor => " \\oplus ",
+        Operators::ShiftLeft => " \\ll ",
+        Operators::ShiftRight => " \\gg ",
+        Operators::Less => "<",
+        Operators::LessEqual => " \\le ",
+        Operators::Greater => ">",
+        Operators::GreaterEqual => " \\ge ",
+        Operators::Equal => "=",
+        Operators::NotEqual => " \\neq ",
+        Operators::And => " \\land ",
+        Operators::Or => " \\lor ",
+        Operators::Convert => " \\to ",
+        _ => "",
+    }
+}
+
+/// a number with an exponent is written as a product eg. 6.02 \cdot 10^{-23}
+/// and a number with a radix as it is eg. \mathtt{0xFF}
+fn literal_latex(literal: &str) -> String {
+    if literal_radix(&literal.chars().collect::<Vec<char>>()).is_some() {
+        return format!("\\mathtt{{{}}}", literal);
+    }
+    match scientific(literal) {
+        Some((mantissa, exponent)) => format!("{} \\cdot 10^{{{}}}", mantissa, exponent),
+        None => literal.to_string(),
+    }
+}
+
+fn scientific(literal: &str) -> Option<(&str, &str)> {
+    let (mantissa, exponent) = literal.split_once(['e', 'E'])?;
+    Some((mantissa, exponent.trim_start_matches('+')))
+}
+
+fn float_latex(value: f64) -> String {
+    match value {
+        value if value.is_nan() => String::from("\\mathrm{NaN}"),
+        value if value.is_infinite() && value < 0.0 => String::from("-\\infty"),
+        value if value.is_infinite() => String::from("\\infty"),
+        value => value.to_string(),
+    }
+}
+
+/// the precedence of the value as it is typeset
+/// a fraction needs no parentheses after a sign or in a product eg. -\frac{1}{2} \cdot x
+/// but it does under a power eg. \left(\frac{1}{2}\right)^{2}
+pub(crate) fn precedence(value: &NodeValue) -> u8 {
+    match value {
+        NodeValue::NumberLiteral(literal) if scientific(literal).is_some() => {
+            Operators::Mult.precedence()
+        }
+        NodeValue::OperationValue(node) => node_precedence(node),
+        value => value_precedence(value),
+    }
+}
+
+pub(crate) fn node_precedence(node: &OperationNode) -> u8 {
+    match node.operation {
+        Operators::None => precedence(&node.left_node),
+        Operators::Div => UnaryOperators::Negate.precedence(),
+        Operators::IntDiv => u8::MAX,
+        operation => operation.precedence(),
+    }
+}
+
+/// the right side of an operation, a sign is put in parentheses eg. x-(-y), x \cdot (-y)
+fn right_operand(value: &NodeValue, least_precedence: u8) -> String {
+    if is_sign(value) {
+        return parentheses(value_latex(value));
+    }
+    operand(value_latex(value), precedence(value), least_precedence)
+}
+
+fn operand(latex: String, precedence: u8, least_precedence: u8) -> String {
+    if precedence < least_precedence {
+        parentheses(latex)
+    } else {
+        latex
+    }
+}
+
+/// the parentheses grow with a fraction inside eg. \left(\frac{1}{2}\right)
+fn parentheses(latex: String) -> String {
+    if latex.contains("\\frac") {
+        format!("\\left({}\\right)", latex)
+    } else {
+        format!("({})", latex)
+    }
+}
+
+/// a value that starts with a sign eg. -2, ~x
+pub(crate) fn is_sign(value: &NodeValue) -> bool {
+    match value {
+        NodeValue::UnitValue(value) => value.is_sign_negative() && !value.is_nan(),
+        NodeValue::Unary { operator, .. } => *operator != UnaryOperators::Not,
+        NodeValue::OperationValue(node) if node.operation == Operators::None => {
+            is_sign(&node.left_node)
+        }
+        _ => false,
+    }
+}
+
+/// a number that is written out in digits eg. 2, 0.5 but not 6.02e-23
+fn is_number(value: &NodeValue) -> bool {
+    match value {
+        NodeValue::NumberLiteral(literal) => {
+            scientific(literal).is_none()
+                && literal_radix(&literal.chars().collect::<Vec<char>>()).is_none()
+        }
+        NodeValue::UnitValue(value) => value.is_finite() && *value >= 0.0,
+        NodeValue::OperationValue(node) if node.operation == Operators::None => {
+            is_number(&node.left_node)
+        }
+        _ => false,
+    }
+}
+
+/// the products of a number that are written without a sign eg. 2x, 2\pi, 2(x+1), 2\sin(x), 2x^{2}
+/// but not 2 \cdot 3 or 2 \cdot \frac{1}{3}
+pub(crate) fn is_implicit_product(left: &NodeValue, right: &NodeValue) -> bool {
+    is_number(left) && starts_with_name(right, Operators::Mult.precedence() + 1)
+}
+
+/// the value starts with a name or parentheses when it is written as an operand
+fn starts_with_name(value: &NodeValue, least_precedence: u8) -> bool {
+    if is_sign(value) || precedence(value) < least_precedence {
+        return true;
+    }
+    match value {
+        NodeValue::Variable(_) | NodeValue::Constant(_) | NodeValue::Call { .. } => true,
+        NodeValue::Postfix { operator, node } => node_starts_with_name(node, operator.precedence()),
+        NodeValue::OperationValue(node) => node_starts_with_name(node, least_precedence),
+        _ => false,
+    }
+}
+
+fn node_starts_with_name(node: &OperationNode, least_precedence: u8) -> bool {
+    match node.operation {
+        Operators::None => starts_with_name(&node.left_node, least_precedence),
+        _ if node_precedence(node) < least_precedence => true,
+        Operators::Exp => starts_with_name(&node.left_node, Operators::Exp.precedence() + 1),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_latex() {
+        let cases = [
+            ("2(π+3)^e/7", "\\frac{2(\\pi+3)^{e}}{7}"),
+            ("x*y + 2*3", "x \\cdot y+2 \\cdot 3"),
+            ("2*sin(x)^2", "2\\sin(x)^{2}"),
+            (
+                "(1/2)^2 * -x",
+                "\\left(\\frac{1}{2}\\right)^{2} \\cdot (-x)",
+            ),
+            ("-(1/x) - (a - b)", "-\\frac{1}{x}-(a-b)"),
+            ("2^3^2 + (2^3)^2", "2^{3^{2}}+(2^{3})^{2}"),
+            (
+                "sqrt(x + 1) + cbrt(8) + abs(-x)",
+                "\\sqrt{x+1}+\\sqrt[3]{8}+\\left|-x\\right|",
+            ),
+            (
+                "log(x) * atan(rate)",
+                "\\log_{10}(x) \\cdot \\arctan(\\mathrm{rate})",
+            ),
+            (
+                "6.02e-23 * n_items",
+                "6.02 \\cdot 10^{-23} \\cdot \\mathrm{n\\_items}",
+            ),
+            ("x <= 1 && not y != 2", "x \\le 1 \\land \\neg y \\neq 2"),
+            (
+                "7 // 2 + 5! + 50%",
+                "\\left\\lfloor \\frac{7}{2} \\right\\rfloor+5!+50\\%",
+            ),
+            ("3 m to cm", "3\\mathrm{m} \\to \\mathrm{cm}"),
+            (
+                "60 km/h * 2.5 h",
+                "\\frac{60\\mathrm{km}}{\\mathrm{h}} \\cdot (2.5\\mathrm{h})",
+            ),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(operation_node.to_latex(), result, "{}", eq);
+        }
+
+        let statement = Statement::new("f(x, y) = x/y").unwrap();
+        assert_eq!(statement.to_latex(), "f(x, y) = \\frac{x}{y}");
+        let statement = Statement::new("d/dx (x^2 + 1)").unwrap();
+        assert_eq!(statement.to_latex(), "\\frac{d}{dx}(x^{2}+1)");
+    }
+}
@@ -1,18 +1,101 @@
-use eq_split::{eq_sanitize::EqSanitize, operators::Operators, EquationString, FullSplit};
-use math_characters::{match_math_character, math_character_position};
+use complex::{calculate_complex, calculate_complex_postfix, calculate_complex_unary};
+use decimal::{
+    calculate_decimal, calculate_decimal_postfix, calculate_decimal_unary, decimal_constant,
+    decimal_literal,
+};
+use eq_split::{
+    calls::SplitCall,
+    eq_sanitize::EqSanitize,
+    identifiers::is_identifier,
+    numbers::{literal_radix, number_literal_len, parse_number_literal},
+    operators::{shift_left, Operators, PostfixOperators, UnaryOperators},
+    EquationString, FullSplit,
+};
+use functions::call_builtin_function;
+use integer::{
+    calculate_integer, calculate_integer_postfix, calculate_integer_unary, parse_integer_literal,
+};
+use interval::{calculate_interval, calculate_interval_postfix, calculate_interval_unary};
+use math_characters::{
+    match_boolean, match_math_character, math_character_position, unbound_variable, IMAGINARY_UNIT,
+};
+use rational::{calculate_rational, calculate_rational_postfix, calculate_rational_unary};
+use scope::{evaluate, Scope};
+use units::{calculate_quantity, calculate_quantity_postfix, calculate_quantity_unary, unit_value};
 
+pub use bigdecimal::{BigDecimal, RoundingMode};
+pub use complex::ComplexFormat;
+pub use environment::Environment;
+pub use eq_split::arithmetic::Number;
+pub use eq_split::error::{EquationError, Span};
+pub use eq_split::options::ParseOptions;
+pub use eq_split::units::Unit;
+pub use error::CalculationError;
+pub use functions::Function;
+pub use interval::Interval;
+pub use math_characters::MathConstant;
+pub use mode::{NumberMode, DEFAULT_DECIMAL_PRECISION};
+pub use num_complex::Complex64;
+pub use num_rational::BigRational;
+pub use rational::RationalFormat;
+pub use statement::Statement;
+pub use units::Quantity;
+pub use value::{Value, ValueFormat};
+
+mod complex;
+mod decimal;
+mod derivative;
+mod display;
+mod environment;
+mod error;
+mod functions;
+mod generic;
+mod integer;
+mod interval;
+mod latex;
 mod math_characters;
+mod mathml;
+mod mode;
+mod rational;
+mod scope;
+mod simplify;
+mod statement;
+mod units;
+mod value;
 
 /// with the higher precedence located at the bottom of the tree
 /// we will do breath first search and they will be calculated first
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum NodeValue {
     UnitValue(f64),
+    /// a number as it is written eg. 0.1, 6.02e-23, 0xFF
+    /// so that it is parsed straight into the number type of the 'NumberMode'
+    NumberLiteral(String),
+    Constant(MathConstant),
+    BooleanValue(bool),
+    Variable(String),
+    Call {
+        name: String,
+        args: Vec<OperationNode>,
+    },
+    /// a sign in front of a value eg. -x, +2, -(1+2), not x
+    /// a sign binds looser than ^ eg. -3^2 = -(3^2) unless set otherwise in 'ParseOptions'
+    /// and not binds looser than the comparisons eg. not x > 1 = not (x > 1)
+    Unary {
+        operator: UnaryOperators,
+        node: OperationNode,
+    },
+    /// an operator after a value eg. 5!, (n+1)!, 12.5%
+    /// it binds tighter than the signs and ^ eg. -3! = -(3!), 2^3! = 2^(3!)
+    Postfix {
+        operator: PostfixOperators,
+        node: OperationNode,
+    },
     OperationValue(OperationNode),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct OperationNode {
     pub operation: Operators,
     pub left_node: Box<NodeValue>,
@@ -20,129 +103,490 @@ pub struct OperationNode {
 }
 
 impl OperationNode {
-    pub fn new(eq: &str) -> Result<Self, String> {
+    /// the errors returned point at the positions in 'eq'
+    pub fn new(eq: &str) -> Result<Self, EquationError> {
+        Self::new_with(eq, ParseOptions::default())
+    }
+
+    /// create the tree with the rules set in 'options' eg. for the associativity of ^
+    pub fn new_with(eq: &str, options: ParseOptions) -> Result<Self, EquationError> {
+        let original_eq = eq;
+
         //to remove whitespaces
         let eq = EquationString::remove_whitespaces(eq);
-        let eq_res = eq.handle_special_character_multiplication();
-        if let Err(t) = eq_res {
-            return Err(t);
+        let eq = eq.handle_interval_brackets()?;
+        let eq = eq.handle_special_character_multiplication()?;
+        let eq = eq.handle_direct_multiplication()?;
+
+        Self::create(eq.clone(), 0, options)
+            .map_err(|e| e.to_source(&eq.source_positions(original_eq)))
+    }
+
+    /// 'offset' is the position of 'eq' in the equation passed to 'new'
+    /// and is used to point the errors at the right place
+    fn create(
+        eq: EquationString,
+        offset: usize,
+        options: ParseOptions,
+    ) -> Result<Self, EquationError> {
+        // the spaces kept around a word operator stay on the halves after a split eg. 7 rem 3
+        let leading_spaces = eq.iter().take_while(|c| **c == ' ').count();
+        let trailing_spaces = eq[leading_spaces..]
+            .iter()
+            .rev()
+            .take_while(|c| **c == ' ')
+            .count();
+        let eq = eq[leading_spaces..eq.len() - trailing_spaces].to_vec();
+        let offset = offset + leading_spaces;
+
+        // the parentheses wrapping the entire eq are removed here instead of in 'split'
+        // so that the left half starts where the eq starts and
+        // the right half ends where the eq ends
+        let (eq, removed) = eq.remove_outer_parentheses().map_err(|e| e.shift(offset))?;
+        let offset = offset + removed;
+
+        if eq.is_empty() {
+            return Err(EquationError::EmptyExpression(Span::new(offset, offset)));
+        }
+
+        let (left, right, operator) = eq.split_with(options).map_err(|e| e.shift(offset))?;
+
+        if operator == Operators::None {
+            return Self::create_value(left, offset, options);
         }
 
-        let eq = eq_res.unwrap();
+        let right_offset = offset + eq.len() - right.len();
+        let left_node = Self::create(left, offset, options)?;
+        let right_node = Self::create(right, right_offset, options)?;
 
-        let eq_res = eq.handle_direct_multiplication();
-        if let Err(t) = eq_res {
-            return Err(t);
+        Ok(OperationNode {
+            operation: operator,
+            left_node: Box::new(NodeValue::OperationValue(left_node)),
+            right_node: Box::new(NodeValue::OperationValue(right_node)),
+        })
+    }
+
+    /// create the node of a single value eg. 5, -5, pi, 5e, x, -x, sin(x), -(1+2), 5!, true
+    fn create_value(
+        value: EquationString,
+        offset: usize,
+        options: ParseOptions,
+    ) -> Result<Self, EquationError> {
+        if let Some((operator, len)) = UnaryOperators::match_prefix(&value) {
+            // each sign is a node of its own so that --1 is -(-1)
+            let node = Self::create(value[len..].to_vec(), offset + len, options)?;
+            return Ok(Self::leaf(NodeValue::Unary { operator, node }));
         }
 
-        let eq = eq_res.unwrap();
-        if eq.is_empty() {
-            return Err(String::from("Cannot be empty"));
+        if let Some(operator) = value.last().and_then(|c| PostfixOperators::to_enum(*c)) {
+            let node = Self::create(value[..value.len() - 1].to_vec(), offset, options)?;
+            return Ok(Self::leaf(NodeValue::Postfix { operator, node }));
         }
 
-        return Self::create(eq);
+        if let Some((name, args)) = value.split_call() {
+            let args = args
+                .into_iter()
+                .map(|(arg, arg_offset)| Self::create(arg, offset + arg_offset, options))
+                .collect::<Result<Vec<Self>, EquationError>>()?;
+            return Ok(Self::leaf(NodeValue::Call { name, args }));
+        }
+
+        // π is not a name but it is the same constant as pi
+        if value == ['π'] {
+            return Ok(Self::leaf(NodeValue::Constant(MathConstant::Pi)));
+        }
+
+        if is_identifier(&value) {
+            let name = value.iter().collect::<String>();
+            if let Some(value) = match_boolean(&name) {
+                return Ok(Self::leaf(NodeValue::BooleanValue(value)));
+            }
+
+            return match MathConstant::from_name(&name) {
+                Some(constant) => Ok(Self::leaf(NodeValue::Constant(constant))),
+                None => Ok(Self::leaf(NodeValue::Variable(name))),
+            };
+        }
+
+        if number_literal_len(&value) == value.len() && parse_number_literal(&value).is_some() {
+            let literal = value.iter().collect::<String>();
+            return Ok(Self::leaf(NodeValue::NumberLiteral(literal)));
+        }
+
+        let value = Self::parse_value(value, offset)?;
+        Ok(Self::leaf(NodeValue::UnitValue(value)))
     }
 
-    fn create(eq: EquationString) -> Result<Self, String> {
-        let split_res = eq.split();
-        if let Err(t) = split_res {
-            return Err(t);
+    fn leaf(value: NodeValue) -> Self {
+        OperationNode {
+            operation: Operators::None,
+            left_node: Box::new(value),
+            right_node: Box::new(NodeValue::UnitValue(0.0)),
         }
+    }
 
-        let (left, right, operator) = split_res.unwrap();
+    /// the operation on two nodes, a node that is a single value is kept as the value eg. x in x+1
+    fn binary(operation: Operators, left: Self, right: Self) -> Self {
+        let value_of = |node: Self| match node.operation {
+            Operators::None => *node.left_node,
+            _ => NodeValue::OperationValue(node),
+        };
+        OperationNode {
+            operation,
+            left_node: Box::new(value_of(left)),
+            right_node: Box::new(value_of(right)),
+        }
+    }
 
-        if operator == Operators::None {
-            #[allow(unused_assignments)]
-            let mut left_float = 0.0;
-
-            let math_character_position_res = math_character_position(left.clone());
-            if let Some((index, operator)) = math_character_position_res {
-                let left_len = left.len();
-                let operator_str = operator.to_string();
-
-                if index == 0 && left_len == 1 {
-                    left_float = match_math_character(&operator_str).unwrap();
-                } else if index == 0 && operator_str == "pi" {
-                    left_float = match_math_character(&operator_str).unwrap();
-                } else if index == 0 && left_len > 2 && operator_str == "pi" {
-                    return Err(String::from("Invalid syntax"));
-                } else if index == 0 && left_len > 1 {
-                    return Err(String::from("Invalid syntax"));
-                } else {
-                    let num_str: EquationString = left[..index].to_vec();
-                    let num_parse_res = num_str.to_string().parse::<f64>();
-                    if num_parse_res.is_err() {
-                        return Err(String::from("Incorrect number"));
-                    }
-
-                    let math_character_start: EquationString = left[index..].to_vec();
-                    let math_characters_start_len = math_character_start.len();
-                    if math_characters_start_len > 2 && operator_str == "pi" {
-                        return Err(String::from("Invalid syntax"));
-                    } else if math_characters_start_len > 1 && operator_str != "pi" {
-                        return Err(String::from("Invalid syntax"));
-                    }
-
-                    left_float =
-                        num_parse_res.unwrap() * match_math_character(&operator_str).unwrap();
-                }
-            } else {
-                // handle for +5 or -5 or 1*-5 or 1+-5
-                let left_parse_res = left.clone().to_string().parse::<f64>();
-                if left_parse_res.is_err() {
-                    return Err(String::from("Incorrect number"));
-                }
-
-                left_float = left_parse_res.unwrap();
+    /// the node of a value, the value is wrapped in a leaf unless it already is a node
+    fn from_value(value: &NodeValue) -> Self {
+        match value {
+            NodeValue::OperationValue(node) => node.clone(),
+            value => Self::leaf(value.clone()),
+        }
+    }
+
+    /// parse a single number eg. 5, pi, 5e, 6.02e-23, 0xFF
+    fn parse_value(left: EquationString, offset: usize) -> Result<f64, EquationError> {
+        let span = Span::new(offset, offset + left.len());
+
+        // a number with an exponent or a radix cannot be followed by anything else eg. 1e2.5, 0b12
+        let literal_len = number_literal_len(&left);
+        let has_exponent = left[..literal_len].iter().any(|c| matches!(c, 'e' | 'E'));
+        if literal_len == left.len() || has_exponent || literal_radix(&left).is_some() {
+            return parse_number_literal(&left).ok_or_else(|| Self::number_error(&left, offset));
+        }
+
+        let math_character_position_res = math_character_position(left.clone());
+        if let Some((index, operator)) = math_character_position_res {
+            let left_len = left.len();
+            let operator_str = operator.to_string();
+
+            if index == 0 && (left_len == 1 || operator_str == "pi" && left_len == 2) {
+                return Ok(match_math_character(&operator_str).unwrap());
+            } else if index == 0 {
+                return Err(EquationError::InvalidConstantUsage(span));
+            }
+
+            let num_str: EquationString = left[..index].to_vec();
+            let num_parse_res = num_str.to_string().parse::<f64>();
+            if num_parse_res.is_err() {
+                return Err(Self::number_error(&left[..index], offset));
+            }
+
+            let math_character_start: EquationString = left[index..].to_vec();
+            let math_characters_start_len = math_character_start.len();
+            if (math_characters_start_len > 2 && operator_str == "pi")
+                || (math_characters_start_len > 1 && operator_str != "pi")
+            {
+                return Err(EquationError::InvalidConstantUsage(span));
             }
 
-            return Ok(OperationNode {
-                operation: Operators::None,
-                left_node: Box::new(NodeValue::UnitValue(left_float)),
-                right_node: Box::new(NodeValue::UnitValue(0.0)),
-            });
+            return Ok(num_parse_res.unwrap() * match_math_character(&operator_str).unwrap());
         }
 
-        let left_node_res = Self::create(left);
-        if let Err(t) = left_node_res {
-            return Err(t);
+        // handle for +5 or -5 or 1*-5 or 1+-5
+        let left_parse_res = left.clone().to_string().parse::<f64>();
+        if left_parse_res.is_err() {
+            return Err(Self::number_error(&left, offset));
         }
 
-        let right_node_res = Self::create(right);
-        if let Err(t) = right_node_res {
-            return Err(t);
+        Ok(left_parse_res.unwrap())
+    }
+
+    /// pick the error for a value that cannot be parsed
+    /// an operator that is not a leading sign means the operator is misplaced eg. *1, --1
+    /// otherwise the number itself is incorrect eg. 1.2.3, 1343=3
+    fn number_error(value: &[char], offset: usize) -> EquationError {
+        if value.is_empty() {
+            return EquationError::EmptyExpression(Span::new(offset, offset));
         }
 
-        let left_node = left_node_res.unwrap();
-        let right_node = right_node_res.unwrap();
+        let operator_position = value.iter().enumerate().position(|(i, c)| {
+            let is_operator = Operators::to_enum(&[*c]) != Operators::None || *c == ',';
+            let is_sign = i == 0 && (*c == '+' || *c == '-');
+            is_operator && !is_sign
+        });
 
-        Ok(OperationNode {
-            operation: operator,
-            left_node: Box::new(NodeValue::OperationValue(left_node)),
-            right_node: Box::new(NodeValue::OperationValue(right_node)),
-        })
+        match operator_position {
+            Some(index) => EquationError::UnexpectedOperator(Span::at(offset + index)),
+            None => EquationError::InvalidNumber(Span::new(offset, offset + value.len())),
+        }
     }
 
+    /// calculate the tree without any variables
+    /// a tree that has variables or that is not a number will give NaN, use 'calculate_with' instead
     pub fn calculate(&self) -> f64 {
-        if self.operation == Operators::None {
-            return if let NodeValue::UnitValue(i) = *self.left_node {
-                self.operation.calculate(i, 0.0)
-            } else {
-                0.0
-            };
+        self.calculate_with(&Environment::new()).unwrap_or(f64::NAN)
+    }
+
+    /// calculate the tree using the values of the variables in 'env'
+    /// the result has to be a number, use 'evaluate_with' for any value eg. 1 < 2
+    pub fn calculate_with(&self, env: &Environment) -> Result<f64, CalculationError> {
+        self.evaluate_with(env)?.as_number()
+    }
+
+    /// calculate the tree using the values of the variables in 'env'
+    /// the result is a number or a boolean eg. 1 < 2
+    pub fn evaluate_with(&self, env: &Environment) -> Result<Value, CalculationError> {
+        evaluate(self, env)
+    }
+
+    /// the bitwise operators only work on whole numbers that fit in an i64
+    fn check_integer(operator: &str, value: f64) -> Result<(), CalculationError> {
+        if value.fract() == 0.0 && value.abs() <= i64::MAX as f64 {
+            return Ok(());
         }
 
-        let left_sum = match &*self.left_node {
-            NodeValue::UnitValue(i) => i.to_owned(),
-            NodeValue::OperationValue(node) => node.calculate(),
+        Err(CalculationError::NonIntegerOperand {
+            operator: operator.to_string(),
+            value,
+        })
+    }
+
+    /// the bits shifted to the left have to fit in an i64 eg. 1 << 62 but not 1 << 63
+    fn check_shift(operator: Operators, value: i64, shift: i64) -> Result<(), CalculationError> {
+        let shift = match operator {
+            Operators::ShiftLeft => shift,
+            Operators::ShiftRight => shift.saturating_neg(),
+            _ => return Ok(()),
         };
 
-        let right_sum = match &*self.right_node {
-            NodeValue::UnitValue(i) => i.to_owned(),
-            NodeValue::OperationValue(node) => node.calculate(),
+        match shift_left(value, shift) {
+            Some(_) => Ok(()),
+            None => Err(CalculationError::BitwiseOverflow(
+                operator.symbol().to_string(),
+            )),
+        }
+    }
+}
+
+/// the values of the mode of the environment eg. the exact fractions of the rational mode
+impl Scope for Environment {
+    type Value = Value;
+
+    fn boolean(value: bool) -> Value {
+        Value::Boolean(value)
+    }
+
+    fn as_boolean(value: &Value) -> Result<bool, CalculationError> {
+        value.as_boolean()
+    }
+
+    fn number(&self, value: f64) -> Result<Value, CalculationError> {
+        Ok(Value::Number(value))
+    }
+
+    /// the number literal in the number type of the mode eg. 0.1 as an exact decimal
+    /// a literal with an exponent too large to be written out eg. 1e99999999999 is an f64
+    /// in the decimal, integer and rational modes
+    fn literal(&self, literal: &str) -> Result<Value, CalculationError> {
+        let chars = literal.chars().collect::<EquationString>();
+        let float = parse_number_literal(&chars)
+            .ok_or_else(|| CalculationError::InvalidLiteral(literal.to_string()))?;
+
+        let value = match self.mode() {
+            NumberMode::Float => Value::Number(float),
+            NumberMode::Decimal(_) => decimal_literal(literal)
+                .map(Value::from)
+                .unwrap_or(Value::Number(float)),
+            NumberMode::Integer => parse_integer_literal(literal).unwrap_or(Value::Number(float)),
+            NumberMode::Rational => BigRational::parse_literal(literal)
+                .map(Value::from)
+                .unwrap_or(Value::Number(float)),
+            NumberMode::Complex => Value::Complex(Complex64::from(float)),
+            NumberMode::Interval => Value::Interval(Interval::from_literal(literal)),
         };
+        Ok(value)
+    }
+
+    /// a unit is only used when there is no variable with its name eg. m
+    /// i is the imaginary unit in the complex mode and a variable like any other in the other modes
+    fn variable(&self, name: &str) -> Result<Value, CalculationError> {
+        if name == IMAGINARY_UNIT && matches!(self.mode(), NumberMode::Complex) {
+            return Ok(Value::Complex(Complex64::i()));
+        }
+
+        self.get(name)
+            .or_else(|| unit_value(name))
+            .ok_or_else(|| unbound_variable(name))
+    }
 
-        self.operation.calculate(left_sum, right_sum)
+    /// the constants have no exact fraction so they are an f64 in the integer and rational modes
+    /// and the constants are intervals in the interval mode
+    fn constant(&self, constant: MathConstant) -> Result<Value, CalculationError> {
+        match (constant, self.mode()) {
+            (_, NumberMode::Decimal(ctx)) => decimal_constant(constant, ctx)
+                .map(Value::from)
+                .ok_or_else(|| CalculationError::NoResult(constant.name().to_string())),
+            (_, NumberMode::Complex) => Ok(Value::Complex(Complex64::from(constant.value()))),
+            (_, NumberMode::Interval) => {
+                Ok(Value::Interval(Interval::from_constant(constant.value())))
+            }
+            _ => Ok(Value::Number(constant.value())),
+        }
+    }
+
+    fn calculate_unary(
+        &self,
+        operator: UnaryOperators,
+        value: Value,
+    ) -> Result<Value, CalculationError> {
+        if let Value::Quantity(quantity) = value {
+            return calculate_quantity_unary(operator, *quantity);
+        }
+
+        if operator.is_bitwise() && !matches!(value, Value::Integer(_)) {
+            OperationNode::check_integer(operator.symbol(), value.as_number()?)?;
+        }
+
+        match (self.mode(), value) {
+            (NumberMode::Decimal(ctx), value) if !matches!(value, Value::Number(_)) => Ok(
+                Value::from(calculate_decimal_unary(operator, value.as_decimal()?, ctx)),
+            ),
+            (NumberMode::Integer, Value::Integer(value)) => {
+                Ok(calculate_integer_unary(operator, *value))
+            }
+            (NumberMode::Rational | NumberMode::Integer, Value::Rational(value)) => {
+                Ok(calculate_rational_unary(operator, *value))
+            }
+            (NumberMode::Complex, value) => {
+                calculate_complex_unary(operator, value.as_complex()?).map(Value::Complex)
+            }
+            (NumberMode::Interval, value) => {
+                calculate_interval_unary(operator, value.as_interval()?).map(Value::Interval)
+            }
+            (_, value) => Ok(Value::Number(operator.calculate(value.as_number()?))),
+        }
+    }
+
+    fn calculate_postfix(
+        &self,
+        operator: PostfixOperators,
+        value: Value,
+    ) -> Result<Value, CalculationError> {
+        match (self.mode(), value) {
+            (_, Value::Quantity(quantity)) => calculate_quantity_postfix(operator, *quantity),
+            (NumberMode::Decimal(ctx), value) if !matches!(value, Value::Number(_)) => Ok(
+                calculate_decimal_postfix(operator, value.as_decimal()?, ctx),
+            ),
+            (NumberMode::Integer, Value::Integer(value)) => {
+                Ok(calculate_integer_postfix(operator, *value))
+            }
+            (NumberMode::Rational | NumberMode::Integer, Value::Rational(value)) => {
+                Ok(calculate_rational_postfix(operator, *value))
+            }
+            (NumberMode::Complex, value) => {
+                calculate_complex_postfix(operator, value.as_complex()?).map(Value::Complex)
+            }
+            (NumberMode::Interval, value) => {
+                calculate_interval_postfix(operator, value.as_interval()?).map(Value::Interval)
+            }
+            (_, value) => {
+                let result = operator.calculate(value.as_number()?);
+                Ok(Value::Number(result.unwrap_or(f64::NAN)))
+            }
+        }
+    }
+
+    /// only == and != work on booleans and only to works on units
+    fn calculate_operation(
+        &self,
+        operator: Operators,
+        left_value: Value,
+        right_value: Value,
+    ) -> Result<Value, CalculationError> {
+        let mode = self.mode();
+        if let (Operators::Equal | Operators::NotEqual, Value::Boolean(left_bool)) =
+            (operator, &left_value)
+        {
+            let is_equal = *left_bool == right_value.as_boolean()?;
+            return Ok(Value::Boolean(is_equal == (operator == Operators::Equal)));
+        }
+
+        if operator == Operators::PlusMinus && !matches!(mode, NumberMode::Interval) {
+            return Err(CalculationError::IntervalModeRequired);
+        }
+
+        if operator == Operators::Convert
+            || matches!(left_value, Value::Quantity(_))
+            || matches!(right_value, Value::Quantity(_))
+        {
+            return calculate_quantity(operator, left_value, right_value);
+        }
+
+        if let (NumberMode::Integer, Value::Integer(left_sum), Value::Integer(right_sum)) =
+            (mode, &left_value, &right_value)
+        {
+            return calculate_integer(operator, left_sum, right_sum);
+        }
+
+        if operator.is_bitwise() {
+            let left_sum = left_value.as_number()?;
+            let right_sum = right_value.as_number()?;
+            OperationNode::check_integer(operator.symbol(), left_sum)?;
+            OperationNode::check_integer(operator.symbol(), right_sum)?;
+            OperationNode::check_shift(operator, left_sum as i64, right_sum as i64)?;
+        }
+
+        if let NumberMode::Interval = mode {
+            let left_sum = left_value.as_interval()?;
+            let right_sum = right_value.as_interval()?;
+            return calculate_interval(operator, left_sum, right_sum);
+        }
+
+        // once a value has no exact decimal eg. sin(1) the rest is calculated with f64
+        let is_float =
+            matches!(left_value, Value::Number(_)) || matches!(right_value, Value::Number(_));
+        if let (NumberMode::Decimal(ctx), false) = (mode, is_float) {
+            let left_sum = left_value.as_decimal()?;
+            let right_sum = right_value.as_decimal()?;
+            return calculate_decimal(operator, left_sum, right_sum, ctx);
+        }
+
+        if let NumberMode::Complex = mode {
+            let left_sum = left_value.as_complex()?;
+            let right_sum = right_value.as_complex()?;
+            return calculate_complex(operator, left_sum, right_sum);
+        }
+
+        // the rest of the calculation is done with f64 once a value has no exact fraction
+        let (left_sum, right_sum) = match (left_value, right_value) {
+            (Value::Rational(left_sum), Value::Rational(right_sum)) => {
+                return calculate_rational(operator, *left_sum, *right_sum);
+            }
+            (Value::Integer(left_sum), Value::Rational(right_sum)) => {
+                let left_sum = BigRational::from_integer(*left_sum);
+                return calculate_rational(operator, left_sum, *right_sum);
+            }
+            (Value::Rational(left_sum), Value::Integer(right_sum)) => {
+                let right_sum = BigRational::from_integer(*right_sum);
+                return calculate_rational(operator, *left_sum, right_sum);
+            }
+            (left_value, right_value) => (left_value.as_number()?, right_value.as_number()?),
+        };
+        if let Some(result) = operator.compare(&left_sum, &right_sum) {
+            return Ok(Value::Boolean(result));
+        }
+
+        let result = operator.calculate(left_sum, right_sum);
+        Ok(Value::Number(result.unwrap_or(f64::NAN)))
+    }
+
+    fn call_builtin_function(
+        &self,
+        name: &str,
+        args: Vec<Value>,
+    ) -> Result<Value, CalculationError> {
+        call_builtin_function(name, &args, self)
+    }
+
+    fn environment(&self) -> &Environment {
+        self
+    }
+
+    fn call_scope(&self, function: &Function, args: Vec<Value>) -> Option<Self> {
+        self.enter_call(function, &args)
     }
 }
 
@@ -188,7 +632,7 @@ mod tests {
         let eq = "*1+5";
         assert!(OperationNode::new(eq).is_err());
 
-        let eq = "--1";
+        let eq = "1*-";
         assert!(OperationNode::new(eq).is_err());
 
         let eq = "(1343+3";
@@ -197,10 +641,72 @@ mod tests {
         let eq = "1343=3";
         assert!(OperationNode::new(eq).is_err());
 
-        let eq = "1343x3x";
+        let eq = "1343.3x.3";
         assert!(OperationNode::new(eq).is_err());
     }
 
+    #[test]
+    pub fn test_invalid_eq_error() {
+        let eq = "";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(0, 0)));
+
+        let eq = "*1+5";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::UnexpectedOperator(Span::at(0)));
+
+        let eq = "1*-";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(3, 3)));
+
+        let eq = "-*1";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(1, 1)));
+
+        let eq = "(1343+3";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::UnbalancedParenthesis(Span::at(0)));
+
+        let eq = "1+(2*(3+4)))";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::UnbalancedParenthesis(Span::at(11)));
+
+        let eq = "1343=3";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::InvalidNumber(Span::new(0, 6)));
+
+        let eq = "1+2*1.2.3";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::InvalidNumber(Span::new(4, 9)));
+
+        let eq = "(1+2)*(3+)";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(9, 9)));
+
+        let eq = "1+()";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(3, 3)));
+
+        let eq = "2+π2";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::InvalidConstantUsage(Span::new(2, 4)));
+    }
+
+    #[test]
+    pub fn test_error_points_at_original_eq() {
+        let eq = "2 (1 + 3";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::UnbalancedParenthesis(Span::at(2)));
+
+        let eq = "5e + 1.2.3";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::InvalidNumber(Span::new(5, 10)));
+
+        let eq = "(1 + 2) 3 + ";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(12, 12)));
+    }
+
     #[test]
     pub fn test_math_character() {
         let eq = "1+e";
@@ -227,10 +733,242 @@ mod tests {
         let operation_node = OperationNode::new(eq).unwrap();
         assert_eq!(operation_node.calculate(), 14.778112197861299);
 
-        let eq = "5e2";
+        let eq = "5e2e";
         assert!(OperationNode::new(eq).is_err());
     }
 
+    #[test]
+    pub fn test_scientific_notation() {
+        let cases = [
+            ("5e2", 500.0),
+            ("1e10", 1e10),
+            ("2.5E-3", 0.0025),
+            ("6.02e-23", 6.02e-23),
+            ("1.5e+3-1e3", 500.0),
+            ("2e3^2", 4e6),
+            ("-1e2", -100.0),
+            ("2*1E1(3)", 60.0),
+            (".5e1", 5.0),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(operation_node.calculate(), result, "{}", eq);
+        }
+
+        // without digits after it, e is still Euler's number
+        let eq = "5e - 1";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.calculate(), 5.0 * std::f64::consts::E - 1.0);
+
+        let eq = "5e-x";
+        let mut env = Environment::new();
+        env.set("x", 1.0);
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.calculate_with(&env),
+            Ok(5.0 * std::f64::consts::E - 1.0)
+        );
+
+        let eq = "2e^2";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.calculate(),
+            2.0 * std::f64::consts::E.powf(2.0)
+        );
+
+        let eq = "1e2.5";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::InvalidNumber(Span::new(0, 5)));
+
+        // an exponent too large for an exact number is calculated with f64 in every mode
+        let modes = [
+            NumberMode::Rational,
+            NumberMode::Integer,
+            NumberMode::default_decimal(),
+        ];
+        for mode in modes {
+            let mut env = Environment::new();
+            env.set_mode(mode);
+            for (eq, result) in [
+                ("1e4294967297", f64::INFINITY),
+                ("1e99999999999 + 1", f64::INFINITY),
+            ] {
+                let operation_node = OperationNode::new(eq).unwrap();
+                assert_eq!(
+                    operation_node.evaluate_with(&env),
+                    Ok(Value::Number(result)),
+                    "{}",
+                    eq
+                );
+            }
+        }
+
+        let operation_node = OperationNode::leaf(NodeValue::NumberLiteral(String::from("1.2.3")));
+        assert_eq!(
+            operation_node.evaluate_with(&Environment::new()),
+            Err(CalculationError::InvalidLiteral(String::from("1.2.3")))
+        );
+    }
+
+    #[test]
+    pub fn test_bitwise_operators() {
+        let cases = [
+            ("0xFF", 255.0),
+            ("0b1010+0o17", 25.0),
+            ("0x1e+5", 35.0),
+            ("12&10", 8.0),
+            ("12|3", 15.0),
+            ("12 xor 10", 6.0),
+            ("1<<4", 16.0),
+            ("-16>>2", -4.0),
+            ("1<<-1", 0.0),
+            ("1<<62", 2.0_f64.powi(62)),
+            ("-1<<63", -(2.0_f64.powi(63))),
+            ("1>>64", 0.0),
+            ("~5", -6.0),
+            ("~-1", 0.0),
+            ("1-~1", 3.0),
+            ("1|2 xor 3&4<<1", 3.0),
+            ("1+2<<1", 6.0),
+            ("1|(2)+4", 7.0),
+            ("(1|2)*2", 6.0),
+            ("2*(0xF&6)", 12.0),
+            ("1<<2<<3", 32.0),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(operation_node.calculate(), result, "{}", eq);
+        }
+
+        let eq = "1.5&1";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.calculate_with(&Environment::new()),
+            Err(CalculationError::NonIntegerOperand {
+                operator: "&".to_string(),
+                value: 1.5
+            })
+        );
+
+        let eq = "~(1/2)";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.calculate_with(&Environment::new()),
+            Err(CalculationError::NonIntegerOperand {
+                operator: "~".to_string(),
+                value: 0.5
+            })
+        );
+
+        for eq in ["1<<63", "1<<64", "3<<62", "-1>>-64"] {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert!(
+                matches!(
+                    operation_node.calculate_with(&Environment::new()),
+                    Err(CalculationError::BitwiseOverflow(_))
+                ),
+                "{}",
+                eq
+            );
+        }
+
+        let eq = "0b102";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::InvalidNumber(Span::new(0, 5)));
+
+        let eq = "1|";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(2, 2)));
+    }
+
+    #[test]
+    pub fn test_comparison_and_logical_operators() {
+        let cases = [
+            ("1<2", true),
+            ("2<=1", false),
+            ("1+1>=2", true),
+            ("3>2*2", false),
+            ("0.5==50%", true),
+            ("1!=1", false),
+            ("1<2==2<3", true),
+            ("true&&false", false),
+            ("1<2||1/0>1", true),
+            ("not 1>2", true),
+            ("not true||true", true),
+            ("not(true||true)", false),
+            ("1<<2<5", true),
+            ("12&10==8", true),
+            ("x>-1&&x<10", true),
+            ("(x<5)!=false", true),
+        ];
+
+        let mut env = Environment::new();
+        env.set("x", 2.0);
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(
+                operation_node.evaluate_with(&env),
+                Ok(Value::Boolean(result)),
+                "{}",
+                eq
+            );
+        }
+
+        let eq = "(1<2)+1";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.evaluate_with(&env),
+            Err(CalculationError::ExpectedNumber(Value::Boolean(true)))
+        );
+        assert!(operation_node.calculate().is_nan());
+
+        let eq = "1&&true";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.evaluate_with(&env),
+            Err(CalculationError::ExpectedBoolean(Value::Number(1.0)))
+        );
+    }
+
+    #[test]
+    pub fn test_if() {
+        let mut env = Environment::new();
+        env.set("x", 0.0);
+
+        // the branch that is not chosen is not calculated
+        let eq = "if(x == 0, 0, 1/x) + if(x != 0, y, 2)";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.calculate_with(&env), Ok(2.0));
+
+        let eq = "if(x < 1, x < 2, false)";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.evaluate_with(&env), Ok(Value::Boolean(true)));
+
+        let eq = "2*if(x >= 0, 3, 4)^2";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.calculate_with(&env), Ok(18.0));
+
+        let eq = "if(x, 1, 2)";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.evaluate_with(&env),
+            Err(CalculationError::ExpectedBoolean(Value::Number(0.0)))
+        );
+
+        let eq = "if(true, 1)";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.evaluate_with(&env),
+            Err(CalculationError::ArityMismatch {
+                name: String::from("if"),
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
     #[test]
     pub fn test_starting_with_plus_or_minus() {
         let test_eq = "-(1+2)";
@@ -290,6 +1028,283 @@ mod tests {
         assert_eq!(operation_node.calculate(), 90.0);
     }
 
+    #[test]
+    pub fn test_unary_operators() {
+        let cases = [
+            ("--1", 1.0),
+            ("-+-1", 1.0),
+            ("-0.5++1", 0.5),
+            ("-0.5--1", 0.5),
+            ("2*-3", -6.0),
+            ("2^-1", 0.5),
+            ("-3^2", -9.0),
+            ("-(1+2)*2", -6.0),
+            ("--(1+2)", 3.0),
+            ("-(-2^2)", 4.0),
+            ("-sin(pi/2)", -1.0),
+            ("--2*(3)", 6.0),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(operation_node.calculate(), result, "{}", eq);
+        }
+
+        // each sign is kept in the tree as it was written
+        let operation_node = OperationNode::new("-+x").unwrap();
+        let NodeValue::Unary { operator, node } = *operation_node.left_node else {
+            panic!("expected a unary node");
+        };
+        assert_eq!(operator, UnaryOperators::Negate);
+        let NodeValue::Unary { operator, node } = *node.left_node else {
+            panic!("expected a unary node");
+        };
+        assert_eq!(operator, UnaryOperators::Plus);
+        assert!(matches!(*node.left_node, NodeValue::Variable(ref name) if name == "x"));
+    }
+
+    #[test]
+    pub fn test_postfix_operators() {
+        let cases = [
+            ("5!", 120.0),
+            ("0!", 1.0),
+            ("3!!", 720.0),
+            ("(1+2)!", 6.0),
+            ("-3!", -6.0),
+            ("2^3!", 64.0),
+            ("3!^2", 36.0),
+            ("2*3!", 12.0),
+            ("3!(2)", 12.0),
+            ("(2)!(3)", 6.0),
+            ("12.5%", 0.125),
+            ("200*50%", 100.0),
+            ("50%*200", 100.0),
+            ("1-(50%)", 0.5),
+            ("50%+1", 1.5),
+            ("max(50%, 25%)", 0.5),
+            ("7%(2)", 1.0),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(operation_node.calculate(), result, "{}", eq);
+        }
+
+        let mut env = Environment::new();
+        env.set("n", 4.0);
+        let operation_node = OperationNode::new("(n+1)!/n!").unwrap();
+        assert_eq!(operation_node.calculate_with(&env), Ok(5.0));
+
+        // non-integers use the gamma function
+        let operation_node = OperationNode::new("0.5!").unwrap();
+        let result = operation_node.calculate();
+        assert!((result - std::f64::consts::PI.sqrt() / 2.0).abs() < 1e-12);
+
+        let operation_node = OperationNode::new("(-0.5)!").unwrap();
+        let result = operation_node.calculate();
+        assert!((result - std::f64::consts::PI.sqrt()).abs() < 1e-12);
+
+        let operation_node = OperationNode::new("(-1)!").unwrap();
+        assert!(operation_node.calculate().is_nan());
+
+        let error = OperationNode::new("!5").unwrap_err();
+        assert_eq!(error, EquationError::InvalidNumber(Span::new(0, 2)));
+    }
+
+    #[test]
+    pub fn test_associativity_regression_corpus() {
+        // eq, result with the default options, result with the legacy options
+        let corpus = [
+            ("2^3^2", 512.0, 64.0),
+            ("2^(3)^2", 512.0, 64.0),
+            ("(2)^3^2", 512.0, 64.0),
+            ("2^3^0", 2.0, 1.0),
+            ("-3^2", -9.0, 9.0),
+            ("-2^2", -4.0, 4.0),
+            ("--2^2", 4.0, 4.0),
+            ("-(3)^2", -9.0, 9.0),
+            ("-x^2", -16.0, 16.0),
+            ("2^-1", 0.5, 0.5),
+            ("2^-1^2", 0.5, 0.25),
+            ("1-3^2", -8.0, -8.0),
+            ("2*-3^2", -18.0, 18.0),
+            ("-2^2*3", -12.0, 12.0),
+            ("1-2+3", 2.0, 2.0),
+            ("10-2*1+3", 11.0, 11.0),
+            ("1-(2)+3", 2.0, 2.0),
+            ("8/4/2", 1.0, 1.0),
+            ("1-2-3", -4.0, -4.0),
+            ("(1)-2-(3)", -4.0, -4.0),
+            ("-1+(-2^2^2)*2+3^3", -6.0, 58.0),
+        ];
+
+        let mut env = Environment::new();
+        env.set("x", 4.0);
+
+        for (eq, result, legacy_result) in corpus {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(operation_node.calculate_with(&env), Ok(result), "{}", eq);
+
+            let operation_node = OperationNode::new_with(eq, ParseOptions::legacy()).unwrap();
+            assert_eq!(
+                operation_node.calculate_with(&env),
+                Ok(legacy_result),
+                "{}",
+                eq
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_variables() {
+        let mut env = Environment::new();
+        env.set("x", 2.0);
+        env.set("rate", 0.5);
+        env.set("n_items", 10.0);
+
+        let eq = "x^2+rate*n_items";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.calculate_with(&env), Ok(9.0));
+
+        env.set("x", 3.0);
+        assert_eq!(operation_node.calculate_with(&env), Ok(14.0));
+
+        let eq = "-x*(rate+1)-+x";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.calculate_with(&env), Ok(-7.5));
+
+        let eq = "2x + 3x^2 - 2rate";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.calculate_with(&env), Ok(32.0));
+
+        let eq = "x rate";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.calculate_with(&env), Ok(1.5));
+
+        let eq = "2pi*x2e";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.calculate_with(&env),
+            Err(CalculationError::UnboundVariable(String::from("x2e")))
+        );
+        assert!(operation_node.calculate().is_nan());
+
+        let eq = "-pi+e";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.calculate(),
+            -std::f64::consts::PI + std::f64::consts::E
+        );
+    }
+
+    #[test]
+    pub fn test_function_call() {
+        let eq = "sin(pi/2)+cos(0)";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.calculate(), 2.0);
+
+        let eq = "2sqrt(16)";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.calculate(), 8.0);
+
+        let eq = "2*sqrt(3^2+4^2)(2)";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.calculate(), 20.0);
+
+        let eq = "-max(1, abs(-3)) + ln(e) - log(100)";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.calculate(), -4.0);
+
+        let eq = "exp(ln(2))^2";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(operation_node.calculate(), 4.0);
+
+        let mut env = Environment::new();
+        env.set("x", 4.0);
+        let eq = "max(x, x^2/2)*5pi(1)";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.calculate_with(&env),
+            Ok(8.0 * 5.0 * std::f64::consts::PI)
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_function_call() {
+        let eq = "max(1,)";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(6, 6)));
+
+        let eq = "1 + sin(1 + 2*)";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(14, 14)));
+
+        let eq = "(1,2)";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::UnexpectedOperator(Span::at(2)));
+
+        let eq = "sin(1,2)";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.calculate_with(&Environment::new()),
+            Err(CalculationError::ArityMismatch {
+                name: String::from("sin"),
+                expected: 1,
+                found: 2
+            })
+        );
+
+        let eq = "foo(1)";
+        let operation_node = OperationNode::new(eq).unwrap();
+        assert_eq!(
+            operation_node.calculate_with(&Environment::new()),
+            Err(CalculationError::UnknownFunction(String::from("foo")))
+        );
+    }
+
+    #[test]
+    pub fn test_modulo_and_integer_division() {
+        let cases = [
+            ("7 % 3", 1.0),
+            ("-7 % 3", 2.0),
+            ("7 % (-3)", -2.0),
+            ("10 % -3", -2.0),
+            ("10 % +3", 1.0),
+            ("7 % -3*2", -4.0),
+            ("10% - 3", -2.9),
+            ("10%-3", -2.9),
+            ("7.5 % 2", 1.5),
+            ("7 // 2", 3.0),
+            ("-7 // 2", -4.0),
+            ("7 rem 3", 1.0),
+            ("-7 rem 3", -1.0),
+            ("7 rem -3", 1.0),
+            ("1 + 10 % 4 * 3", 7.0),
+            ("2^5 // 3 rem 4", 2.0),
+            ("(17)rem(5)", 2.0),
+            ("(1+2) % 2 + 17 // (2*2)", 5.0),
+            ("max(7 rem 4, 2)", 3.0),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(operation_node.calculate(), result, "{}", eq);
+        }
+
+        // rem inside of a name is not an operator
+        let mut env = Environment::new();
+        env.set("premium", 9.0);
+        let operation_node = OperationNode::new("premium rem 4").unwrap();
+        assert_eq!(operation_node.calculate_with(&env), Ok(1.0));
+
+        let eq = "7 rem";
+        let error = OperationNode::new(eq).unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(5, 5)));
+
+        let eq = "7 // // 2";
+        assert!(OperationNode::new(eq).is_err());
+    }
+
     #[test]
     pub fn test_more() {
         let eq = "2(e+2)^π*2+-((5+7/2)-3^pi)";
@@ -300,4 +1315,339 @@ mod tests {
         let operation_node = OperationNode::new(eq).unwrap();
         assert_eq!(operation_node.calculate(), 6731.23);
     }
+
+    #[test]
+    pub fn test_decimal_mode() {
+        let mut env = Environment::new();
+        env.set_mode(NumberMode::default_decimal());
+
+        let cases = [
+            ("0.1 + 0.2", "0.3"),
+            ("0.1 + 0.2 == 0.3", "true"),
+            ("1.10 * 3", "3.30"),
+            ("1/3", "0.3333333333333333333333333333"),
+            ("2/3", "0.6666666666666666666666666667"),
+            ("2^-2", "0.25"),
+            ("(-2)^3", "-8"),
+            ("7 % (-3)", "-2"),
+            ("-7 % 3", "2"),
+            ("-7 // 2", "-4"),
+            ("-7 rem 3", "-1"),
+            ("0xFF + 0b1", "256"),
+            ("2.5E-3 * 2", "0.0050"),
+            (".5 + 1", "1.5"),
+            ("20!", "2432902008176640000"),
+            ("200 * 12.5%", "25.000"),
+            ("pi", "3.141592653589793238462643383"),
+            ("-(1.5)", "-1.5"),
+            ("6 & 3 | 8", "10"),
+            ("max(0.1, 0.2)", "0.2"),
+            ("sqrt(2)", "1.414213562373095048801688724"),
+            ("cbrt(-8) + round(-2.5) + floor(1/3)", "-5"),
+            ("abs(-1/3)", "0.3333333333333333333333333333"),
+            ("sqrt(2.25) + sqrt(400)", "21.5"),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let value = operation_node.evaluate_with(&env).unwrap();
+            assert_eq!(value.to_string(), result, "{}", eq);
+        }
+
+        let operation_node = OperationNode::new("1/(2-2)").unwrap();
+        assert_eq!(
+            operation_node.evaluate_with(&env),
+            Err(CalculationError::DivisionByZero)
+        );
+
+        let operation_node = OperationNode::new("1.5 & 1").unwrap();
+        assert!(operation_node.evaluate_with(&env).is_err());
+
+        // a result without an exact decimal is an f64 and the rest is calculated with f64
+        let cases = [
+            ("sin(1) + 0.1", 1.0_f64.sin() + 0.1),
+            ("2^0.5 * 2", 2.0_f64.sqrt() * 2.0),
+            ("-(0.5!)", -OperationNode::new("0.5!").unwrap().calculate()),
+        ];
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let value = operation_node.evaluate_with(&env).unwrap();
+            assert_eq!(value, Value::Number(result), "{}", eq);
+        }
+
+        // each result is rounded to the precision with the rounding mode
+        let modes = [
+            (RoundingMode::HalfEven, "0.66667", "-0.66667"),
+            (RoundingMode::Down, "0.66666", "-0.66666"),
+            (RoundingMode::Floor, "0.66666", "-0.66667"),
+            (RoundingMode::Up, "0.66667", "-0.66667"),
+        ];
+        let positive = OperationNode::new("2/3").unwrap();
+        let negative = OperationNode::new("-2/3").unwrap();
+        for (rounding, positive_result, negative_result) in modes {
+            env.set_mode(NumberMode::decimal(5, rounding).unwrap());
+            let value = positive.evaluate_with(&env).unwrap();
+            assert_eq!(value.to_string(), positive_result, "{:?}", rounding);
+            let value = negative.evaluate_with(&env).unwrap();
+            assert_eq!(value.to_string(), negative_result, "{:?}", rounding);
+        }
+
+        assert!(NumberMode::decimal(0, RoundingMode::HalfEven).is_none());
+    }
+
+    #[test]
+    pub fn test_rational_mode() {
+        let mut env = Environment::new();
+        env.set_mode(NumberMode::Rational);
+
+        let cases = [
+            ("1/3 + 1/6", "1/2"),
+            ("1/3 + 1/6 == 1/2", "true"),
+            ("0.1 + 0.2", "3/10"),
+            ("2.5e-3", "1/400"),
+            ("0xFF / 2", "255/2"),
+            ("(2/3)^-2", "9/4"),
+            ("4^0.5", "2"),
+            ("(8/27)^(1/3)", "2/3"),
+            ("(-8)^(1/3)", "-2"),
+            ("-7/2 % 3", "5/2"),
+            ("-7 // 2", "-4"),
+            ("-7 rem 3", "-1"),
+            ("-(1/3)", "-1/3"),
+            ("12.5%", "1/8"),
+            ("20!/18!", "380"),
+            ("6 & 3 | 8", "10"),
+            ("max(1/3, 1/4)", "1/3"),
+            ("floor(-7/2)", "-4"),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let value = operation_node.evaluate_with(&env).unwrap();
+            assert_eq!(value.to_string(), result, "{}", eq);
+        }
+
+        // the operations without an exact fraction are calculated with f64
+        let cases = [
+            ("2^0.5", 2.0_f64.sqrt()),
+            ("pi/2", std::f64::consts::FRAC_PI_2),
+            ("1/2 + sin(0)", 0.5),
+        ];
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let value = operation_node.evaluate_with(&env).unwrap();
+            assert_eq!(value, Value::Number(result), "{}", eq);
+        }
+
+        let operation_node = OperationNode::new("1/(1/2 - 0.5)").unwrap();
+        assert_eq!(
+            operation_node.evaluate_with(&env),
+            Err(CalculationError::DivisionByZero)
+        );
+
+        let formats = [
+            ("7/2", RationalFormat::Mixed, "3 1/2"),
+            ("-7/2", RationalFormat::Mixed, "-3 1/2"),
+            ("1/2", RationalFormat::Mixed, "1/2"),
+            ("-7/2", RationalFormat::Decimal, "-3.5"),
+            ("1/3", RationalFormat::Decimal, "0.33333333333333333333..."),
+            ("6/3", RationalFormat::Decimal, "2"),
+        ];
+        for (eq, rational, result) in formats {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let value = operation_node.evaluate_with(&env).unwrap();
+            let format = ValueFormat {
+                rational,
+                ..ValueFormat::default()
+            };
+            assert_eq!(value.to_string_with(format), result, "{}", eq);
+        }
+    }
+
+    #[test]
+    pub fn test_integer_mode() {
+        let mut env = Environment::new();
+        env.set_mode(NumberMode::Integer);
+
+        let cases = [
+            ("2^100", "1267650600228229401496703205376"),
+            ("30!", "265252859812191058636308480000000"),
+            ("2^127 - 1", "170141183460469231731687303715884105727"),
+            ("100!/(98!*2!)", "4950"),
+            ("0xFFFFFFFFFFFFFFFFF + 1", "295147905179352825856"),
+            ("1e30 + 1", "1000000000000000000000000000001"),
+            ("6/3", "2"),
+            ("7/2", "7/2"),
+            ("7/2 * 2", "7"),
+            ("1.5", "3/2"),
+            ("2^-2", "1/4"),
+            ("-7 // 2", "-4"),
+            ("-7 % 3", "2"),
+            ("-7 rem 3", "-1"),
+            ("(2^100) >> 98", "4"),
+            ("1 << 70", "1180591620717411303424"),
+            ("~0", "-1"),
+            ("50%", "1/2"),
+            ("2^64 > 2^64 - 1", "true"),
+            ("abs(-2^70)", "1180591620717411303424"),
+            ("max(7/2, 3)", "7/2"),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let value = operation_node.evaluate_with(&env).unwrap();
+            assert_eq!(value.to_string(), result, "{}", eq);
+        }
+
+        let cases = [("2^0.5", 2.0_f64.sqrt()), ("(-1)!", f64::NAN)];
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let value = operation_node.evaluate_with(&env).unwrap().as_number();
+            assert!(
+                value.is_ok_and(|value| value.total_cmp(&result).is_eq()),
+                "{}",
+                eq
+            );
+        }
+
+        for eq in ["1/0", "5 // 0", "0^-1"] {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(
+                operation_node.evaluate_with(&env),
+                Err(CalculationError::DivisionByZero),
+                "{}",
+                eq
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_complex_mode() {
+        let mut env = Environment::new();
+        env.set_mode(NumberMode::Complex);
+
+        let cases = [
+            ("sqrt(-4)", "2i"),
+            ("(1+2i)*(3-i)", "5+5i"),
+            ("e^(i*pi)", "-1"),
+            ("i^2", "-1"),
+            ("2i^2", "-2"),
+            ("1/i", "-i"),
+            ("-(1+2i)", "-1-2i"),
+            ("(-8)^(1/3)", "1+1.732050807568877i"),
+            ("ln(-1)", "3.141592653589793i"),
+            ("abs(3+4i)", "5"),
+            ("conj(1+2i) + re(2i) + im(2i)", "3-2i"),
+            ("(1+i) == (1+i)", "true"),
+            ("max(2, 3) + 50%*i", "3+0.5i"),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let value = operation_node.evaluate_with(&env).unwrap();
+            assert_eq!(value.to_string(), result, "{}", eq);
+        }
+
+        let operation_node = OperationNode::new("i < 1").unwrap();
+        let error = CalculationError::ExpectedReal(Value::Complex(Complex64::i()));
+        assert_eq!(operation_node.evaluate_with(&env), Err(error));
+
+        let format = ValueFormat {
+            complex: ComplexFormat::Polar,
+            ..ValueFormat::default()
+        };
+        let operation_node = OperationNode::new("2i").unwrap();
+        let value = operation_node.evaluate_with(&env).unwrap();
+        assert_eq!(value.to_string_with(format), "2*e^(1.5707963267948966i)");
+
+        // i is the imaginary unit even when a variable i is set
+        env.set("i", 3.0);
+        let operation_node = OperationNode::new("i*i").unwrap();
+        assert_eq!(
+            operation_node.evaluate_with(&env),
+            Ok(Value::Complex(Complex64::new(-1.0, 0.0)))
+        );
+
+        // i is a variable outside of the complex mode
+        let operation_node = OperationNode::new("1+2i").unwrap();
+        assert_eq!(
+            operation_node.calculate_with(&Environment::new()),
+            Err(CalculationError::ComplexModeRequired)
+        );
+        let mut env = Environment::new();
+        env.set("i", 3.0);
+        assert_eq!(operation_node.calculate_with(&env), Ok(7.0));
+        let operation_node = OperationNode::new("(-8)^(1/3)").unwrap();
+        assert!(operation_node.calculate().is_nan());
+    }
+
+    #[test]
+    pub fn test_interval_mode() {
+        let mut env = Environment::new();
+        env.set_mode(NumberMode::Interval);
+
+        let cases = [
+            ("[1.5, 2] + 1", "[2.5, 3.0]"),
+            ("1.25 ± 0.25", "[1.0, 1.5]"),
+            ("[1, 2] * [-3, 4]", "[-6.0, 8.0]"),
+            ("[-2, 3]^2", "[0.0, 9.0]"),
+            ("2[1, 2] - [1, 2]", "[0.0, 3.0]"),
+            ("1/[2, 4]", "[0.25, 0.5]"),
+            ("1/[-1, 1]", "[-inf, inf]"),
+            ("sqrt([4, 9])", "[2.0, 3.0]"),
+            ("abs([-3, 2]) + 50%", "[0.5, 3.5]"),
+            ("[1, 2] < 3", "true"),
+            ("3!", "[6.0, 6.0]"),
+            ("5 & 3", "[1.0, 1.0]"),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let value = operation_node.evaluate_with(&env).unwrap();
+            assert_eq!(value.to_string(), result, "{}", eq);
+        }
+
+        // the bounds are rounded outward so they hold the exact result
+        let cases = [
+            ("0.1 + 0.2", 0.3),
+            ("1/3*3", 1.0),
+            ("pi", std::f64::consts::PI),
+            ("sin(pi)", 0.0),
+        ];
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let value = operation_node.evaluate_with(&env).unwrap();
+            let Value::Interval(value) = value else {
+                panic!("{} is not an interval", eq);
+            };
+            assert!(value.contains(result) && !value.is_point(), "{}", eq);
+        }
+
+        let cases = [
+            (
+                "[1, 3] < 2",
+                CalculationError::UncertainComparison(String::from("<")),
+            ),
+            ("1/[0, 0]", CalculationError::DivisionByZero),
+            (
+                "[1, 2] & 1",
+                CalculationError::ExpectedNumber(Value::Interval(Interval::new(1.0, 2.0))),
+            ),
+        ];
+        for (eq, error) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(operation_node.evaluate_with(&env), Err(error), "{}", eq);
+        }
+
+        // an interval is not a number outside of the interval mode
+        for eq in ["[1, 2]", "1 ± 0.1"] {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(
+                operation_node.calculate_with(&Environment::new()),
+                Err(CalculationError::IntervalModeRequired),
+                "{}",
+                eq
+            );
+        }
+    }
 }
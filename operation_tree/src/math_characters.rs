@@ -1,32 +1,80 @@
 use eq_split::EquationString;
 
+use crate::CalculationError;
+
+/// the name of the imaginary unit, it is only a number in the complex mode
+/// and a variable like any other in the other modes eg. i = 3
+pub const IMAGINARY_UNIT: &str = "i";
+
+/// the math characters that are values of their own eg. pi
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MathConstant {
+    Pi,
+    E,
+}
+
+impl MathConstant {
+    pub fn from_name(val: &str) -> Option<Self> {
+        match val {
+            "π" => Some(Self::Pi), // in macos, option+p
+            "pi" => Some(Self::Pi),
+            "e" => Some(Self::E),
+            _ => None,
+        }
+    }
+
+    /// the name the constant is written with eg. pi
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pi => "pi",
+            Self::E => "e",
+        }
+    }
+
+    pub fn value(&self) -> f64 {
+        match self {
+            Self::Pi => std::f64::consts::PI,
+            Self::E => std::f64::consts::E,
+        }
+    }
+}
+
 pub fn match_math_character(val: &str) -> Option<f64> {
+    MathConstant::from_name(val).map(|constant| constant.value())
+}
+
+/// the error of a variable without a value
+/// i without a value is most likely meant as the imaginary unit
+pub fn unbound_variable(name: &str) -> CalculationError {
+    match name {
+        IMAGINARY_UNIT => CalculationError::ComplexModeRequired,
+        name => CalculationError::UnboundVariable(name.to_string()),
+    }
+}
+
+/// the names of the boolean values
+pub fn match_boolean(val: &str) -> Option<bool> {
     match val {
-        "π" => Some(std::f64::consts::PI), // in macos, option+p
-        "pi" => Some(std::f64::consts::PI),
-        "e" => Some(std::f64::consts::E),
+        "true" => Some(true),
+        "false" => Some(false),
         _ => None,
     }
 }
 
 pub fn math_character_position(val: EquationString) -> Option<(usize, EquationString)> {
-    let has_pi = val.clone().iter().position(|&r| r == 'π');
-    if has_pi.is_some() {
-        return Some((has_pi.unwrap(), vec!['π']));
+    let has_pi = val.iter().position(|&r| r == 'π');
+    if let Some(index) = has_pi {
+        return Some((index, vec!['π']));
     }
 
-    let has_e = val.clone().iter().position(|&r| r == 'e');
-    if has_e.is_some() {
-        return Some((has_e.unwrap(), vec!['e']));
+    let has_e = val.iter().position(|&r| r == 'e');
+    if let Some(index) = has_e {
+        return Some((index, vec!['e']));
     }
 
-
-    let has_pi_word_p = val.clone().iter().position(|&r| r == 'p');
-    let has_pi_word_i = val.clone().iter().position(|&r| r == 'i');
-    if has_pi_word_p.is_some() && has_pi_word_i.is_some() {
-        let index_p = has_pi_word_p.unwrap();
-        let index_i = has_pi_word_i.unwrap();
-
+    let has_pi_word_p = val.iter().position(|&r| r == 'p');
+    let has_pi_word_i = val.iter().position(|&r| r == 'i');
+    if let (Some(index_p), Some(index_i)) = (has_pi_word_p, has_pi_word_i) {
         if index_p + 1 == index_i {
             return Some((index_p, vec!['p','i']));
         }
@@ -0,0 +1,360 @@
+use eq_split::{
+    numbers::literal_radix,
+    operators::{Operators, PostfixOperators, UnaryOperators},
+    units::find_unit,
+};
+
+use crate::{
+    latex::{is_implicit_product, is_sign, node_precedence, precedence},
+    MathConstant, NodeValue, OperationNode, Statement,
+};
+
+impl OperationNode {
+    /// the tree written as Presentation MathML eg. <mfrac> for a division and <msup> for a power
+    /// each operation is an <mrow> so the nesting follows the tree
+    pub fn to_mathml(&self) -> String {
+        math(node_mathml(self))
+    }
+}
+
+impl Statement {
+    /// the statement written as Presentation MathML eg. f(x) = x^2, d/dx (x^2)
+    pub fn to_mathml(&self) -> String {
+        match self {
+            Self::Equation(node) => node.to_mathml(),
+            Self::Assignment(name, node) => math(row(&[mi(name), mo("="), node_mathml(node)])),
+            Self::Definition(name, params, node) => {
+                let params = params
+                    .iter()
+                    .map(|param| mi(param))
+                    .collect::<Vec<String>>();
+                math(row(&[
+                    function_mathml(name),
+                    mo("&#x2061;"),
+                    parentheses(list(&params)),
+                    mo("="),
+                    node_mathml(node),
+                ]))
+            }
+            Self::Derivative(var, node) => {
+                let fraction = format!("<mfrac>{}{}</mfrac>", mi("d"), row(&[mi("d"), mi(var)]));
+                let node = operand(node_mathml(node), node_precedence(node), u8::MAX);
+                math(row(&[fraction, node]))
+            }
+        }
+    }
+}
+
+/// the tree as a single element, an <mrow> when it has more than one part
+fn node_mathml(node: &OperationNode) -> String {
+    let (left, right) = (node.left_node.as_ref(), node.right_node.as_ref());
+    match node.operation {
+        Operators::None => value_mathml(left),
+        Operators::Div => format!(
+            "<mfrac>{}{}</mfrac>",
+            value_mathml(left),
+            value_mathml(right)
+        ),
+        Operators::IntDiv => row(&[
+            mo("⌊"),
+            format!(
+                "<mfrac>{}{}</mfrac>",
+                value_mathml(left),
+                value_mathml(right)
+            ),
+            mo("⌋"),
+        ]),
+        // the exponent is raised so it needs no parentheses
+        Operators::Exp => {
+            let base = operand(
+                value_mathml(left),
+                precedence(left),
+                Operators::Exp.precedence() + 1,
+            );
+            format!("<msup>{}{}</msup>", base, value_mathml(right))
+        }
+        Operators::Mult => {
+            let precedence = Operators::Mult.precedence();
+            let right_mathml = right_operand(right, precedence + 1);
+            // the invisible times keeps 2x a product for screen readers
+            if is_implicit_product(left, right) {
+                return row(&[value_mathml(left), mo("&#x2062;"), right_mathml]);
+            }
+            row(&[
+                operand(value_mathml(left), self::precedence(left), precedence),
+                mo("⋅"),
+                right_mathml,
+            ])
+        }
+        operation => {
+            let precedence = operation.precedence();
+            row(&[
+                operand(value_mathml(left), self::precedence(left), precedence),
+                mo(operator_mathml(operation)),
+                right_operand(right, precedence + 1),
+            ])
+        }
+    }
+}
+
+fn value_mathml(value: &NodeValue) -> String {
+    match value {
+        NodeValue::UnitValue(value) => float_mathml(*value),
+        NodeValue::NumberLiteral(literal) => literal_mathml(literal),
+        NodeValue::Constant(constant) => match constant {
+            MathConstant::Pi => mi("π"),
+            constant => mi(constant.name()),
+        },
+        NodeValue::BooleanValue(value) => mi(&value.to_string()),
+        NodeValue::Variable(name) => variable_mathml(name),
+        NodeValue::Call { name, args } => call_mathml(name, args),
+        NodeValue::Unary { operator, node } => {
+            let node = operand(
+                node_mathml(node),
+                node_precedence(node),
+                operator.precedence(),
+            );
+            let operator = match operator {
+                UnaryOperators::Negate => "−",
+                UnaryOperators::Plus => "+",
+                UnaryOperators::BitNot => "~",
+                UnaryOperators::Not => "¬",
+            };
+            row(&[mo(operator), node])
+        }
+        NodeValue::Postfix { operator, node } => {
+            let node = operand(
+                node_mathml(node),
+                node_precedence(node),
+                operator.precedence(),
+            );
+            let operator = match operator {
+                PostfixOperators::Factorial => "!",
+                PostfixOperators::Percent => "%",
+            };
+            row(&[node, mo(operator)])
+        }
+        NodeValue::OperationValue(node) => node_mathml(node),
+    }
+}
+
+/// the functions with a sign of their own are written with it eg. <msqrt>, |x|
+/// and the others with their name applied to the arguments eg. sin(x), area(r)
+fn call_mathml(name: &str, args: &[OperationNode]) -> String {
+    let args = args.iter().map(node_mathml).collect::<Vec<String>>();
+    match (name, &args[..]) {
+        ("sqrt", [value]) => format!("<msqrt>{}</msqrt>", value),
+        ("cbrt", [value]) => format!("<mroot>{}{}</mroot>", value, mn("3")),
+        ("abs", [value]) => row(&[mo("|"), value.clone(), mo("|")]),
+        ("floor", [value]) => row(&[mo("⌊"), value.clone(), mo("⌋")]),
+        ("ceil", [value]) => row(&[mo("⌈"), value.clone(), mo("⌉")]),
+        ("exp", [value]) => format!("<msup>{}{}</msup>", mi("e"), value),
+        ("interval", [lower, upper]) => {
+            row(&[mo("["), lower.clone(), mo(","), upper.clone(), mo("]")])
+        }
+        _ => row(&[
+            function_mathml(name),
+            mo("&#x2061;"),
+            parentheses(list(&args)),
+        ]),
+    }
+}
+
+/// the name of a function eg. sin, arctan, log with a subscript 10
+fn function_mathml(name: &str) -> String {
+    match name {
+        "asin" | "acos" | "atan" => mi(&format!("arc{}", &name[1..])),
+        "log" => format!("<msub>{}{}</msub>", mi("log"), mn("10")),
+        "log2" => format!("<msub>{}{}</msub>", mi("log"), mn("2")),
+        _ => mi(name),
+    }
+}
+
+fn operator_mathml(operation: Operators) -> &'static str {
+    match operation {
+        Operators::Plus => "+",
+        Operators::Minus => "−",
+        Operators::PlusMinus => "±",
+        Operators::Modulo => "mod",
+        Operators::Rem => "rem",
+        Operators::BitAnd => "&amp;",
+        Operators::BitOr => "|",
+        Operators::BitXor => "⊕",
+        Operators::ShiftLeft => "≪",
+        Operators::ShiftRight => "≫",
+        Operators::Less => "&lt;",
+        Operators::LessEqual => "≤",
+        Operators::Greater => "&gt;",
+        Operators::GreaterEqual => "≥",
+        Operators::Equal => "=",
+        Operators::NotEqual => "≠",
+        Operators::And => "∧",
+        Operators::Or => "∨",
+        Operators::Convert => "→",
+        _ => "",
+    }
+}
+
+/// a number with an exponent is written as a product eg. 6.02⋅10^-23
+fn literal_mathml(literal: &str) -> String {
+    if literal_radix(&literal.chars().collect::<Vec<char>>()).is_some() {
+        return mn(literal);
+    }
+    match literal.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => row(&[
+            mn(mantissa),
+            mo("⋅"),
+            format!(
+                "<msup>{}{}</msup>",
+                mn("10"),
+                number_mathml(exponent.trim_start_matches('+'))
+            ),
+        ]),
+        None => mn(literal),
+    }
+}
+
+fn float_mathml(value: f64) -> String {
+    match value {
+        value if value.is_nan() => mi("NaN"),
+        value if value.is_infinite() && value < 0.0 => row(&[mo("−"), mi("∞")]),
+        value if value.is_infinite() => mi("∞"),
+        value => number_mathml(&value.to_string()),
+    }
+}
+
+/// the minus of a negative number is an operator eg. <mo>−</mo><mn>2</mn>
+fn number_mathml(number: &str) -> String {
+    match number.strip_prefix('-') {
+        Some(number) => row(&[mo("−"), mn(number)]),
+        None => mn(number),
+    }
+}
+
+/// the right side of an operation, a sign is put in parentheses eg. x−(−y)
+fn right_operand(value: &NodeValue, least_precedence: u8) -> String {
+    if is_sign(value) {
+        return parentheses(value_mathml(value));
+    }
+    operand(value_mathml(value), precedence(value), least_precedence)
+}
+
+fn operand(mathml: String, precedence: u8, least_precedence: u8) -> String {
+    if precedence < least_precedence {
+        parentheses(mathml)
+    } else {
+        mathml
+    }
+}
+
+/// the parentheses stretch with their content so a fraction needs nothing more
+fn parentheses(mathml: String) -> String {
+    format!("<mrow>{}{}{}</mrow>", mo("("), mathml, mo(")"))
+}
+
+/// the elements separated by commas eg. the arguments of a call
+fn list(elements: &[String]) -> String {
+    let mut list = Vec::new();
+    for (index, element) in elements.iter().enumerate() {
+        if index > 0 {
+            list.push(mo(","));
+        }
+        list.push(element.clone());
+    }
+    row(&list)
+}
+
+/// the elements as one element, an <mrow> is only needed for more than one
+fn row(elements: &[String]) -> String {
+    match elements {
+        [element] => element.clone(),
+        elements => format!("<mrow>{}</mrow>", elements.concat()),
+    }
+}
+
+fn math(mathml: String) -> String {
+    format!(
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">{}</math>",
+        mathml
+    )
+}
+
+/// a variable with the name of a unit is upright as the unit eg. m in 3 m, a longer name already is
+fn variable_mathml(name: &str) -> String {
+    if find_unit(name).is_some() {
+        return format!("<mi mathvariant=\"normal\">{}</mi>", name);
+    }
+    mi(name)
+}
+
+fn mi(name: &str) -> String {
+    format!("<mi>{}</mi>", name)
+}
+
+fn mn(number: &str) -> String {
+    format!("<mn>{}</mn>", number)
+}
+
+fn mo(operator: &str) -> String {
+    format!("<mo>{}</mo>", operator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_mathml() {
+        let cases = [
+            (
+                "2(π+3)^e/7",
+                "<mfrac><mrow><mn>2</mn><mo>&#x2062;</mo><msup><mrow><mo>(</mo><mrow><mi>π</mi><mo>+</mo><mn>3</mn></mrow><mo>)</mo></mrow><mi>e</mi></msup></mrow><mn>7</mn></mfrac>",
+            ),
+            (
+                "1 - (2 - 3) - x",
+                "<mrow><mrow><mn>1</mn><mo>−</mo><mrow><mo>(</mo><mrow><mn>2</mn><mo>−</mo><mn>3</mn></mrow><mo>)</mo></mrow></mrow><mo>−</mo><mi>x</mi></mrow>",
+            ),
+            (
+                "(1/2)^2 * -x",
+                "<mrow><msup><mrow><mo>(</mo><mfrac><mn>1</mn><mn>2</mn></mfrac><mo>)</mo></mrow><mn>2</mn></msup><mo>⋅</mo><mrow><mo>(</mo><mrow><mo>−</mo><mi>x</mi></mrow><mo>)</mo></mrow></mrow>",
+            ),
+            (
+                "sqrt(x) + cbrt(8)",
+                "<mrow><msqrt><mi>x</mi></msqrt><mo>+</mo><mroot><mn>8</mn><mn>3</mn></mroot></mrow>",
+            ),
+            (
+                "log(x)",
+                "<mrow><msub><mi>log</mi><mn>10</mn></msub><mo>&#x2061;</mo><mrow><mo>(</mo><mi>x</mi><mo>)</mo></mrow></mrow>",
+            ),
+            (
+                "6.02e-23",
+                "<mrow><mn>6.02</mn><mo>⋅</mo><msup><mn>10</mn><mrow><mo>−</mo><mn>23</mn></mrow></msup></mrow>",
+            ),
+            (
+                "3 m to cm",
+                "<mrow><mrow><mn>3</mn><mo>&#x2062;</mo><mi mathvariant=\"normal\">m</mi></mrow><mo>→</mo><mi mathvariant=\"normal\">cm</mi></mrow>",
+            ),
+            (
+                "x <= 1 && y < 2",
+                "<mrow><mrow><mi>x</mi><mo>≤</mo><mn>1</mn></mrow><mo>∧</mo><mrow><mi>y</mi><mo>&lt;</mo><mn>2</mn></mrow></mrow>",
+            ),
+            ("5!", "<mrow><mn>5</mn><mo>!</mo></mrow>"),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(node_mathml(&operation_node), result, "{}", eq);
+        }
+
+        let operation_node = OperationNode::new("pi").unwrap();
+        assert_eq!(
+            operation_node.to_mathml(),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mi>π</mi></math>"
+        );
+        let statement = Statement::new("f(x, y) = x/y").unwrap();
+        assert_eq!(
+            statement.to_mathml(),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mrow><mi>f</mi><mo>&#x2061;</mo><mrow><mo>(</mo><mrow><mi>x</mi><mo>,</mo><mi>y</mi></mrow><mo>)</mo></mrow><mo>=</mo><mfrac><mi>x</mi><mi>y</mi></mfrac></mrow></math>"
+        );
+    }
+}
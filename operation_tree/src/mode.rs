@@ -0,0 +1,44 @@
+use std::num::NonZeroU64;
+
+use bigdecimal::{Context, RoundingMode};
+
+/// the digits kept by default in the decimal mode
+pub const DEFAULT_DECIMAL_PRECISION: u64 = 28;
+
+/// how the numbers of an operation tree are calculated
+#[derive(Debug, Clone, Default)]
+pub enum NumberMode {
+    /// f64 as in the earlier versions eg. 0.1+0.2 = 0.30000000000000004
+    #[default]
+    Float,
+    /// exact decimals eg. 0.1+0.2 = 0.3
+    /// each result is rounded to the precision and with the rounding mode of the context
+    /// an operation without an exact decimal eg. sin(1), 2^0.5 gives an f64 and so does the rest of the calculation
+    Decimal(Context),
+    /// exact whole numbers eg. 2^100 = 1267650600228229401496703205376, 30!
+    /// a division without a whole result gives a fraction eg. 7/2 and the rest is calculated as in the rational mode
+    Integer,
+    /// exact fractions eg. 1/3 + 1/6 = 1/2
+    /// an operation without an exact result eg. 2^0.5, pi gives an f64 and so does the rest of the calculation
+    Rational,
+    /// complex numbers eg. sqrt(-4) = 2i, (-8)^(1/3) = 1+1.7320508075688772i
+    /// i is only a number in this mode
+    Complex,
+    /// intervals that hold the exact result eg. 0.1 = [0.09999999999999999, 0.1]
+    /// written as [1.2, 1.3] or 1.25 ± 0.05, the bounds are rounded outward
+    Interval,
+}
+
+impl NumberMode {
+    /// the decimal mode that keeps 'precision' significant digits eg. 1/3 = 0.333 for 3
+    /// None if 'precision' is 0
+    pub fn decimal(precision: u64, rounding: RoundingMode) -> Option<Self> {
+        let precision = NonZeroU64::new(precision)?;
+        Some(Self::Decimal(Context::new(precision, rounding)))
+    }
+
+    /// the decimal mode with 28 significant digits that rounds half to even
+    pub fn default_decimal() -> Self {
+        Self::decimal(DEFAULT_DECIMAL_PRECISION, RoundingMode::HalfEven).unwrap()
+    }
+}
@@ -0,0 +1,101 @@
+use bigdecimal::{num_bigint::BigInt, Signed, ToPrimitive, Zero};
+use eq_split::operators::{Operators, PostfixOperators, UnaryOperators};
+use num_rational::BigRational;
+
+use crate::{CalculationError, Value};
+
+/// the digits after the point shown by 'RationalFormat::Decimal'
+const DECIMAL_DIGITS: usize = 20;
+
+/// how a fraction is shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RationalFormat {
+    /// eg. 7/2
+    #[default]
+    Fraction,
+    /// the whole part before the fraction eg. 3 1/2
+    Mixed,
+    /// the digits after the point, ... when there are more eg. 0.33333333333333333333...
+    Decimal,
+}
+
+/// calculate the result of the operation with fractions
+/// an operation without an exact result eg. 2^0.5 gives an f64
+pub fn calculate_rational(
+    operator: Operators,
+    left: BigRational,
+    right: BigRational,
+) -> Result<Value, CalculationError> {
+    if let Some(result) = operator.compare(&left, &right) {
+        return Ok(Value::Boolean(result));
+    }
+    if operator.divides_by_zero(&left, &right) {
+        return Err(CalculationError::DivisionByZero);
+    }
+
+    let (float_left, float_right) = (rational_to_float(&left), rational_to_float(&right));
+    match operator.calculate(left, right) {
+        Some(result) => Ok(Value::from(result)),
+        None => {
+            let result = operator.calculate(float_left, float_right);
+            Ok(Value::Number(result.unwrap_or(f64::NAN)))
+        }
+    }
+}
+
+/// calculate the result of the sign or ~ with fractions eg. -x
+pub fn calculate_rational_unary(operator: UnaryOperators, value: BigRational) -> Value {
+    Value::from(operator.calculate(value))
+}
+
+/// calculate the result of the postfix operator with fractions eg. 5!, 12.5%
+/// the factorial of a number that is not whole gives an f64
+pub fn calculate_rational_postfix(operator: PostfixOperators, value: BigRational) -> Value {
+    match operator {
+        PostfixOperators::Percent => Value::from(value / BigInt::from(100)),
+        PostfixOperators::Factorial => match operator.calculate(value.clone()) {
+            Some(product) if value.is_integer() => Value::from(product),
+            _ => Value::Number(
+                operator
+                    .calculate(rational_to_float(&value))
+                    .unwrap_or(f64::NAN),
+            ),
+        },
+    }
+}
+
+/// the fraction as it is shown with 'format'
+pub fn format_rational(value: &BigRational, format: RationalFormat) -> String {
+    if value.is_integer() {
+        return value.to_integer().to_string();
+    }
+
+    match format {
+        RationalFormat::Fraction => value.to_string(),
+        RationalFormat::Mixed => {
+            let whole = value.trunc();
+            if whole.is_zero() {
+                return value.to_string();
+            }
+            format!("{} {}", whole, (value - &whole).abs())
+        }
+        RationalFormat::Decimal => {
+            let sign = if value.is_negative() { "-" } else { "" };
+            let value = value.abs();
+            let mut digits = String::new();
+            let mut remainder = value.fract();
+            while !remainder.is_zero() && digits.len() < DECIMAL_DIGITS {
+                remainder *= BigInt::from(10);
+                digits.push_str(&remainder.to_integer().to_string());
+                remainder = remainder.fract();
+            }
+
+            let ellipsis = if remainder.is_zero() { "" } else { "..." };
+            format!("{}{}.{}{}", sign, value.to_integer(), digits, ellipsis)
+        }
+    }
+}
+
+pub fn rational_to_float(value: &BigRational) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
@@ -0,0 +1,174 @@
+use eq_split::operators::{Operators, PostfixOperators, UnaryOperators};
+
+use crate::{
+    functions::{check_arity, Function},
+    CalculationError, Environment, MathConstant, NodeValue, OperationNode,
+};
+
+/// what the values of a tree are calculated with
+/// eg. 'Environment' for the values of its mode or 'NumberScope' for a number type
+/// each of them walks the tree the same way with 'evaluate'
+pub(crate) trait Scope: Sized {
+    type Value;
+
+    fn boolean(value: bool) -> Self::Value;
+
+    fn as_boolean(value: &Self::Value) -> Result<bool, CalculationError>;
+
+    /// the value of a number that is already calculated eg. the 2.5 of a tree that is built by hand
+    fn number(&self, value: f64) -> Result<Self::Value, CalculationError>;
+
+    /// the value of a number literal eg. 0.1, 6.02e-23, 0xFF
+    fn literal(&self, literal: &str) -> Result<Self::Value, CalculationError>;
+
+    fn constant(&self, constant: MathConstant) -> Result<Self::Value, CalculationError>;
+
+    fn variable(&self, name: &str) -> Result<Self::Value, CalculationError>;
+
+    /// calculate the values of both sides with 'operator', && and || are calculated by 'evaluate'
+    fn calculate_operation(
+        &self,
+        operator: Operators,
+        left_value: Self::Value,
+        right_value: Self::Value,
+    ) -> Result<Self::Value, CalculationError>;
+
+    /// calculate the sign or ~ in front of the value, not is calculated by 'evaluate'
+    fn calculate_unary(
+        &self,
+        operator: UnaryOperators,
+        value: Self::Value,
+    ) -> Result<Self::Value, CalculationError>;
+
+    /// calculate the ! or % after the value
+    fn calculate_postfix(
+        &self,
+        operator: PostfixOperators,
+        value: Self::Value,
+    ) -> Result<Self::Value, CalculationError>;
+
+    fn call_builtin_function(
+        &self,
+        name: &str,
+        args: Vec<Self::Value>,
+    ) -> Result<Self::Value, CalculationError>;
+
+    /// the environment with the functions defined by the user
+    fn environment(&self) -> &Environment;
+
+    /// the scope used inside a call of 'function' with the parameters set to 'args'
+    /// returns None if there are too many nested calls
+    fn call_scope(&self, function: &Function, args: Vec<Self::Value>) -> Option<Self>;
+}
+
+/// calculate the tree with the values of 'scope'
+pub(crate) fn evaluate<S: Scope>(
+    node: &OperationNode,
+    scope: &S,
+) -> Result<S::Value, CalculationError> {
+    let left_value = evaluate_value(&node.left_node, scope)?;
+    if node.operation == Operators::None {
+        return Ok(left_value);
+    }
+
+    // && and || do not calculate the right when the left gives the result eg. x != 0 && 1/x > 2
+    if node.operation.is_logical() {
+        let left_bool = S::as_boolean(&left_value)?;
+        if left_bool == (node.operation == Operators::Or) {
+            return Ok(S::boolean(left_bool));
+        }
+
+        let right_bool = S::as_boolean(&evaluate_value(&node.right_node, scope)?)?;
+        return Ok(S::boolean(right_bool));
+    }
+
+    let right_value = evaluate_value(&node.right_node, scope)?;
+    scope.calculate_operation(node.operation, left_value, right_value)
+}
+
+fn evaluate_value<S: Scope>(value: &NodeValue, scope: &S) -> Result<S::Value, CalculationError> {
+    match value {
+        NodeValue::UnitValue(value) => scope.number(*value),
+        NodeValue::NumberLiteral(literal) => scope.literal(literal),
+        NodeValue::Constant(constant) => scope.constant(*constant),
+        NodeValue::BooleanValue(value) => Ok(S::boolean(*value)),
+        NodeValue::Variable(name) => scope.variable(name),
+        NodeValue::Call { name, args } if name == "if" => evaluate_if(args, scope),
+        NodeValue::Call { name, args } => evaluate_call(name, args, scope),
+        NodeValue::Unary { operator, node } => evaluate_unary(*operator, node, scope),
+        NodeValue::Postfix { operator, node } => evaluate_postfix(*operator, node, scope),
+        NodeValue::OperationValue(node) => evaluate(node, scope),
+    }
+}
+
+/// not gives a boolean in every scope, the other unary operators are calculated by 'scope'
+fn evaluate_unary<S: Scope>(
+    operator: UnaryOperators,
+    node: &OperationNode,
+    scope: &S,
+) -> Result<S::Value, CalculationError> {
+    let value = evaluate(node, scope)?;
+    if operator == UnaryOperators::Not {
+        return Ok(S::boolean(!S::as_boolean(&value)?));
+    }
+    scope.calculate_unary(operator, value)
+}
+
+fn evaluate_postfix<S: Scope>(
+    operator: PostfixOperators,
+    node: &OperationNode,
+    scope: &S,
+) -> Result<S::Value, CalculationError> {
+    let value = evaluate(node, scope)?;
+    scope.calculate_postfix(operator, value)
+}
+
+/// if(condition, then, else) only calculates the branch that is chosen
+/// eg. if(x > 0, 1/x, 0) has no division by zero
+fn evaluate_if<S: Scope>(args: &[OperationNode], scope: &S) -> Result<S::Value, CalculationError> {
+    let [condition, then_node, else_node] = args else {
+        return Err(CalculationError::ArityMismatch {
+            name: String::from("if"),
+            expected: 3,
+            found: args.len(),
+        });
+    };
+
+    if S::as_boolean(&evaluate(condition, scope)?)? {
+        evaluate(then_node, scope)
+    } else {
+        evaluate(else_node, scope)
+    }
+}
+
+/// kept apart from 'evaluate_value' so that the recursive calls use less of the stack
+fn evaluate_call<S: Scope>(
+    name: &str,
+    args: &[OperationNode],
+    scope: &S,
+) -> Result<S::Value, CalculationError> {
+    let args = args
+        .iter()
+        .map(|arg| evaluate(arg, scope))
+        .collect::<Result<Vec<S::Value>, CalculationError>>()?;
+    call_function(name, args, scope)
+}
+
+/// call the function 'name' with the calculated arguments
+/// the functions defined in the environment of 'scope' are used before the builtin functions
+/// their body is calculated with the parameters set to the arguments
+pub(crate) fn call_function<S: Scope>(
+    name: &str,
+    args: Vec<S::Value>,
+    scope: &S,
+) -> Result<S::Value, CalculationError> {
+    let Some(function) = scope.environment().get_function(name) else {
+        return scope.call_builtin_function(name, args);
+    };
+    check_arity(name, function.params.len(), &args)?;
+
+    let call_scope = scope
+        .call_scope(&function, args)
+        .ok_or_else(|| CalculationError::RecursionLimit(name.to_string()))?;
+    evaluate(&function.body, &call_scope)
+}
@@ -0,0 +1,693 @@
+use bigdecimal::{num_bigint::BigInt, One, Signed, ToPrimitive, Zero};
+use eq_split::{
+    arithmetic::Number,
+    operators::{Operators, PostfixOperators, UnaryOperators},
+};
+use num_rational::BigRational;
+
+use crate::{
+    functions::match_builtin_function,
+    rational::{
+        calculate_rational, calculate_rational_postfix, calculate_rational_unary, format_rational,
+        RationalFormat,
+    },
+    NodeValue, OperationNode, Value,
+};
+
+/// the largest whole power of a number that is worked out eg. (2*x)^3 is 8*x^3
+const MAX_EXPONENT: u32 = 256;
+
+/// the largest whole number that the bitwise operators work out exactly, see f64::MAX_EXACT
+const MAX_BITWISE: f64 = 9_007_199_254_740_992.0;
+
+impl OperationNode {
+    /// the same tree written in a shorter way eg. x for x*1 + 0
+    /// the operations on numbers are done with fractions eg. 1/2 for 1/3 + 1/6
+    /// and the ones without an exact result are kept eg. sqrt(2), 2^0.5
+    /// the like terms are added eg. 5*x for 2*x + 3*x
+    /// and the common factors are cancelled eg. x for x^2/x or 1 for x/x
+    /// the simplified tree has the same value wherever the tree has one
+    /// but it may have a value where the tree has none eg. 1 for x/x at x = 0, x for x^0.5*x^0.5 at x = -1
+    /// a part without a value for any value of its variables is kept eg. 0*(1/0)
+    pub fn simplify(&self) -> OperationNode {
+        if self.operation == Operators::None {
+            return simplify_value(&self.left_node);
+        }
+
+        let left = OperationNode::from_value(&self.left_node).simplify();
+        let right = OperationNode::from_value(&self.right_node).simplify();
+        if let Some(result) = fold(self.operation, &left, &right) {
+            return result;
+        }
+
+        let node = OperationNode::binary(self.operation, left, right);
+        match self.operation {
+            Operators::Plus | Operators::Minus => from_terms(terms(node)),
+            Operators::Mult | Operators::Div | Operators::Exp => from_product(product(node)),
+            _ => node,
+        }
+    }
+}
+
+/// a number times factors with their exponents eg. 3*x^2/y is 3 * x^2 * y^-1
+struct Product {
+    coefficient: BigRational,
+    factors: Vec<(OperationNode, BigRational)>,
+}
+
+impl Product {
+    fn number(value: BigRational) -> Self {
+        Product {
+            coefficient: value,
+            factors: Vec::new(),
+        }
+    }
+
+    fn factor(node: OperationNode) -> Self {
+        Product {
+            coefficient: BigRational::one(),
+            factors: vec![(node, BigRational::one())],
+        }
+    }
+
+    fn mul(mut self, other: Product) -> Self {
+        self.coefficient *= other.coefficient;
+        for (base, exponent) in other.factors {
+            self.push(base, exponent);
+        }
+        self
+    }
+
+    /// multiply with base^exponent, the exponents of the same base are added eg. x^3 for x^2*x
+    fn push(&mut self, base: OperationNode, exponent: BigRational) {
+        let key = base.to_string();
+        match self
+            .factors
+            .iter()
+            .position(|(other, _)| other.to_string() == key)
+        {
+            Some(index) => {
+                self.factors[index].1 += exponent;
+                if self.factors[index].1.is_zero() {
+                    self.factors.remove(index);
+                }
+            }
+            None => self.factors.push((base, exponent)),
+        }
+    }
+
+    /// the product to a whole power, None for 1/0 or for a number that gets too large
+    fn pow(mut self, exponent: i32) -> Option<Self> {
+        if self.coefficient.is_zero() && exponent < 0 {
+            return None;
+        }
+        let is_unit = self.coefficient.abs().is_one();
+        if !is_unit && exponent.unsigned_abs() > MAX_EXPONENT {
+            return None;
+        }
+
+        self.coefficient = self.coefficient.pow(exponent);
+        for (_, power) in &mut self.factors {
+            *power *= BigRational::from_integer(BigInt::from(exponent));
+        }
+        Some(self)
+    }
+
+    fn negate(mut self) -> Self {
+        self.coefficient = -self.coefficient;
+        self
+    }
+
+    /// the factors without the number, the like terms have the same key eg. 2*x*y and y*x/3
+    fn key(&self) -> Vec<(String, BigRational)> {
+        let mut key = self
+            .factors
+            .iter()
+            .map(|(base, exponent)| (base.to_string(), exponent.clone()))
+            .collect::<Vec<(String, BigRational)>>();
+        key.sort();
+        key
+    }
+}
+
+/// simplify a single value
+fn simplify_value(value: &NodeValue) -> OperationNode {
+    match value {
+        NodeValue::OperationValue(node) => node.simplify(),
+        NodeValue::NumberLiteral(literal) => match BigRational::parse_literal(literal) {
+            Some(number) => rational_node(&number),
+            None => OperationNode::leaf(value.clone()),
+        },
+        NodeValue::UnitValue(number) => match BigRational::from_float(*number) {
+            Some(number) => rational_node(&number),
+            None => OperationNode::leaf(value.clone()),
+        },
+        NodeValue::Call { name, args } => {
+            let args = args
+                .iter()
+                .map(|arg| arg.simplify())
+                .collect::<Vec<OperationNode>>();
+            simplify_call(name, args)
+        }
+        NodeValue::Unary { operator, node } => simplify_unary(*operator, node.simplify()),
+        NodeValue::Postfix { operator, node } => simplify_postfix(*operator, node.simplify()),
+        _ => OperationNode::leaf(value.clone()),
+    }
+}
+
+/// not not x is x when x is true or false, -x is a product with -1 and the sign of a number is worked out
+fn simplify_unary(operator: UnaryOperators, node: OperationNode) -> OperationNode {
+    match operator {
+        UnaryOperators::Plus => node,
+        UnaryOperators::Negate => {
+            from_product(Product::number(-BigRational::one()).mul(product(node)))
+        }
+        UnaryOperators::Not => match node.left_node.as_ref() {
+            NodeValue::BooleanValue(value) if node.operation == Operators::None => {
+                OperationNode::leaf(NodeValue::BooleanValue(!value))
+            }
+            NodeValue::Unary {
+                operator: UnaryOperators::Not,
+                node: inner,
+            } if node.operation == Operators::None && is_boolean(inner) => inner.clone(),
+            _ => unary(operator, node),
+        },
+        UnaryOperators::BitNot => match as_rational(&node) {
+            Some(value) if is_whole_number(&value) => {
+                exact_node(calculate_rational_unary(operator, value))
+                    .unwrap_or_else(|| unary(operator, node))
+            }
+            _ => unary(operator, node),
+        },
+    }
+}
+
+/// x% is x/100 and the factorial of a whole number is worked out
+fn simplify_postfix(operator: PostfixOperators, node: OperationNode) -> OperationNode {
+    match (operator, as_rational(&node)) {
+        (PostfixOperators::Percent, _) => {
+            let hundredth = BigRational::new(BigInt::one(), BigInt::from(100));
+            from_product(product(node).mul(Product::number(hundredth)))
+        }
+        (PostfixOperators::Factorial, Some(value))
+            if value.is_integer() && !value.is_negative() =>
+        {
+            exact_node(calculate_rational_postfix(operator, value))
+                .unwrap_or_else(|| postfix(operator, node))
+        }
+        _ => postfix(operator, node),
+    }
+}
+
+/// a call with numbers is worked out when the result is exact eg. abs(-2), sqrt(4), sin(0)
+/// if with a known condition is the branch it picks
+fn simplify_call(name: &str, args: Vec<OperationNode>) -> OperationNode {
+    if let ("if", [condition, then_branch, else_branch]) = (name, &args[..]) {
+        if let NodeValue::BooleanValue(value) = condition.left_node.as_ref() {
+            if condition.operation == Operators::None {
+                return if *value { then_branch } else { else_branch }.clone();
+            }
+        }
+    }
+
+    let numbers = args
+        .iter()
+        .map(as_rational)
+        .collect::<Option<Vec<BigRational>>>();
+    if let Some(numbers) = numbers {
+        if let Some(result) = call_exact(name, &numbers) {
+            return rational_node(&result);
+        }
+    }
+
+    OperationNode::leaf(NodeValue::Call {
+        name: name.to_string(),
+        args,
+    })
+}
+
+/// the result of a builtin function when it is exact
+/// the roots are worked out with fractions and the other functions when they give a whole number
+fn call_exact(name: &str, args: &[BigRational]) -> Option<BigRational> {
+    let root = match name {
+        "sqrt" => Some(2),
+        "cbrt" => Some(3),
+        _ => None,
+    };
+    match (root, args) {
+        (Some(root), [value]) if !value.is_negative() || root == 3 => {
+            let exponent = BigRational::new(BigInt::one(), BigInt::from(root));
+            if value.is_negative() {
+                return value.abs().checked_pow(exponent).map(|root| -root);
+            }
+            return value.clone().checked_pow(exponent);
+        }
+        (Some(_), _) => return None,
+        _ => {}
+    }
+
+    if let Some(result) = BigRational::call_function(name, args) {
+        return Some(result);
+    }
+
+    let (arity, function) = match_builtin_function(name)?;
+    if arity != args.len() {
+        return None;
+    }
+    let floats = args.iter().map(|arg| arg.to_float()).collect::<Vec<f64>>();
+    let result = function(&floats);
+    if result.fract() == 0.0 && result.abs() < MAX_BITWISE {
+        return BigRational::from_float(result);
+    }
+    None
+}
+
+/// the operation on two numbers eg. 1/2 for 1/3 + 1/6, true for 2 < 3
+/// or on true and false eg. x for true && x
+fn fold(
+    operation: Operators,
+    left: &OperationNode,
+    right: &OperationNode,
+) -> Option<OperationNode> {
+    if operation.is_logical() {
+        return fold_logical(operation, left, right);
+    }
+    if matches!(operation, Operators::Convert | Operators::PlusMinus) {
+        return None;
+    }
+
+    let (left, right) = (as_rational(left)?, as_rational(right)?);
+    if operation.is_bitwise() && !(is_whole_number(&left) && is_whole_number(&right)) {
+        return None;
+    }
+    exact_node(calculate_rational(operation, left, right).ok()?)
+}
+
+/// && and || with true or false on one side
+/// the left side is always calculated so it is only dropped when it is true or false
+/// the other side is only kept alone when it is true or false eg. x && true is not x
+/// as && gives an error for a number x
+fn fold_logical(
+    operation: Operators,
+    left: &OperationNode,
+    right: &OperationNode,
+) -> Option<OperationNode> {
+    let is_and = operation == Operators::And;
+    match (as_boolean(left), as_boolean(right)) {
+        (Some(value), _) if value == is_and && is_boolean(right) => Some(right.clone()),
+        (Some(value), _) if value != is_and => {
+            Some(OperationNode::leaf(NodeValue::BooleanValue(value)))
+        }
+        (_, Some(value)) if value == is_and && is_boolean(left) => Some(left.clone()),
+        _ => None,
+    }
+}
+
+/// the terms of a sum, the numbers times a sum are spread over its terms eg. 2*x, 2 for 2*(x + 1)
+fn terms(node: OperationNode) -> Vec<Product> {
+    match node.operation {
+        Operators::Plus | Operators::Minus => {
+            let mut left = terms(OperationNode::from_value(&node.left_node));
+            let right = terms(OperationNode::from_value(&node.right_node));
+            if node.operation == Operators::Minus {
+                left.extend(right.into_iter().map(Product::negate));
+            } else {
+                left.extend(right);
+            }
+            left
+        }
+        _ => {
+            let term = product(node);
+            match &term.factors[..] {
+                [(base, exponent)] if exponent.is_one() && is_sum(base) => {
+                    let coefficient = term.coefficient.clone();
+                    terms(base.clone())
+                        .into_iter()
+                        .map(|inner| inner.mul(Product::number(coefficient.clone())))
+                        .collect()
+                }
+                _ => vec![term],
+            }
+        }
+    }
+}
+
+/// the sum of the terms, the like terms are added in the place of the first one eg. 3*x + 1 for x + 1 + 2*x
+fn from_terms(terms: Vec<Product>) -> OperationNode {
+    let mut sum: Vec<Product> = Vec::new();
+    for term in terms {
+        let key = term.key();
+        match sum.iter_mut().find(|other| other.key() == key) {
+            Some(other) => other.coefficient += term.coefficient,
+            None => sum.push(term),
+        }
+    }
+    sum.retain(|term| !is_zero_term(term));
+
+    let mut sum = sum.into_iter();
+    let first = match sum.next() {
+        Some(first) => from_product(first),
+        None => return rational_node(&BigRational::zero()),
+    };
+    sum.fold(first, |result, term| {
+        if term.coefficient.is_negative() {
+            OperationNode::binary(Operators::Minus, result, from_product(term.negate()))
+        } else {
+            OperationNode::binary(Operators::Plus, result, from_product(term))
+        }
+    })
+}
+
+/// the factors of a product eg. 2, x^2, y^-1 for 2*x^2/y
+fn product(node: OperationNode) -> Product {
+    if let Some(value) = as_rational(&node) {
+        return Product::number(value);
+    }
+
+    let left = OperationNode::from_value(&node.left_node);
+    let right = OperationNode::from_value(&node.right_node);
+    match node.operation {
+        Operators::Mult => product(left).mul(product(right)),
+        Operators::Div => match product(right).pow(-1) {
+            Some(inverse) => product(left).mul(inverse),
+            None => Product::factor(node),
+        },
+        Operators::Exp => match as_rational(&right) {
+            Some(exponent) if exponent.is_integer() => {
+                match exponent
+                    .to_i32()
+                    .and_then(|exponent| product(left).pow(exponent))
+                {
+                    Some(power) => power,
+                    None => Product::factor(node),
+                }
+            }
+            // only a single value is kept under a root eg. x^0.5 but not (4*x)^0.5
+            Some(exponent) => match product(left) {
+                Product {
+                    coefficient,
+                    factors,
+                } if coefficient.is_one() && factors.len() == 1 && factors[0].1.is_one() => {
+                    let mut power = Product::number(coefficient);
+                    power.push(factors[0].0.clone(), exponent);
+                    power
+                }
+                _ => Product::factor(node),
+            },
+            None => Product::factor(node),
+        },
+        Operators::None => match node.left_node.as_ref() {
+            NodeValue::Unary {
+                operator: UnaryOperators::Negate,
+                node: inner,
+            } => Product::number(-BigRational::one()).mul(product(inner.clone())),
+            _ => Product::factor(node),
+        },
+        _ => Product::factor(node),
+    }
+}
+
+/// the node of the product, the negative exponents are put under a division eg. 2*x/y
+/// 0 times factors that have a value somewhere is 0 but 0*(1/0) is kept as it has no result
+fn from_product(product: Product) -> OperationNode {
+    if is_zero_term(&product) || product.factors.is_empty() {
+        return rational_node(&product.coefficient);
+    }
+
+    let negative = product.coefficient.is_negative();
+    let coefficient = product.coefficient.abs();
+    let mut numerator = Vec::new();
+    let mut denominator = Vec::new();
+    if !coefficient.numer().is_one() {
+        numerator.push(integer_node(coefficient.numer()));
+    }
+    if !coefficient.denom().is_one() {
+        denominator.push(integer_node(coefficient.denom()));
+    }
+    for (base, exponent) in product.factors {
+        if exponent.is_negative() {
+            denominator.push(power_node(base, -exponent));
+        } else {
+            numerator.push(power_node(base, exponent));
+        }
+    }
+
+    let numerator = multiply(numerator);
+    let result = match denominator.is_empty() {
+        true => numerator,
+        false => OperationNode::binary(Operators::Div, numerator, multiply(denominator)),
+    };
+    match negative {
+        true => unary(UnaryOperators::Negate, result),
+        false => result,
+    }
+}
+
+/// the product of the nodes, 1 when there are none
+fn multiply(nodes: Vec<OperationNode>) -> OperationNode {
+    let mut nodes = nodes.into_iter();
+    match nodes.next() {
+        Some(first) => nodes.fold(first, |result, node| {
+            OperationNode::binary(Operators::Mult, result, node)
+        }),
+        None => rational_node(&BigRational::one()),
+    }
+}
+
+fn power_node(base: OperationNode, exponent: BigRational) -> OperationNode {
+    match exponent.is_one() {
+        true => base,
+        false => OperationNode::binary(Operators::Exp, base, rational_node(&exponent)),
+    }
+}
+
+/// the node of a fraction eg. 2, 0.25, 1/3, a negative number is a sign in front of it eg. -(2)
+fn rational_node(value: &BigRational) -> OperationNode {
+    if value.is_negative() {
+        return unary(UnaryOperators::Negate, rational_node(&-value));
+    }
+    if value.is_integer() {
+        return integer_node(&value.to_integer());
+    }
+
+    let decimal = format_rational(value, RationalFormat::Decimal);
+    if !decimal.ends_with("...") {
+        return OperationNode::leaf(NodeValue::NumberLiteral(decimal));
+    }
+    OperationNode::binary(
+        Operators::Div,
+        integer_node(value.numer()),
+        integer_node(value.denom()),
+    )
+}
+
+fn integer_node(value: &BigInt) -> OperationNode {
+    OperationNode::leaf(NodeValue::NumberLiteral(value.to_string()))
+}
+
+/// the node of an exact result, None for an f64 result eg. 2^0.5
+fn exact_node(value: Value) -> Option<OperationNode> {
+    match value {
+        Value::Rational(value) => Some(rational_node(&value)),
+        Value::Boolean(value) => Some(OperationNode::leaf(NodeValue::BooleanValue(value))),
+        _ => None,
+    }
+}
+
+fn unary(operator: UnaryOperators, node: OperationNode) -> OperationNode {
+    OperationNode::leaf(NodeValue::Unary { operator, node })
+}
+
+fn postfix(operator: PostfixOperators, node: OperationNode) -> OperationNode {
+    OperationNode::leaf(NodeValue::Postfix { operator, node })
+}
+
+/// the fraction of a node that is only a number eg. 2, -0.5, 1/3
+fn as_rational(node: &OperationNode) -> Option<BigRational> {
+    match node.operation {
+        Operators::None => {}
+        Operators::Div => {
+            let numerator = as_rational(&OperationNode::from_value(&node.left_node))?;
+            let denominator = as_rational(&OperationNode::from_value(&node.right_node))?;
+            return numerator.checked_div(denominator);
+        }
+        _ => return None,
+    }
+
+    match node.left_node.as_ref() {
+        NodeValue::NumberLiteral(literal) => BigRational::parse_literal(literal),
+        NodeValue::UnitValue(value) => BigRational::from_float(*value),
+        NodeValue::Unary {
+            operator: UnaryOperators::Negate,
+            node,
+        } => as_rational(node).map(|value| -value),
+        NodeValue::OperationValue(node) => as_rational(node),
+        _ => None,
+    }
+}
+
+fn as_boolean(node: &OperationNode) -> Option<bool> {
+    match node.left_node.as_ref() {
+        NodeValue::BooleanValue(value) if node.operation == Operators::None => Some(*value),
+        _ => None,
+    }
+}
+
+/// a whole number that the bitwise operators work out exactly
+fn is_whole_number(value: &BigRational) -> bool {
+    value.is_integer() && value.abs().to_float() < MAX_BITWISE
+}
+
+/// check if the node has a value for some value of its variables eg. 1/x, sqrt(x) but not 1/0, x/0
+/// a part without variables is calculated to know if it has a value
+fn has_value(node: &OperationNode) -> bool {
+    if !has_variable(node) {
+        return node.calculate().is_finite();
+    }
+
+    let has_value_at = |value: &NodeValue| match value {
+        NodeValue::Call { args, .. } => args.iter().all(has_value),
+        NodeValue::Unary { node, .. }
+        | NodeValue::Postfix { node, .. }
+        | NodeValue::OperationValue(node) => has_value(node),
+        _ => true,
+    };
+    let divides_by_zero = matches!(
+        node.operation,
+        Operators::Div | Operators::Modulo | Operators::IntDiv | Operators::Rem
+    ) && as_rational(&OperationNode::from_value(&node.right_node))
+        .is_some_and(|divisor| divisor.is_zero());
+    has_value_at(&node.left_node) && has_value_at(&node.right_node) && !divides_by_zero
+}
+
+fn has_variable(node: &OperationNode) -> bool {
+    [&node.left_node, &node.right_node]
+        .iter()
+        .any(|value| match value.as_ref() {
+            NodeValue::Variable(_) => true,
+            NodeValue::Call { args, .. } => args.iter().any(has_variable),
+            NodeValue::Unary { node, .. }
+            | NodeValue::Postfix { node, .. }
+            | NodeValue::OperationValue(node) => has_variable(node),
+            _ => false,
+        })
+}
+
+/// a term that is 0 wherever it has a value eg. x - x, 1/x - 1/x but not 0*(1/0)
+fn is_zero_term(term: &Product) -> bool {
+    term.coefficient.is_zero() && term.factors.iter().all(|(base, _)| has_value(base))
+}
+
+/// check if the node is true or false eg. x > 1, not x, a && b
+fn is_boolean(node: &OperationNode) -> bool {
+    if node.operation.is_comparison() || node.operation.is_logical() {
+        return true;
+    }
+
+    match node.left_node.as_ref() {
+        _ if node.operation != Operators::None => false,
+        NodeValue::BooleanValue(_)
+        | NodeValue::Unary {
+            operator: UnaryOperators::Not,
+            ..
+        } => true,
+        NodeValue::OperationValue(node) => is_boolean(node),
+        _ => false,
+    }
+}
+
+fn is_sum(node: &OperationNode) -> bool {
+    matches!(node.operation, Operators::Plus | Operators::Minus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Environment;
+
+    #[test]
+    pub fn test_simplify() {
+        let cases = [
+            ("x*1 + 0", "x"),
+            ("x^1", "x"),
+            ("-1 * -1 * x", "x"),
+            ("2*x + 3*x", "5*x"),
+            ("x - x", "0"),
+            ("x*x", "x^2"),
+            ("x^2/x", "x"),
+            ("(x*y)/(y*x)", "1"),
+            ("2*(x + 1) - 2", "2*x"),
+            ("x/2 + x/3", "5*x/6"),
+            ("1/3 + 1/6", "0.5"),
+            ("1/3", "1/3"),
+            ("2^-2 * y", "y/4"),
+            ("-(x + 1) + x", "-1"),
+            ("3 - x", "3 - x"),
+            ("x + 1 + 2*x", "3*x + 1"),
+            ("sqrt(4) + abs(-2) + sin(0)", "4"),
+            ("sqrt(2)", "sqrt(2)"),
+            ("2^0.5", "2^0.5"),
+            ("4^0.5*x^0.5*x^0.5", "2*x"),
+            ("x/0", "x/0"),
+            ("2 < 3 && x > 1", "x > 1"),
+            ("if(1 > 2, x, y)", "y"),
+            ("not not x", "not not x"),
+            ("not not (x > 1)", "x > 1"),
+            ("0*(1/0) + 0*x", "0*(1/0)"),
+            ("1/x - 1/x", "0"),
+            ("0*sqrt(x) + 0*(x/0)", "0*(x/0)"),
+            ("x && true", "x && true"),
+            ("true && x", "true && x"),
+            ("x > 1 || false", "x > 1"),
+            ("false && x", "false"),
+            ("5! + 50%", "120.5"),
+            ("pi*2 - pi", "pi"),
+            ("3 m to cm", "3*m to cm"),
+            ("(x*y)/(y*x) + 2*x + 3*x", "1 + 5*x"),
+        ];
+
+        for (eq, result) in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            assert_eq!(operation_node.simplify().to_string(), result, "{}", eq);
+        }
+    }
+
+    #[test]
+    pub fn test_simplify_value() {
+        let cases = [
+            "(x + 1)^2 - (x - 1)*(x + 1)",
+            "x^3/(2*x) - 3*x*x/4",
+            "(2*x)^-2 * 8 + 1/x^2",
+            "sin(x)^2 + 0.1 + cos(x)*cos(x) - 1/10",
+            "-(3 - x)*2 + 7 % 4 + x^0.5*sqrt(x)",
+            "if(x > 1, x*2, 0) + 5! / 3!",
+            "x^(1/3)*x^(2/3) - (x*y)/y",
+        ];
+
+        for eq in cases {
+            let operation_node = OperationNode::new(eq).unwrap();
+            let simplified = operation_node.simplify();
+            let at = |node: &OperationNode| {
+                let mut env = Environment::new();
+                env.set("x", 1.7);
+                env.set("y", -2.5);
+                node.calculate_with(&env).unwrap()
+            };
+            let (value, simplified_value) = (at(&operation_node), at(&simplified));
+            assert!(
+                (value - simplified_value).abs() < 1e-12 * value.abs().max(1.0),
+                "{} = {}",
+                eq,
+                simplified
+            );
+        }
+
+        // the simplified tree gives the same errors
+        let operation_node = OperationNode::new("0*(1/0)").unwrap();
+        assert!(operation_node.simplify().calculate().is_nan());
+        let operation_node = OperationNode::new("x && true").unwrap();
+        let mut env = Environment::new();
+        env.set("x", 2.0);
+        assert!(operation_node.simplify().evaluate_with(&env).is_err());
+    }
+}
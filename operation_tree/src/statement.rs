@@ -0,0 +1,424 @@
+use eq_split::{
+    calls::SplitCall,
+    eq_sanitize::EqSanitize,
+    identifiers::{is_identifier, is_identifier_char},
+    operators::is_word_operator,
+    EquationString,
+};
+
+use crate::{
+    functions::is_builtin_function,
+    math_characters::{match_boolean, match_math_character, IMAGINARY_UNIT},
+    CalculationError, Environment, EquationError, NumberMode, OperationNode, ParseOptions, Span,
+    Value,
+};
+
+/// a line entered by the user
+/// either an equation to calculate eg. 2*x+1
+/// an assignment of an equation to a variable eg. x = 3*pi
+/// or a definition of a function eg. f(x, y) = x^2 + y
+/// or the derivative of an equation by a variable eg. d/dx (x^2*sin(x))
+#[derive(Debug)]
+pub enum Statement {
+    Equation(OperationNode),
+    Assignment(String, OperationNode),
+    Definition(String, Vec<String>, OperationNode),
+    Derivative(String, OperationNode),
+}
+
+impl Statement {
+    /// the errors returned point at the positions in 'eq'
+    pub fn new(eq: &str) -> Result<Self, EquationError> {
+        Self::new_with(eq, ParseOptions::default())
+    }
+
+    /// read the statement with the rules set in 'options' eg. for the associativity of ^
+    pub fn new_with(eq: &str, options: ParseOptions) -> Result<Self, EquationError> {
+        let eq_chars = eq.chars().collect::<Vec<char>>();
+        if let Some((var, eq_start)) = find_derivative(&eq_chars) {
+            let value = eq_chars[eq_start..].iter().collect::<String>();
+            let node = OperationNode::new_with(&value, options).map_err(|e| e.shift(eq_start))?;
+            return Ok(Self::Derivative(var, node));
+        }
+
+        let equal_index = find_assignment(&eq_chars);
+
+        let Some(equal_index) = equal_index else {
+            return Ok(Self::Equation(OperationNode::new_with(eq, options)?));
+        };
+
+        let name = eq_chars[..equal_index].iter().collect::<String>();
+        let name_start = name.chars().count() - name.trim_start().chars().count();
+        let name = name.trim();
+        let name_span = Span::new(name_start, name_start + name.chars().count());
+
+        let name_chars = name.chars().collect::<Vec<char>>();
+        let definition = EquationString::remove_whitespaces(name).split_call();
+        if !is_identifier(&name_chars) && definition.is_none() {
+            return Err(EquationError::InvalidAssignment(name_span));
+        }
+
+        let value_start = equal_index + 1;
+        let value = eq_chars[value_start..].iter().collect::<String>();
+
+        // only one name is assigned eg. y = x = 2 is an error and not y = (x = 2)
+        if let Some(next_equal) = find_assignment(&eq_chars[value_start..]) {
+            let left = eq_chars[..value_start + next_equal]
+                .iter()
+                .collect::<String>();
+            let left_end = left.trim_end().chars().count();
+            return Err(EquationError::InvalidAssignment(Span::new(
+                name_start, left_end,
+            )));
+        }
+
+        let Some((function_name, params)) = definition else {
+            if is_constant(name) {
+                return Err(EquationError::InvalidConstantUsage(name_span));
+            }
+            if is_reserved(name) {
+                return Err(EquationError::InvalidAssignment(name_span));
+            }
+
+            let node =
+                OperationNode::new_with(&value, options).map_err(|e| e.shift(value_start))?;
+            return Ok(Self::Assignment(name.to_string(), node));
+        };
+
+        if is_constant(&function_name) {
+            return Err(EquationError::InvalidConstantUsage(name_span));
+        }
+
+        // a function can replace a builtin function but not a keyword
+        if is_keyword(&function_name) {
+            return Err(EquationError::InvalidAssignment(name_span));
+        }
+
+        // the parameters have to be different names eg. f(x, y) but not f(x, x) or f(2)
+        let mut param_names: Vec<String> = Vec::new();
+        for (param, _) in params {
+            let param_name = param.iter().collect::<String>();
+            if !is_identifier(&param)
+                || is_constant(&param_name)
+                || is_reserved(&param_name)
+                || param_names.contains(&param_name)
+            {
+                return Err(EquationError::InvalidAssignment(name_span));
+            }
+            param_names.push(param_name);
+        }
+
+        let node = OperationNode::new_with(&value, options).map_err(|e| e.shift(value_start))?;
+        Ok(Self::Definition(function_name, param_names, node))
+    }
+
+    /// calculate the statement, an assignment also stores the result in 'env'
+    /// a definition stores the function in 'env' and has no result
+    /// a derivative is calculated at the values of the variables in 'env'
+    /// i cannot be assigned in the complex mode as it is the imaginary unit there
+    pub fn run(self, env: &mut Environment) -> Result<Option<Value>, CalculationError> {
+        match self {
+            Self::Equation(node) => node.evaluate_with(env).map(Some),
+            Self::Assignment(name, _) if is_imaginary_unit(&name, env) => {
+                Err(CalculationError::ImaginaryUnitAssignment)
+            }
+            Self::Definition(_, params, _)
+                if params.iter().any(|param| is_imaginary_unit(param, env)) =>
+            {
+                Err(CalculationError::ImaginaryUnitAssignment)
+            }
+            Self::Assignment(name, node) => {
+                let value = node.evaluate_with(env)?;
+                env.set(&name, value.clone());
+                Ok(Some(value))
+            }
+            Self::Definition(name, params, node) => {
+                env.define_function(&name, params, node);
+                Ok(None)
+            }
+            Self::Derivative(var, node) => node
+                .derivative_with(&var, env)?
+                .evaluate_with(env)
+                .map(Some),
+        }
+    }
+}
+
+/// the position of the = of an assignment eg. x = 1
+/// the = of a comparison is skipped eg. x == 1, x <= 1
+fn find_assignment(eq: &[char]) -> Option<usize> {
+    (0..eq.len()).find(|i| {
+        let previous_char = if *i > 0 { eq[i - 1] } else { ' ' };
+        eq[*i] == '='
+            && !matches!(previous_char, '=' | '!' | '<' | '>')
+            && eq.get(i + 1) != Some(&'=')
+    })
+}
+
+/// the variable and the start of the eq of a derivative eg. x and 4 for d/dx (x^2)
+/// the eq has to start with a space or a parenthesis so that d/dx*2 is still a division
+fn find_derivative(eq: &[char]) -> Option<(String, usize)> {
+    let start = eq.iter().take_while(|c| **c == ' ').count();
+    if !eq[start..].starts_with(&['d', '/', 'd']) {
+        return None;
+    }
+
+    let name_start = start + 3;
+    let name_len = eq[name_start..]
+        .iter()
+        .take_while(|c| is_identifier_char(**c))
+        .count();
+    let name = &eq[name_start..name_start + name_len];
+    let eq_start = name_start + name_len;
+    if !is_identifier(name) || !matches!(eq.get(eq_start), Some(' ' | '(')) {
+        return None;
+    }
+
+    Some((name.iter().collect(), eq_start))
+}
+
+/// check if 'name' is i while it is the imaginary unit
+fn is_imaginary_unit(name: &str, env: &Environment) -> bool {
+    name == IMAGINARY_UNIT && matches!(env.mode(), NumberMode::Complex)
+}
+
+/// the names of the math characters that cannot be changed eg. pi, e
+/// i is not one of them as it is a variable outside of the complex mode
+fn is_constant(name: &str) -> bool {
+    match_math_character(name).is_some()
+}
+
+/// the words of the equations eg. true, not, rem
+/// if is one of them as only one of its branches is calculated
+fn is_keyword(name: &str) -> bool {
+    let name_chars = name.chars().collect::<Vec<char>>();
+    name == "if" || match_boolean(name).is_some() || is_word_operator(&name_chars)
+}
+
+/// the names that cannot be variables eg. not, if, sin
+/// a variable named after a builtin function could not be multiplied eg. sin(2) would be a call
+fn is_reserved(name: &str) -> bool {
+    is_keyword(name) || is_builtin_function(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_assignment() {
+        let mut env = Environment::new();
+
+        let statement = Statement::new("x = 3*pi").unwrap();
+        assert!(matches!(statement, Statement::Assignment(ref name, _) if name == "x"));
+        assert_eq!(
+            statement.run(&mut env),
+            Ok(Some(Value::Number(3.0 * std::f64::consts::PI)))
+        );
+
+        let statement = Statement::new("y = x^2 + 1").unwrap();
+        statement.run(&mut env).unwrap();
+        assert_eq!(
+            env.get("y"),
+            Some(Value::Number((3.0 * std::f64::consts::PI).powf(2.0) + 1.0))
+        );
+
+        let statement = Statement::new("x = 2").unwrap();
+        statement.run(&mut env).unwrap();
+        let statement = Statement::new("y - x").unwrap();
+        assert!(matches!(statement, Statement::Equation(_)));
+        assert_eq!(
+            statement.run(&mut env),
+            Ok(Some(Value::Number(
+                (3.0 * std::f64::consts::PI).powf(2.0) - 1.0
+            )))
+        );
+    }
+
+    #[test]
+    pub fn test_comparison_is_not_assignment() {
+        let mut env = Environment::new();
+
+        let statement = Statement::new("x = 2 == 2").unwrap();
+        assert!(matches!(statement, Statement::Assignment(ref name, _) if name == "x"));
+        assert_eq!(statement.run(&mut env), Ok(Some(Value::Boolean(true))));
+
+        for eq in ["x == true", "1 <= 1", "2 >= 1", "1 != 2", "3! == 6"] {
+            let statement = Statement::new(eq).unwrap();
+            assert!(matches!(statement, Statement::Equation(_)), "{}", eq);
+            assert_eq!(
+                statement.run(&mut env),
+                Ok(Some(Value::Boolean(true))),
+                "{}",
+                eq
+            );
+        }
+
+        let statement = Statement::new("tax(x) = if(x <= 100, 0, (x - 100) * 20%)").unwrap();
+        statement.run(&mut env).unwrap();
+        let statement = Statement::new("tax(50) + tax(200)").unwrap();
+        assert_eq!(statement.run(&mut env), Ok(Some(Value::Number(20.0))));
+    }
+
+    #[test]
+    pub fn test_definition() {
+        let mut env = Environment::new();
+
+        let statement = Statement::new("f(x) = x^2 + 1").unwrap();
+        assert!(matches!(statement, Statement::Definition(ref name, _, _) if name == "f"));
+        assert_eq!(statement.run(&mut env), Ok(None));
+
+        let statement = Statement::new("area(w, h) = w*h").unwrap();
+        statement.run(&mut env).unwrap();
+
+        let statement = Statement::new("x = 2").unwrap();
+        statement.run(&mut env).unwrap();
+
+        let statement = Statement::new("f(3) + area(x, f(x))").unwrap();
+        assert_eq!(statement.run(&mut env), Ok(Some(Value::Number(20.0))));
+
+        // a definition replaces the previous one
+        let statement = Statement::new("f(x) = x").unwrap();
+        statement.run(&mut env).unwrap();
+        let statement = Statement::new("f(3)").unwrap();
+        assert_eq!(statement.run(&mut env), Ok(Some(Value::Number(3.0))));
+
+        let statement = Statement::new("area(1)").unwrap();
+        assert_eq!(
+            statement.run(&mut env),
+            Err(CalculationError::ArityMismatch {
+                name: String::from("area"),
+                expected: 2,
+                found: 1
+            })
+        );
+
+        let statement = Statement::new("loop(x) = loop(x + 1)").unwrap();
+        statement.run(&mut env).unwrap();
+        let statement = Statement::new("loop(1)").unwrap();
+        assert_eq!(
+            statement.run(&mut env),
+            Err(CalculationError::RecursionLimit(String::from("loop")))
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_definition() {
+        let error = Statement::new("f(x, x) = x").unwrap_err();
+        assert_eq!(error, EquationError::InvalidAssignment(Span::new(0, 7)));
+
+        let error = Statement::new("f(2) = 2").unwrap_err();
+        assert_eq!(error, EquationError::InvalidAssignment(Span::new(0, 4)));
+
+        let error = Statement::new("f(pi) = 2").unwrap_err();
+        assert_eq!(error, EquationError::InvalidAssignment(Span::new(0, 5)));
+
+        let error = Statement::new("e(x) = 2").unwrap_err();
+        assert_eq!(error, EquationError::InvalidConstantUsage(Span::new(0, 4)));
+
+        let error = Statement::new("f(x) = x+").unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(9, 9)));
+
+        let error = Statement::new("if(x, y, z) = x").unwrap_err();
+        assert_eq!(error, EquationError::InvalidAssignment(Span::new(0, 11)));
+
+        let error = Statement::new("f(x, max) = x").unwrap_err();
+        assert_eq!(error, EquationError::InvalidAssignment(Span::new(0, 9)));
+
+        let error = Statement::new("rem(x) = x").unwrap_err();
+        assert_eq!(error, EquationError::InvalidAssignment(Span::new(0, 6)));
+    }
+
+    #[test]
+    pub fn test_derivative() {
+        let mut env = Environment::new();
+
+        let statement = Statement::new("d/dx (x^2*sin(x))").unwrap();
+        let Statement::Derivative(ref var, ref node) = statement else {
+            panic!("d/dx is not a derivative");
+        };
+        assert_eq!(var, "x");
+        assert_eq!(
+            node.derivative(var).unwrap().to_string(),
+            "2*x*sin(x) + x^2*cos(x)"
+        );
+
+        let statement = Statement::new("d/dx (2x+1)").unwrap();
+        let Statement::Derivative(ref var, ref node) = statement else {
+            panic!("d/dx is not a derivative");
+        };
+        assert_eq!(node.derivative(var).unwrap().to_string(), "2");
+
+        env.set("x", 3.0);
+        let statement = Statement::new(" d/dx x^3 + y").unwrap();
+        env.set("y", 1.0);
+        assert_eq!(statement.run(&mut env), Ok(Some(Value::Number(27.0))));
+
+        // a division by the variable dx is not a derivative
+        let statement = Statement::new("d/dx*2").unwrap();
+        assert!(matches!(statement, Statement::Equation(_)));
+
+        let error = Statement::new("d/dx (x+)").unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(8, 8)));
+    }
+
+    #[test]
+    pub fn test_invalid_assignment() {
+        let error = Statement::new("1343=3").unwrap_err();
+        assert_eq!(error, EquationError::InvalidAssignment(Span::new(0, 4)));
+
+        let error = Statement::new(" pi = 3").unwrap_err();
+        assert_eq!(error, EquationError::InvalidConstantUsage(Span::new(1, 3)));
+
+        let error = Statement::new("true = 1 > 2").unwrap_err();
+        assert_eq!(error, EquationError::InvalidAssignment(Span::new(0, 4)));
+
+        for eq in ["not = 2", "if = 3", "rem = 3", "sin = 3", "in = 1"] {
+            let error = Statement::new(eq).unwrap_err();
+            let name_len = eq.find(' ').unwrap();
+            assert_eq!(
+                error,
+                EquationError::InvalidAssignment(Span::new(0, name_len)),
+                "{}",
+                eq
+            );
+        }
+
+        let error = Statement::new("y = x = 2").unwrap_err();
+        assert_eq!(error, EquationError::InvalidAssignment(Span::new(0, 5)));
+
+        let error = Statement::new("x = (1+2").unwrap_err();
+        assert_eq!(error, EquationError::UnbalancedParenthesis(Span::at(4)));
+
+        let error = Statement::new("x = ").unwrap_err();
+        assert_eq!(error, EquationError::EmptyExpression(Span::new(4, 4)));
+
+        let statement = Statement::new("x = y").unwrap();
+        let mut env = Environment::new();
+        assert_eq!(
+            statement.run(&mut env),
+            Err(CalculationError::UnboundVariable(String::from("y")))
+        );
+        assert_eq!(env.get("x"), None);
+    }
+
+    #[test]
+    pub fn test_imaginary_unit_assignment() {
+        let mut env = Environment::new();
+        Statement::new("i = 3").unwrap().run(&mut env).unwrap();
+        Statement::new("f(i) = i^2").unwrap().run(&mut env).unwrap();
+        let statement = Statement::new("i + f(2)").unwrap();
+        assert_eq!(statement.run(&mut env), Ok(Some(Value::Number(7.0))));
+
+        env.set_mode(NumberMode::Complex);
+        for eq in ["i = 3", "g(x, i) = x*i"] {
+            let statement = Statement::new(eq).unwrap();
+            assert_eq!(
+                statement.run(&mut env),
+                Err(CalculationError::ImaginaryUnitAssignment),
+                "{}",
+                eq
+            );
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use std::fmt;
+
+use eq_split::{
+    operators::{Operators, PostfixOperators, UnaryOperators},
+    units::{find_unit, Dimension, Unit, DIMENSIONLESS},
+};
+
+use crate::{CalculationError, Value};
+
+/// a number with units eg. 3 m, 60 km/h
+/// the value is in its units and not in the SI units eg. 60 for 60 km/h
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    /// each unit with its power eg. [(km, 1), (h, -1)] for km/h
+    pub units: Vec<(&'static Unit, i32)>,
+}
+
+impl Quantity {
+    /// the dimension of all the units together eg. length / time for km/h
+    pub fn dimension(&self) -> Dimension {
+        let mut dimension = DIMENSIONLESS;
+        for (unit, power) in &self.units {
+            for (total, base) in dimension.iter_mut().zip(unit.dimension) {
+                *total += base * power;
+            }
+        }
+        dimension
+    }
+
+    /// the units as they are written eg. m*kg/s^2, 1 for a number
+    pub fn unit_name(&self) -> String {
+        let numerator = self.unit_powers(1);
+        let denominator = self.unit_powers(-1);
+
+        let numerator = if numerator.is_empty() {
+            String::from("1")
+        } else {
+            numerator.join("*")
+        };
+        match denominator.len() {
+            0 => numerator,
+            1 => format!("{}/{}", numerator, denominator[0]),
+            _ => format!("{}/({})", numerator, denominator.join("*")),
+        }
+    }
+
+    /// the units with a power of the same sign as 'sign' eg. [s^2] of m/s^2 for -1
+    fn unit_powers(&self, sign: i32) -> Vec<String> {
+        self.units
+            .iter()
+            .filter(|(_, power)| power * sign > 0)
+            .map(|(unit, power)| match power * sign {
+                1 => unit.name.to_string(),
+                power => format!("{}^{}", unit.name, power),
+            })
+            .collect()
+    }
+
+    /// a number is a quantity without units
+    fn from_value(value: Value) -> Result<Self, CalculationError> {
+        match value {
+            Value::Quantity(quantity) => Ok(*quantity),
+            value => Ok(Quantity {
+                value: value.as_number()?,
+                units: Vec::new(),
+            }),
+        }
+    }
+
+    /// the quantity without a dimension is a number eg. 2 km / 500 m = 4
+    fn into_value(self) -> Value {
+        if self.dimension() == DIMENSIONLESS {
+            Value::Number(self.value * self.factor())
+        } else {
+            Value::Quantity(Box::new(self))
+        }
+    }
+
+    /// the size of the units in the SI units eg. 1000/3600 for km/h
+    fn factor(&self) -> f64 {
+        self.units
+            .iter()
+            .map(|(unit, power)| unit.factor.powi(*power))
+            .product()
+    }
+
+    /// 'operator' is only used for the error eg. m + s
+    fn check_dimension(&self, other: &Self, operator: &str) -> Result<(), CalculationError> {
+        if self.dimension() == other.dimension() {
+            return Ok(());
+        }
+
+        Err(CalculationError::IncompatibleUnits(format!(
+            "{} {} {}",
+            self.unit_name(),
+            operator,
+            other.unit_name()
+        )))
+    }
+
+    /// the value of 'other' in the units of this quantity eg. 20 cm is 0.2 in m
+    fn value_of(&self, other: &Self, operator: &str) -> Result<f64, CalculationError> {
+        self.check_dimension(other, operator)?;
+        Ok(other.value * other.factor() / self.factor())
+    }
+
+    /// multiply with 'other' to the power of 'sign' so that -1 divides
+    /// a unit with the same dimension as a unit of the left is changed to it eg. 3 m * 20 cm = 0.6 m^2
+    fn combine(self, other: Self, sign: i32) -> Self {
+        let mut value = self.value * other.value.powi(sign);
+        let mut units = self.units;
+
+        for (unit, power) in other.units {
+            let power = power * sign;
+            match units
+                .iter_mut()
+                .find(|(u, _)| u.dimension == unit.dimension)
+            {
+                Some((left_unit, left_power)) => {
+                    value *= (unit.factor / left_unit.factor).powi(power);
+                    *left_power += power;
+                }
+                None => units.push((unit, power)),
+            }
+        }
+
+        units.retain(|(_, power)| *power != 0);
+        Self { value, units }
+    }
+
+    /// the powers of the units times 'exponent' have to be whole eg. (4 m^2)^0.5 = 2 m
+    fn power(self, exponent: Self) -> Result<Value, CalculationError> {
+        if exponent.dimension() != DIMENSIONLESS {
+            return Err(CalculationError::ExpectedNumber(exponent.into_value()));
+        }
+
+        let exponent = exponent.value * exponent.factor();
+        let mut units = Vec::new();
+        for (unit, power) in &self.units {
+            let power = *power as f64 * exponent;
+            if power.fract() != 0.0 || power.abs() > i32::MAX as f64 {
+                return Err(CalculationError::UnitPower {
+                    unit: self.unit_name(),
+                    power: exponent,
+                });
+            }
+            units.push((*unit, power as i32));
+        }
+
+        let value = self.value.powf(exponent);
+        Ok(Self { value, units }.into_value())
+    }
+
+    /// the value in the units of 'target' eg. 5 ft to m
+    /// the zero of a temperature is only moved when both sides are a single temperature
+    /// eg. 100 degF to degC = 37.77..., but 1 degF/s to degC/s = 0.55...
+    fn convert(self, target: Self) -> Result<Value, CalculationError> {
+        if target.units.is_empty() {
+            return Err(CalculationError::ExpectedUnit(target.into_value()));
+        }
+
+        self.check_dimension(&target, "to")?;
+        let value = match (&self.units[..], &target.units[..]) {
+            ([(from, 1)], [(to, 1)]) => {
+                (self.value * from.factor + from.offset - to.offset) / to.factor
+            }
+            _ => self.value * self.factor() / target.factor(),
+        };
+        Ok(Value::from(Self {
+            value,
+            units: target.units,
+        }))
+    }
+
+    /// the same units with another value eg. abs(-3 m) = 3 m
+    fn map(self, function: impl Fn(f64) -> f64) -> Value {
+        Self {
+            value: function(self.value),
+            units: self.units,
+        }
+        .into_value()
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit_name())
+    }
+}
+
+/// the value of a single unit eg. km is 1 km
+/// used for a name that is not a variable
+pub fn unit_value(name: &str) -> Option<Value> {
+    let unit = find_unit(name)?;
+    Some(Value::from(Quantity {
+        value: 1.0,
+        units: vec![(unit, 1)],
+    }))
+}
+
+/// calculate the result of the operation when a side has units
+/// the right is changed to the units of the left for the other operators eg. 3 m + 20 cm = 3.2 m
+/// the values are calculated with f64 in every mode
+pub fn calculate_quantity(
+    operator: Operators,
+    left: Value,
+    right: Value,
+) -> Result<Value, CalculationError> {
+    let left = Quantity::from_value(left)?;
+    let right = Quantity::from_value(right)?;
+
+    match operator {
+        Operators::Mult => Ok(left.combine(right, 1).into_value()),
+        Operators::Div => Ok(left.combine(right, -1).into_value()),
+        Operators::Exp => left.power(right),
+        Operators::Convert => left.convert(right),
+        _ if operator.is_bitwise() || operator.is_logical() => {
+            let value = if left.units.is_empty() { right } else { left };
+            Err(CalculationError::ExpectedNumber(value.into_value()))
+        }
+        _ => {
+            let right_value = left.value_of(&right, operator.symbol())?;
+            if let Some(result) = operator.compare(&left.value, &right_value) {
+                return Ok(Value::Boolean(result));
+            }
+
+            let value = operator
+                .calculate(left.value, right_value)
+                .unwrap_or(f64::NAN);
+            match operator {
+                // the number of times the right fits in the left has no unit
+                Operators::IntDiv => Ok(Value::Number(value)),
+                _ => Ok(left.map(|_| value)),
+            }
+        }
+    }
+}
+
+/// calculate the result of the sign in front of a value with units eg. -3 m
+pub fn calculate_quantity_unary(
+    operator: UnaryOperators,
+    quantity: Quantity,
+) -> Result<Value, CalculationError> {
+    match operator {
+        UnaryOperators::Negate | UnaryOperators::Plus => {
+            Ok(quantity.map(|value| operator.calculate(value)))
+        }
+        _ => Err(CalculationError::ExpectedNumber(Value::from(quantity))),
+    }
+}
+
+/// calculate the result of the postfix operator after a value with units eg. 50% m
+/// a value with units has no factorial
+pub fn calculate_quantity_postfix(
+    operator: PostfixOperators,
+    quantity: Quantity,
+) -> Result<Value, CalculationError> {
+    match operator {
+        PostfixOperators::Percent => Ok(quantity.map(|value| value / 100.0)),
+        PostfixOperators::Factorial => Err(CalculationError::ExpectedNumber(Value::from(quantity))),
+    }
+}
+
+/// the builtin functions that keep the units eg. sqrt(16 m^2) = 4 m, max(1 m, 20 cm) = 1 m
+/// the other functions only take numbers
+pub fn call_quantity_function(name: &str, args: &[Value]) -> Result<Value, CalculationError> {
+    let mut args = args
+        .iter()
+        .cloned()
+        .map(Quantity::from_value)
+        .collect::<Result<Vec<Quantity>, CalculationError>>()?;
+    let number = |value: f64| Quantity {
+        value,
+        units: Vec::new(),
+    };
+
+    match (name, args.len()) {
+        ("abs", 1) => Ok(args.remove(0).map(f64::abs)),
+        ("floor", 1) => Ok(args.remove(0).map(f64::floor)),
+        ("ceil", 1) => Ok(args.remove(0).map(f64::ceil)),
+        ("round", 1) => Ok(args.remove(0).map(f64::round)),
+        ("sqrt", 1) => args.remove(0).power(number(0.5)),
+        ("cbrt", 1) => args.remove(0).power(number(1.0 / 3.0)),
+        ("min" | "max", 2) => {
+            let right = args.remove(1);
+            let left = args.remove(0);
+            let right_value = left.value_of(&right, name)?;
+            let is_right = if name == "min" {
+                right_value < left.value
+            } else {
+                right_value > left.value
+            };
+            Ok(left.map(|value| if is_right { right_value } else { value }))
+        }
+        _ => {
+            let quantity = args.into_iter().find(|arg| !arg.units.is_empty());
+            Err(CalculationError::ExpectedNumber(
+                quantity.map_or(Value::Number(f64::NAN), Quantity::into_value),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Environment, OperationNode};
+
+    fn evaluate(eq: &str) -> Result<String, CalculationError> {
+        let operation_node = OperationNode::new(eq).unwrap();
+        operation_node
+            .evaluate_with(&Environment::new())
+            .map(|value| value.to_string())
+    }
+
+    #[test]
+    pub fn test_units() {
+        let cases = [
+            ("3 m + 20 cm", "3.2 m"),
+            ("60 km/h * 2.5 h", "150 km"),
+            ("9.81 m/s^2 * 70 kg", "686.7 m*kg/s^2"),
+            ("3 m * 20 cm", "0.6 m^2"),
+            ("1 km / (1 m)", "1000.0"),
+            ("1 km / 1 s", "1 km/s"),
+            ("2 km / 500 m", "4.0"),
+            ("100 km / 2 h", "50 km/h"),
+            ("1/2 s", "0.5 1/s"),
+            ("3 m^2 * 2", "6 m^2"),
+            ("2 kg m", "2 kg*m"),
+            ("1 N m", "1 N*m"),
+            ("sqrt(16 m^2) - (50 cm)%", "3.995 m"),
+            ("-2 kN / (4 m^2) + max(1 N, 20 N)/m^2", "-0.48 kN/m^2"),
+            ("1 m + 20 cm > 110 cm", "true"),
+            ("1 / (2 s)", "0.5 1/s"),
+            ("5 ft to m", "1.524 m"),
+            ("5 ft in inch", "60 inch"),
+            ("60 km/h to m/s", "16.666666666666668 m/s"),
+            ("100 degF to degC", "37.77777777777777 degC"),
+            ("212 degF to degC", "100 degC"),
+            ("0 degC in K", "273.15 K"),
+            ("2 kWh to J", "7200000 J"),
+        ];
+
+        for (eq, value) in cases {
+            assert_eq!(evaluate(eq), Ok(String::from(value)), "{}", eq);
+        }
+    }
+
+    #[test]
+    pub fn test_incompatible_units() {
+        let incompatible =
+            |units: &str| Err(CalculationError::IncompatibleUnits(String::from(units)));
+
+        assert_eq!(evaluate("3 m + 2 s"), incompatible("m + s"));
+        assert_eq!(evaluate("3 m >= 2"), incompatible("m >= 1"));
+        assert_eq!(evaluate("5 ft to kg"), incompatible("ft to kg"));
+        assert_eq!(evaluate("60 km/h to m"), incompatible("km/h to m"));
+        assert_eq!(evaluate("max(1 m, 1 s)"), incompatible("m max s"));
+        assert_eq!(
+            evaluate("5 to 3"),
+            Err(CalculationError::ExpectedUnit(Value::Number(3.0)))
+        );
+        assert_eq!(
+            evaluate("sqrt(2 m)"),
+            Err(CalculationError::UnitPower {
+                unit: String::from("m"),
+                power: 0.5
+            })
+        );
+        assert_eq!(
+            evaluate("sin(2 m)").unwrap_err().to_string(),
+            "Expected a number but got 2 m"
+        );
+    }
+
+    #[test]
+    pub fn test_variables_before_units() {
+        let mut env = Environment::new();
+        env.set("m", 5.0);
+
+        let operation_node = OperationNode::new("2m * 3 g").unwrap();
+        let value = operation_node.evaluate_with(&env).unwrap();
+        assert_eq!(value.to_string(), "30 g");
+    }
+}
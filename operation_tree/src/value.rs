@@ -0,0 +1,171 @@
+use std::fmt;
+
+use bigdecimal::{num_bigint::BigInt, BigDecimal};
+use num_complex::Complex64;
+use num_rational::BigRational;
+
+use crate::{
+    complex::{as_real, format_complex, ComplexFormat},
+    decimal::{float_to_decimal, to_float},
+    integer,
+    interval::Interval,
+    rational::{format_rational, rational_to_float, RationalFormat},
+    units::Quantity,
+    CalculationError,
+};
+
+/// the result of calculating an operation tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    /// a number of the decimal mode eg. 0.3
+    /// boxed to keep the values small, they are kept on the stack for each nested call
+    Decimal(Box<BigDecimal>),
+    /// a whole number of the integer mode eg. 2^100
+    Integer(Box<BigInt>),
+    /// a number of the rational mode eg. 1/3
+    Rational(Box<BigRational>),
+    /// a number of the complex mode eg. 1+2i
+    Complex(Complex64),
+    /// a range of numbers of the interval mode eg. [1.2, 1.3]
+    Interval(Interval),
+    /// a number with units eg. 3 m, 60 km/h
+    Quantity(Box<Quantity>),
+    /// the result of a comparison eg. 1 < 2 or of a logical operator eg. x > 1 && x < 5
+    Boolean(bool),
+}
+
+/// how the numbers of the rational and complex modes are shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValueFormat {
+    pub rational: RationalFormat,
+    pub complex: ComplexFormat,
+}
+
+impl Value {
+    /// a decimal or a fraction is changed to the closest f64
+    /// a complex number must have no imaginary part and an interval must hold a single number
+    pub fn as_number(&self) -> Result<f64, CalculationError> {
+        match self {
+            Self::Number(value) => Ok(*value),
+            Self::Decimal(value) => Ok(to_float(value)),
+            Self::Integer(value) => Ok(integer::to_float(value)),
+            Self::Rational(value) => Ok(rational_to_float(value)),
+            Self::Complex(value) => as_real(*value),
+            Self::Interval(value) if value.is_point() => Ok(value.lo),
+            _ => Err(CalculationError::ExpectedNumber(self.clone())),
+        }
+    }
+
+    /// an f64 is changed to the decimal with its shortest digits eg. 0.1
+    pub fn as_decimal(self) -> Result<BigDecimal, CalculationError> {
+        match self {
+            Self::Number(value) => float_to_decimal(value),
+            Self::Decimal(value) => Ok(*value),
+            Self::Integer(value) => Ok(BigDecimal::from(*value)),
+            Self::Rational(value) => float_to_decimal(rational_to_float(&value)),
+            Self::Complex(value) => float_to_decimal(as_real(value)?),
+            _ => Err(CalculationError::ExpectedNumber(self)),
+        }
+    }
+
+    /// a real number is the complex number without an imaginary part
+    pub fn as_complex(&self) -> Result<Complex64, CalculationError> {
+        match self {
+            Self::Complex(value) => Ok(*value),
+            _ => self.as_number().map(Complex64::from),
+        }
+    }
+
+    /// a number is the interval holding only it
+    pub fn as_interval(&self) -> Result<Interval, CalculationError> {
+        match self {
+            Self::Interval(value) => Ok(*value),
+            _ => self.as_number().map(Interval::point),
+        }
+    }
+
+    /// the value shown with 'format' eg. 3 1/2 for 'RationalFormat::Mixed'
+    pub fn to_string_with(&self, format: ValueFormat) -> String {
+        match self {
+            Self::Rational(value) => format_rational(value, format.rational),
+            Self::Complex(value) => format_complex(*value, format.complex),
+            _ => self.to_string(),
+        }
+    }
+
+    pub fn as_boolean(&self) -> Result<bool, CalculationError> {
+        match self {
+            Self::Boolean(value) => Ok(*value),
+            _ => Err(CalculationError::ExpectedBoolean(self.clone())),
+        }
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<BigDecimal> for Value {
+    fn from(value: BigDecimal) -> Self {
+        Self::Decimal(Box::new(value))
+    }
+}
+
+impl From<BigInt> for Value {
+    fn from(value: BigInt) -> Self {
+        Self::Integer(Box::new(value))
+    }
+}
+
+impl From<BigRational> for Value {
+    fn from(value: BigRational) -> Self {
+        Self::Rational(Box::new(value))
+    }
+}
+
+impl From<Complex64> for Value {
+    fn from(value: Complex64) -> Self {
+        Self::Complex(value)
+    }
+}
+
+impl From<Interval> for Value {
+    fn from(value: Interval) -> Self {
+        Self::Interval(value)
+    }
+}
+
+impl From<Quantity> for Value {
+    fn from(value: Quantity) -> Self {
+        Self::Quantity(Box::new(value))
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Boolean(value)
+    }
+}
+
+impl fmt::Display for Value {
+    /// the numbers are shown as in the earlier versions eg. 3.0
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(value) => write!(f, "{:?}", value),
+            Self::Decimal(value) => write!(f, "{}", value),
+            Self::Integer(value) => write!(f, "{}", value),
+            Self::Rational(value) => {
+                write!(f, "{}", format_rational(value, RationalFormat::Fraction))
+            }
+            Self::Complex(value) => {
+                write!(f, "{}", format_complex(*value, ComplexFormat::Rectangular))
+            }
+            Self::Interval(value) => write!(f, "{}", value),
+            Self::Quantity(value) => write!(f, "{}", value),
+            Self::Boolean(value) => write!(f, "{}", value),
+        }
+    }
+}
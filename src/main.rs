@@ -1,36 +1,281 @@
 use std::io;
 use std::io::prelude::*;
 
-use operation_tree::OperationNode;
+use operation_tree::{
+    ComplexFormat, Environment, NumberMode, OperationNode, ParseOptions, RationalFormat,
+    RoundingMode, Statement, Value, ValueFormat, DEFAULT_DECIMAL_PRECISION,
+};
 
 fn main() {
-    let mut line = String::new();
-    println!("Enter Equation :");
-    let _ = std::io::stdin().read_line(&mut line).unwrap();
+    let mut env = Environment::new();
+    let mut is_successful = true;
 
-    line.pop();
-    let eq = line;
+    let (flags, paths): (Vec<String>, Vec<String>) = std::env::args()
+        .skip(1)
+        .partition(|arg| arg.starts_with("--"));
 
-    let node_res = OperationNode::new(&eq);
-    if node_res.is_err() {
-        return_error_code();
-        return;
+    let mut options = ParseOptions::default();
+    let mut precision = None;
+    let mut rounding = RoundingMode::HalfEven;
+    let mut mode = None;
+    let mut format = ValueFormat::default();
+    let mut markup = None;
+    for flag in flags {
+        let (name, value) = match flag.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (flag.as_str(), None),
+        };
+
+        match (name, value) {
+            // 2^3^2 = (2^3)^2 and -3^2 = (-3)^2 as in the earlier versions
+            ("--legacy-exp", None) => options = ParseOptions::legacy(),
+            // calculate with exact decimals eg. 0.1 + 0.2 = 0.3
+            ("--decimal", None) => precision = Some(DEFAULT_DECIMAL_PRECISION),
+            ("--decimal", Some(value)) => match value.parse::<u64>() {
+                Ok(value) if value > 0 => precision = Some(value),
+                _ => println!("Invalid precision {}", value),
+            },
+            ("--rounding", Some(value)) => match parse_rounding(value) {
+                Some(value) => rounding = value,
+                None => println!("Unknown rounding {}", value),
+            },
+            // calculate with exact whole numbers eg. 2^100, 30!
+            ("--integer", None) => mode = Some(NumberMode::Integer),
+            // calculate with exact fractions eg. 1/3 + 1/6 = 1/2
+            ("--rational", None) => mode = Some(NumberMode::Rational),
+            ("--rational", Some(value)) => match parse_rational_format(value) {
+                Some(value) => {
+                    mode = Some(NumberMode::Rational);
+                    format.rational = value;
+                }
+                None => println!("Unknown format {}", value),
+            },
+            // calculate with complex numbers eg. sqrt(-4) = 2i
+            ("--complex", None) => mode = Some(NumberMode::Complex),
+            ("--complex", Some(value)) => match parse_complex_format(value) {
+                Some(value) => {
+                    mode = Some(NumberMode::Complex);
+                    format.complex = value;
+                }
+                None => println!("Unknown format {}", value),
+            },
+            // calculate with intervals that hold the exact result eg. [1.2, 1.3], 1.25 ± 0.05
+            ("--interval", None) => mode = Some(NumberMode::Interval),
+            // write each equation as LaTeX too eg. \frac{1}{2} for 1/2
+            ("--latex", None) => markup = Some(Markup::Latex),
+            // write each equation as Presentation MathML too eg. <mfrac> for 1/2
+            ("--mathml", None) => markup = Some(Markup::MathMl),
+            _ => println!("Unknown option {}", flag),
+        }
     }
 
-    let node = node_res.unwrap();
-    let calculation_res = node.calculate();
-    println!("Answer:");
-    println! {"{:?}", calculation_res};
-    println!();
-    println!("Exit with 0");
+    if let Some(mode) = precision.and_then(|precision| NumberMode::decimal(precision, rounding)) {
+        env.set_mode(mode);
+    }
+    if let Some(mode) = mode {
+        env.set_mode(mode);
+    }
+
+    // files given as arguments are loaded before the session starts
+    // eg. to define the functions used in the session
+    for path in paths {
+        load_file(&path, &mut env, options);
+    }
+
+    // keep reading equations until an empty line or the end of the input
+    // the variables assigned are kept for the rest of the session
+    loop {
+        let mut line = String::new();
+        println!("Enter Equation :");
+        let read_len = std::io::stdin().read_line(&mut line).unwrap();
+
+        let eq = line.trim_end_matches(['\n', '\r']);
+        if read_len == 0 || eq.trim().is_empty() {
+            break;
+        }
+
+        is_successful = run_equation(eq, &mut env, options, format, markup);
+        println!();
+    }
+
+    if is_successful {
+        println!("Exit with 0");
+    } else {
+        println!("Exit with 1");
+    }
     println!();
     pause();
 }
 
-fn return_error_code() {
-    println!("Exit with 1");
-    println!();
-    pause();
+/// the notation the equations are also written in eg. to paste them into a report
+#[derive(Debug, Clone, Copy)]
+enum Markup {
+    Latex,
+    MathMl,
+}
+
+impl Markup {
+    fn statement(&self, statement: &Statement) -> String {
+        match self {
+            Markup::Latex => statement.to_latex(),
+            Markup::MathMl => statement.to_mathml(),
+        }
+    }
+
+    /// the answer of a derivative eg. 2x for d/dx (x^2)
+    fn node(&self, node: &OperationNode) -> String {
+        match self {
+            Markup::Latex => node.to_latex(),
+            Markup::MathMl => node.to_mathml(),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Markup::Latex => "LaTeX",
+            Markup::MathMl => "MathML",
+        }
+    }
+}
+
+/// calculate a single line and store the result as 'ans'
+fn run_equation(
+    eq: &str,
+    env: &mut Environment,
+    options: ParseOptions,
+    format: ValueFormat,
+    markup: Option<Markup>,
+) -> bool {
+    let statement = match Statement::new_with(eq, options) {
+        Ok(statement) => statement,
+        Err(e) => {
+            println!("{}", e.report(eq));
+            return false;
+        }
+    };
+
+    if let Some(markup) = markup {
+        println!("{}:", markup.name());
+        println!("{}", markup.statement(&statement));
+    }
+
+    // the derivative is shown as an equation eg. d/dx (x^2) = 2*x
+    if let Statement::Derivative(var, node) = &statement {
+        let derivative = match node.derivative_with(var, env) {
+            Ok(derivative) => derivative,
+            Err(e) => {
+                println!("{}", e);
+                return false;
+            }
+        };
+        println!("Answer:");
+        println!("{}", derivative);
+        if let Some(markup) = markup {
+            println!("{}:", markup.name());
+            println!("{}", markup.node(&derivative));
+        }
+        return true;
+    }
+
+    let name = match &statement {
+        Statement::Assignment(name, _) | Statement::Definition(name, _, _) => Some(name.clone()),
+        Statement::Equation(_) | Statement::Derivative(_, _) => None,
+    };
+
+    let calculation_res = match statement.run(env) {
+        Ok(calculation_res) => calculation_res,
+        Err(e) => {
+            println!("{}", e);
+            return false;
+        }
+    };
+
+    // an operation without an exact fraction or decimal eg. 2^0.5, sin(1) gives an f64
+    if let (
+        NumberMode::Rational | NumberMode::Integer | NumberMode::Decimal(_),
+        Some(Value::Number(_)),
+    ) = (env.mode(), &calculation_res)
+    {
+        println!("Warning: the result is not exact, it is calculated with floats");
+    }
+
+    match (name, &calculation_res) {
+        (Some(name), Some(calculation_res)) => {
+            println! {"{} = {}", name, calculation_res.to_string_with(format)}
+        }
+        (Some(name), None) => println!("Function '{}' defined", name),
+        (None, Some(calculation_res)) => {
+            println!("Answer:");
+            println! {"{}", calculation_res.to_string_with(format)};
+        }
+        (None, None) => {}
+    }
+
+    if let Some(calculation_res) = calculation_res {
+        env.set("ans", calculation_res);
+    }
+    true
+}
+
+/// the rounding of the decimal mode eg. half-even, down
+fn parse_rounding(name: &str) -> Option<RoundingMode> {
+    match name {
+        "up" => Some(RoundingMode::Up),
+        "down" => Some(RoundingMode::Down),
+        "ceiling" => Some(RoundingMode::Ceiling),
+        "floor" => Some(RoundingMode::Floor),
+        "half-up" => Some(RoundingMode::HalfUp),
+        "half-down" => Some(RoundingMode::HalfDown),
+        "half-even" => Some(RoundingMode::HalfEven),
+        _ => None,
+    }
+}
+
+/// how the fractions of the rational mode are shown eg. mixed
+fn parse_rational_format(name: &str) -> Option<RationalFormat> {
+    match name {
+        "fraction" => Some(RationalFormat::Fraction),
+        "mixed" => Some(RationalFormat::Mixed),
+        "decimal" => Some(RationalFormat::Decimal),
+        _ => None,
+    }
+}
+
+/// how the numbers of the complex mode are shown eg. polar
+fn parse_complex_format(name: &str) -> Option<ComplexFormat> {
+    match name {
+        "rectangular" => Some(ComplexFormat::Rectangular),
+        "polar" => Some(ComplexFormat::Polar),
+        _ => None,
+    }
+}
+
+/// run every line of the file at 'path', only the errors are shown
+/// empty lines and lines starting with '#' are skipped
+fn load_file(path: &str, env: &mut Environment, options: ParseOptions) {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            println!("Cannot read {}: {}", path, e);
+            return;
+        }
+    };
+
+    for (i, eq) in content.lines().enumerate() {
+        if eq.trim().is_empty() || eq.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let error = match Statement::new_with(eq, options) {
+            Ok(statement) => statement.run(env).err().map(|e| e.to_string()),
+            Err(e) => Some(e.report(eq)),
+        };
+        if let Some(error) = error {
+            println!("{}:{}", path, i + 1);
+            println!("{}", error);
+            println!();
+        }
+    }
 }
 
 fn pause() {